@@ -7,8 +7,8 @@
 
 use hex_literal::hex;
 use green_aureus_runtime::{
-    AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-    SystemConfig, WASM_BINARY, SupplyChainConfig
+    AccountId, AuraConfig, BalancesConfig, CouncilConfig, CouncilMembershipConfig, GenesisConfig,
+    GrandpaConfig, Role, Signature, SystemConfig, WASM_BINARY, SupplyChainConfig
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -55,27 +55,30 @@ pub fn testnet_authorities() -> Vec<(AuraId, GrandpaId)> {
     )]
 }
 
-pub fn testnet_superuser() -> AccountId {
-    hex!["c4d162e241ccc84bc8af3353c655e9ce88d885f5b2b49edd31a1c40614c28e37"].into()
-}
-
-pub fn testnet_auditors() -> Vec<(AccountId, String)> {
+pub fn testnet_auditors() -> Vec<(AccountId, String, Vec<Role>)> {
     vec![
         // Finaca Gustavo
-        (hex!["22556c72508eb016a83dc2c756c9b1f1a66a33762231d35f56f09953f55bae1a"].into(), String::from("Finaca Gustavo")),
+        (hex!["22556c72508eb016a83dc2c756c9b1f1a66a33762231d35f56f09953f55bae1a"].into(), String::from("Finaca Gustavo"), vec![Role::Producer]),
         // Export Trust Limited
-        (hex!["42d1d2386acf5cbbf6c349abd8dcc01bbab47c556743b2e91334f6a1fcf63712"].into(), String::from("Export Trust Limited")),
+        (hex!["42d1d2386acf5cbbf6c349abd8dcc01bbab47c556743b2e91334f6a1fcf63712"].into(), String::from("Export Trust Limited"), vec![Role::Certifier, Role::Registrar]),
         // Safe-Transport Limited
-        (hex!["fafaf10054ae89ac0820e54baec4edf9070bfa5bd3baad13b978eeab9d872332"].into(), String::from("Safe-Transport Limited")),
+        (hex!["fafaf10054ae89ac0820e54baec4edf9070bfa5bd3baad13b978eeab9d872332"].into(), String::from("Safe-Transport Limited"), vec![Role::Transporter]),
         // Bonaza Coffee INC.
-        (hex!["dcf1afa256e61b892a78875ffc52a87f5231f031f79b106fce04baa00b271039"].into(), String::from("Bonaza Coffee INC.")), 
+        (hex!["dcf1afa256e61b892a78875ffc52a87f5231f031f79b106fce04baa00b271039"].into(), String::from("Bonaza Coffee INC."), vec![Role::Producer, Role::Assembler]), 
         // MS Phönix
-        (hex!["fe6888dd220d526145cf96c33240d9e59f37ece49c135cf811d91b767544f571"].into(), String::from("Best-Shipping Limited")),
+        (hex!["fe6888dd220d526145cf96c33240d9e59f37ece49c135cf811d91b767544f571"].into(), String::from("Best-Shipping Limited"), vec![Role::Transporter]),
         // Spedition Pfaff
-        (hex!["ba31465ce6b01b72f502cf3ab424b00fc04252fac191c2038172d06cb7c2b754"].into(), String::from("Spedition Pfaff")),   
+        (hex!["ba31465ce6b01b72f502cf3ab424b00fc04252fac191c2038172d06cb7c2b754"].into(), String::from("Spedition Pfaff"), vec![Role::Transporter]),   
     ]
 }
 
+/// The consortium members that form the council of the live network. The council votes on
+/// proposals to authorize organizations, grant roles, upgrade the runtime and change its own
+/// membership.
+pub fn testnet_council() -> Vec<AccountId> {
+    testnet_auditors().into_iter().map(|e| e.0).collect()
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
     let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -90,8 +93,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
                 wasm_binary,
                 // Initial PoA authorities
                 vec![authority_keys_from_seed("Alice")],
-                // Sudo account
-                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                // Council members
+                vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
                 // Pre-funded accounts
                 vec![
                     get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -132,8 +135,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     authority_keys_from_seed("Alice"),
                     authority_keys_from_seed("Bob"),
                 ],
-                // Sudo account
-                get_account_id_from_seed::<sr25519::Public>("Alice"),
+                // Council members
+                vec![
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    get_account_id_from_seed::<sr25519::Public>("Bob"),
+                    get_account_id_from_seed::<sr25519::Public>("Charlie"),
+                ],
                 // Pre-funded accounts
                 vec![
                     get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -167,8 +174,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 
 pub fn testnet_config() -> Result<ChainSpec, String> {
     let wasm_binary = WASM_BINARY.ok_or_else(|| "Testnet wasm not available".to_string())?;
-    let mut endowed = vec![testnet_superuser()];
-    endowed.append(&mut testnet_auditors().into_iter().map(|e| e.0).collect());
+    let endowed: Vec<AccountId> = testnet_auditors().into_iter().map(|e| e.0).collect();
 
     Ok(ChainSpec::from_genesis(
         // Name
@@ -181,8 +187,8 @@ pub fn testnet_config() -> Result<ChainSpec, String> {
                 wasm_binary,
                 // Initial PoA authorities
                 testnet_authorities(),
-                // Sudo account
-                testnet_superuser(),
+                // Council members
+                testnet_council(),
                 // Pre-funded accounts
                 endowed.clone(),
                 false,
@@ -205,7 +211,7 @@ pub fn testnet_config() -> Result<ChainSpec, String> {
 fn testnet_genesis(
     wasm_binary: &[u8],
     initial_authorities: Vec<(AuraId, GrandpaId)>,
-    root_key: AccountId,
+    council_members: Vec<AccountId>,
     endowed_accounts: Vec<AccountId>,
     _enable_println: bool,
 ) -> GenesisConfig {
@@ -231,9 +237,17 @@ fn testnet_genesis(
                 .map(|x| (x.1.clone(), 1))
                 .collect(),
         },
-        sudo: SudoConfig {
-            // Assign network admin rights.
-            key: root_key,
+        council: CouncilConfig {
+            // Initialized by `council_membership`.
+            members: vec![],
+            phantom: Default::default(),
+        },
+        council_membership: CouncilMembershipConfig {
+            // Members of the council, which owns the supply chain admin rights. Authorizing an
+            // organization is a council proposal of `SupplyChain::authorize` that needs the
+            // approval of more than half of the members before it expires.
+            members: council_members,
+            phantom: Default::default(),
         },
         supply_chain: SupplyChainConfig {
             initial_authorities: testnet_auditors(),
@@ -5,7 +5,8 @@
 // the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
 // merge, publish, distribute, sublicense, and/or sell copies of the Software.
 
-use sc_cli::RunCmd;
+use sc_cli::{DatabaseParams, PruningParams, RunCmd, SharedParams};
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -45,4 +46,88 @@ pub enum Subcommand {
     /// The custom benchmark subcommand benchmarking runtime pallets.
     #[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+    /// Conceal audit claims and check their disclosures.
+    Disclosure(DisclosureCmd),
+
+    /// Import GS1 EPCIS events as supply chain extrinsics and export the history of components
+    /// as EPCIS.
+    Epcis(EpcisCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub enum DisclosureCmd {
+    /// Conceal claims of an audit before its JWT is signed. Prints the claims to sign and the
+    /// disclosures to keep as JSON.
+    Conceal {
+        /// The claims of the audit as JSON object.
+        claims: String,
+
+        /// Name of a claim to conceal.
+        #[structopt(long = "name", required = true)]
+        names: Vec<String>,
+    },
+
+    /// Check the disclosures of a presentation `<jwt>~<disclosure>~...~` against the digests
+    /// in the JWT. Prints the disclosed claims as JSON. Neither the signature of the JWT nor
+    /// whether it was added to the chain is checked, the `supplyChain_verifyPresentation` RPC
+    /// checks the presentation against the audit and rejects revoked or superseded audits.
+    Verify {
+        /// The presentation.
+        presentation: String,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+pub enum EpcisCmd {
+    /// Map the events of an EPCIS 2.0 document to supply chain extrinsics, each with the event
+    /// signed as audit JWT. Prints the extrinsics and their SCALE encoded calls as JSON, in the
+    /// order they have to be submitted.
+    Import(EpcisImportCmd),
+
+    /// Export the history of a component and of its parts from the local database as EPCIS 2.0
+    /// document.
+    Export(EpcisExportCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct EpcisImportCmd {
+    /// Path of the EPCIS document in JSON-LD.
+    #[structopt(parse(from_os_str))]
+    pub path: PathBuf,
+
+    /// Secret URI of the auditor's key that signs the audits, e.g. a mnemonic.
+    #[structopt(long)]
+    pub suri: String,
+
+    /// Signature scheme of the key.
+    #[structopt(long, default_value = "ed25519", possible_values = &["ed25519", "sr25519"])]
+    pub scheme: String,
+
+    /// Id of the key as registered for the auditor, or the DID URL of the key.
+    #[structopt(long)]
+    pub kid: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct EpcisExportCmd {
+    /// Id of the component.
+    #[structopt(long)]
+    pub id: String,
+
+    /// Number of assembly steps to follow towards the parts of the component.
+    #[structopt(long, default_value = "16")]
+    pub max_depth: u32,
+
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub database_params: DatabaseParams,
+
+    #[allow(missing_docs)]
+    #[structopt(flatten)]
+    pub pruning_params: PruningParams,
 }
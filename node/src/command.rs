@@ -7,12 +7,30 @@
 
 use crate::{
     chain_spec,
-    cli::{Cli, Subcommand},
-    service,
+    cli::{Cli, DisclosureCmd, EpcisCmd, EpcisExportCmd, EpcisImportCmd, Subcommand},
+    service::{self, FullClient},
+};
+use green_aureus_runtime::{Block, Call, Runtime};
+use pallet_supply_chain::{
+    jwt::Jwt,
+    types::{Direction, MaxAudits},
+};
+use pallet_supply_chain_epcis::{self as epcis, AuditRecord, History};
+use pallet_supply_chain_rpc::{disclosures, ComponentId, SupplyChainRuntimeApi};
+use sc_cli::{
+    ChainSpec, CliConfiguration, DatabaseParams, PruningParams, RuntimeVersion, SharedParams,
+    SubstrateCli,
 };
-use green_aureus_runtime::Block;
-use sc_cli::{ChainSpec, RuntimeVersion, SubstrateCli};
 use sc_service::PartialComponents;
+use serde_json::{json, Map, Value};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{ed25519, hexdisplay::HexDisplay, sr25519, Encode, Pair};
+use sp_runtime::generic::BlockId;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Upper bound of the number of components an EPCIS export contains.
+const MAX_EXPORTED_COMPONENTS: u32 = 1000;
 
 impl SubstrateCli for Cli {
     fn impl_name() -> String {
@@ -143,6 +161,15 @@ pub fn run() -> sc_cli::Result<()> {
                 )
             }
         }
+        Some(Subcommand::Disclosure(cmd)) => cmd.run(),
+        Some(Subcommand::Epcis(EpcisCmd::Import(cmd))) => cmd.run(),
+        Some(Subcommand::Epcis(EpcisCmd::Export(cmd))) => {
+            let runner = cli.create_runner(cmd)?;
+            runner.sync_run(|config| {
+                let PartialComponents { client, .. } = service::new_partial(&config)?;
+                cmd.run(&client)
+            })
+        }
         None => {
             let runner = cli.create_runner(&cli.run)?;
             runner.run_node_until_exit(|config| async move {
@@ -151,3 +178,162 @@ pub fn run() -> sc_cli::Result<()> {
         }
     }
 }
+
+impl DisclosureCmd {
+    /// Run the disclosure command and print its result.
+    fn run(&self) -> sc_cli::Result<()> {
+        let output = match self {
+            DisclosureCmd::Conceal { claims, names } => {
+                let claims = serde_json::from_str(claims)
+                    .map_err(|error| format!("The claims are no JSON object: {}", error))?;
+                serde_json::to_value(disclosures::conceal(claims, names)?)
+            }
+            DisclosureCmd::Verify { presentation } => {
+                let (_, disclosed) = disclosures::verify(presentation)?;
+                serde_json::to_value(disclosed)
+            }
+        };
+        let output = output.and_then(|output| serde_json::to_string_pretty(&output));
+        println!("{}", output.map_err(|error| error.to_string())?);
+        Ok(())
+    }
+}
+
+impl EpcisImportCmd {
+    /// Sign the events of the document as audits and print the extrinsics that record them.
+    fn run(&self) -> sc_cli::Result<()> {
+        let document = std::fs::read_to_string(&self.path)?;
+        let imports = epcis::import(&document).map_err(|error| error.to_string())?;
+        let invalid_suri = |_| format!("The secret URI is no {} key.", self.scheme);
+        let (alg, sign): (&str, Box<dyn Fn(&[u8]) -> Vec<u8>>) = match self.scheme.as_str() {
+            "sr25519" => {
+                let pair = sr25519::Pair::from_string(&self.suri, None).map_err(invalid_suri)?;
+                ("Sr25519", Box::new(move |message: &[u8]| pair.sign(message).0.to_vec()))
+            }
+            _ => {
+                let pair = ed25519::Pair::from_string(&self.suri, None).map_err(invalid_suri)?;
+                ("EdDSA", Box::new(move |message: &[u8]| pair.sign(message).0.to_vec()))
+            }
+        };
+
+        let mut extrinsics = Vec::with_capacity(imports.len());
+        for import in imports {
+            let audit_data = if import.operation.is_audit() {
+                signed_jwt(alg, self.kid.as_deref(), &import.claims, sign.as_ref())
+            } else {
+                String::new()
+            };
+            let call = Call::SupplyChain(
+                import.operation.clone().into_call::<Runtime>(audit_data.clone()),
+            );
+            extrinsics.push(json!({
+                "event": import.event,
+                "operation": import.operation,
+                "auditData": Some(audit_data).filter(|audit_data| !audit_data.is_empty()),
+                "call": format!("0x{}", HexDisplay::from(&call.encode())),
+            }));
+        }
+        let output = serde_json::to_string_pretty(&extrinsics);
+        println!("{}", output.map_err(|error| error.to_string())?);
+        Ok(())
+    }
+}
+
+impl EpcisExportCmd {
+    /// Print the history of the component and of its parts at the best block.
+    fn run(&self, client: &FullClient) -> sc_cli::Result<()> {
+        let api = client.runtime_api();
+        let at = BlockId::hash(client.info().best_hash);
+        let id: ComponentId = self
+            .id
+            .as_bytes()
+            .to_vec()
+            .try_into()
+            .map_err(|_| "The component id is too long.".to_string())?;
+        let runtime_error = |error: sp_api::ApiError| error.to_string();
+        let nodes = api
+            .provenance(
+                &at,
+                id,
+                Direction::Parts,
+                self.max_depth,
+                0,
+                MAX_EXPORTED_COMPONENTS,
+                MaxAudits::get(),
+            )
+            .map_err(runtime_error)?;
+        if nodes.is_empty() {
+            return Err(format!("There is no component {}.", self.id).into());
+        }
+
+        let mut histories = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let component = api.component(&at, node.id.clone()).map_err(runtime_error)?;
+            let (parts, quantity) = component
+                .map(|component| {
+                    let parts = component.components.iter();
+                    (parts.map(|(id, usage)| (text(id), *usage)).collect(), component.quantity)
+                })
+                .unwrap_or_default();
+            let audits = node
+                .audits
+                .into_iter()
+                .map(|audit| AuditRecord {
+                    timestamp: audit.timestamp,
+                    claims: Jwt::decode(&text(&audit.audit_data))
+                        .map(|jwt| jwt.claims)
+                        .unwrap_or_default(),
+                    status: audit.status,
+                })
+                .collect();
+            histories.push(History { id: text(&node.id), parts, quantity, audits });
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let document = epcis::export(&histories, now.as_millis() as u64);
+        let output = serde_json::to_string_pretty(&document);
+        println!("{}", output.map_err(|error| error.to_string())?);
+        Ok(())
+    }
+}
+
+impl CliConfiguration for EpcisExportCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+}
+
+/// A JWT with the given claims, signed with the algorithm `alg`.
+fn signed_jwt(
+    alg: &str,
+    kid: Option<&str>,
+    claims: &Map<String, Value>,
+    sign: &dyn Fn(&[u8]) -> Vec<u8>,
+) -> String {
+    let mut header = json!({ "alg": alg, "typ": "JWT" });
+    if let Some(kid) = kid {
+        header["kid"] = kid.into();
+    }
+    let input = format!(
+        "{}.{}",
+        encode_segment(header.to_string().as_bytes()),
+        encode_segment(Value::Object(claims.clone()).to_string().as_bytes())
+    );
+    let signature = sign(input.as_bytes());
+    format!("{}.{}", input, encode_segment(&signature))
+}
+
+fn encode_segment(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
@@ -14,7 +14,11 @@
 
 use std::sync::Arc;
 
-use green_aureus_runtime::{opaque::Block, AccountId, Balance, Index};
+use green_aureus_runtime::{opaque::Block, AccountId, Balance, Hash, Index, Moment};
+use pallet_supply_chain_rpc::{
+    documents::DocumentStore, Audit, AuditPayload, Authority, Component, ComponentId,
+    ConfidentialData,
+};
 pub use sc_rpc_api::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
@@ -29,6 +33,8 @@ pub struct FullDeps<C, P> {
     pub pool: Arc<P>,
     /// Whether to deny unsafe calls
     pub deny_unsafe: DenyUnsafe,
+    /// Store of the documents attached to audits.
+    pub documents: Arc<DocumentStore>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -39,9 +45,25 @@ where
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: pallet_supply_chain_rpc::SupplyChainRuntimeApi<
+        Block,
+        AccountId,
+        Hash,
+        Moment,
+        ComponentId,
+        Component,
+        Audit<AccountId, Moment>,
+        AuditPayload<Moment>,
+        ConfidentialData<AccountId>,
+        Authority,
+    >,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
+    use pallet_supply_chain_rpc::{
+        documents::{DocumentApi, Documents},
+        SupplyChain, SupplyChainApi,
+    };
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
 
@@ -50,6 +72,7 @@ where
         client,
         pool,
         deny_unsafe,
+        documents,
     } = deps;
 
     io.extend_with(SystemApi::to_delegate(FullSystem::new(
@@ -62,10 +85,8 @@ where
         client.clone(),
     )));
 
-    // Extend this RPC with a custom API by using the following syntax.
-    // `YourRpcStruct` should have a reference to a client, which is needed
-    // to call into the runtime.
-    // `io.extend_with(YourRpcTrait::to_delegate(YourRpcStruct::new(ReferenceToClient, ...)));`
+    io.extend_with(SupplyChainApi::to_delegate(SupplyChain::new(client, documents.clone())));
+    io.extend_with(DocumentApi::to_delegate(Documents::new(documents, deny_unsafe)));
 
     io
 }
@@ -8,6 +8,7 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use green_aureus_runtime::{self, opaque::Block, RuntimeApi};
+use pallet_supply_chain_rpc::documents::DocumentStore;
 use sc_client_api::ExecutorProvider;
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 pub use sc_executor::NativeElseWasmExecutor;
@@ -39,7 +40,7 @@ impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
     }
 }
 
-type FullClient =
+pub(crate) type FullClient =
     sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
@@ -229,6 +230,15 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
     let enable_grandpa = !config.disable_grandpa;
     let prometheus_registry = config.prometheus_registry().cloned();
 
+    let documents_path = config
+        .base_path
+        .as_ref()
+        .map(|base_path| base_path.config_dir(config.chain_spec.id()).join("documents"))
+        .ok_or_else(|| ServiceError::Other("The document store needs a base path.".into()))?;
+    let documents = Arc::new(
+        DocumentStore::open(documents_path).map_err(|e| ServiceError::Other(e.to_string()))?,
+    );
+
     let rpc_extensions_builder = {
         let client = client.clone();
         let pool = transaction_pool.clone();
@@ -238,6 +248,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
                 client: client.clone(),
                 pool: pool.clone(),
                 deny_unsafe,
+                documents: documents.clone(),
             };
 
             Ok(crate::rpc::create_full(deps))
@@ -0,0 +1,275 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! EPCIS 2.0 documents in JSON-LD. Only the fields the mapping reads or writes are typed, all
+//! other fields of an event, e.g. `readPoint` or `bizLocation`, are kept as they are.
+
+use pallet_supply_chain::types::{Quantity, Unit};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::EpcisError;
+
+/// JSON-LD context of EPCIS 2.0 documents.
+pub const EPCIS_CONTEXT: &str = "https://ref.gs1.org/standards/epcis/2.0.0/epcis-context.jsonld";
+/// The type of EPCIS documents.
+pub const DOCUMENT_TYPE: &str = "EPCISDocument";
+/// The supported EPCIS version.
+pub const SCHEMA_VERSION: &str = "2.0";
+
+/// UN/CEFACT codes of the units of measure, the first code of a unit is used for export.
+const UNIT_CODES: [(&str, Unit); 10] = [
+    ("H87", Unit::Piece),
+    ("EA", Unit::Piece),
+    ("C62", Unit::Piece),
+    ("MGM", Unit::Milligram),
+    ("GRM", Unit::Gram),
+    ("KGM", Unit::Kilogram),
+    ("TNE", Unit::Tonne),
+    ("MLT", Unit::Millilitre),
+    ("LTR", Unit::Litre),
+    ("MTQ", Unit::CubicMetre),
+];
+
+/// An EPCIS document.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpcisDocument {
+    /// The JSON-LD context, a URI or a list of URIs and namespace definitions.
+    #[serde(rename = "@context")]
+    pub context: Value,
+    /// The type of the document, always `EPCISDocument`.
+    #[serde(rename = "type")]
+    pub document_type: String,
+    /// The EPCIS version of the document.
+    pub schema_version: String,
+    /// When the document was created.
+    pub creation_date: String,
+    /// The events of the document.
+    #[serde(rename = "epcisBody")]
+    pub body: EpcisBody,
+}
+
+/// The body of an EPCIS document.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpcisBody {
+    /// The events, in the order they happened.
+    pub event_list: Vec<Event>,
+}
+
+/// An EPCIS event of a type the chain can record.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum Event {
+    /// Objects were created, observed or destroyed.
+    ObjectEvent(ObjectEvent),
+    /// Objects were packed into or unpacked from a parent, e.g. cases onto a pallet.
+    AggregationEvent(AggregationEvent),
+    /// Inputs were irreversibly turned into outputs, e.g. coffee beans were roasted.
+    TransformationEvent(TransformationEvent),
+}
+
+impl Event {
+    /// The fields all events share.
+    pub fn header(&self) -> &EventHeader {
+        match self {
+            Event::ObjectEvent(event) => &event.header,
+            Event::AggregationEvent(event) => &event.header,
+            Event::TransformationEvent(event) => &event.header,
+        }
+    }
+
+    /// The fields all events share.
+    pub fn header_mut(&mut self) -> &mut EventHeader {
+        match self {
+            Event::ObjectEvent(event) => &mut event.header,
+            Event::AggregationEvent(event) => &mut event.header,
+            Event::TransformationEvent(event) => &mut event.header,
+        }
+    }
+}
+
+/// The fields all events share.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventHeader {
+    /// Unique id of the event.
+    #[serde(rename = "eventID", default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    /// When the event happened.
+    pub event_time: String,
+    /// The time zone offset at the place the event happened, e.g. `+01:00`.
+    pub event_time_zone_offset: String,
+    /// The business step of the event, e.g. `shipping`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub biz_step: Option<String>,
+    /// The business condition of the objects after the event, e.g. `in_transit`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disposition: Option<String>,
+    /// Declares the event erroneous.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_declaration: Option<ErrorDeclaration>,
+    /// All other fields of the event.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Objects were created, observed or destroyed.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectEvent {
+    /// Whether the objects were created, observed or destroyed.
+    pub action: Action,
+    /// The objects.
+    #[serde(rename = "epcList", default, skip_serializing_if = "Vec::is_empty")]
+    pub epcs: Vec<String>,
+    /// Quantities of object classes, e.g. lots.
+    #[serde(rename = "quantityList", default, skip_serializing_if = "Vec::is_empty")]
+    pub quantities: Vec<QuantityElement>,
+    /// The fields all events share.
+    #[serde(flatten)]
+    pub header: EventHeader,
+}
+
+/// Objects were packed into or unpacked from a parent.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationEvent {
+    /// Whether the children were packed, observed or unpacked.
+    pub action: Action,
+    /// The parent, e.g. the SSCC of a pallet.
+    #[serde(rename = "parentID")]
+    pub parent_id: String,
+    /// The packed objects.
+    #[serde(rename = "childEPCs", default, skip_serializing_if = "Vec::is_empty")]
+    pub child_epcs: Vec<String>,
+    /// Packed quantities of object classes.
+    #[serde(rename = "childQuantityList", default, skip_serializing_if = "Vec::is_empty")]
+    pub child_quantities: Vec<QuantityElement>,
+    /// The fields all events share.
+    #[serde(flatten)]
+    pub header: EventHeader,
+}
+
+/// Inputs were irreversibly turned into outputs.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformationEvent {
+    /// The consumed objects.
+    #[serde(rename = "inputEPCList", default, skip_serializing_if = "Vec::is_empty")]
+    pub input_epcs: Vec<String>,
+    /// Consumed quantities of object classes.
+    #[serde(rename = "inputQuantityList", default, skip_serializing_if = "Vec::is_empty")]
+    pub input_quantities: Vec<QuantityElement>,
+    /// The produced objects.
+    #[serde(rename = "outputEPCList", default, skip_serializing_if = "Vec::is_empty")]
+    pub output_epcs: Vec<String>,
+    /// Produced quantities of object classes.
+    #[serde(rename = "outputQuantityList", default, skip_serializing_if = "Vec::is_empty")]
+    pub output_quantities: Vec<QuantityElement>,
+    /// The fields all events share.
+    #[serde(flatten)]
+    pub header: EventHeader,
+}
+
+/// What happened to the objects of an event.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Action {
+    /// The objects were created or packed.
+    Add,
+    /// The objects were observed.
+    Observe,
+    /// The objects were destroyed or unpacked.
+    Delete,
+}
+
+/// A quantity of an object class.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantityElement {
+    /// The object class, e.g. an LGTIN.
+    pub epc_class: String,
+    /// The amount. Without unit of measure, the number of pieces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    /// UN/CEFACT code of the unit of measure, e.g. `KGM`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uom: Option<String>,
+}
+
+impl QuantityElement {
+    /// The quantity of an object class as it is exported.
+    pub fn new(epc_class: String, quantity: Option<Quantity>) -> Self {
+        Self {
+            epc_class,
+            quantity: quantity.map(|quantity| quantity.amount as f64),
+            uom: quantity
+                .filter(|quantity| quantity.unit != Unit::Piece)
+                .and_then(|quantity| unit_code(quantity.unit))
+                .map(String::from),
+        }
+    }
+
+    /// The quantity as it is recorded on the chain, if the element has an amount. Fractional
+    /// amounts are converted to the next smaller unit that makes them whole, e.g. 2.5 KGM to
+    /// 2500 g.
+    pub fn to_quantity(&self) -> Result<Option<Quantity>, EpcisError> {
+        let invalid = || EpcisError::InvalidQuantity(self.epc_class.clone());
+        let mut amount = match self.quantity {
+            Some(amount) if amount.is_finite() && amount >= 0.0 => amount,
+            Some(_) => return Err(invalid()),
+            None => return Ok(None),
+        };
+        let mut unit = match &self.uom {
+            Some(code) => {
+                UNIT_CODES.iter().find(|(known, _)| known == code).ok_or_else(invalid)?.1
+            }
+            None => Unit::Piece,
+        };
+        loop {
+            let whole = amount.round();
+            if (amount - whole).abs() < 1e-6 && whole <= u64::MAX as f64 {
+                return Ok(Some(Quantity { amount: whole as u64, unit }));
+            }
+            unit = smaller_unit(unit).ok_or_else(invalid)?;
+            amount *= 1000.0;
+        }
+    }
+}
+
+/// Why an event is declared erroneous.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorDeclaration {
+    /// When the event was declared erroneous.
+    pub declaration_time: String,
+    /// CBV error reason, e.g. `incorrect_data`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The events that correct the erroneous event.
+    #[serde(rename = "correctiveEventIDs", default, skip_serializing_if = "Vec::is_empty")]
+    pub corrective_event_ids: Vec<String>,
+}
+
+/// The UN/CEFACT code of a unit of measure.
+pub fn unit_code(unit: Unit) -> Option<&'static str> {
+    UNIT_CODES.iter().find(|(_, known)| *known == unit).map(|(code, _)| *code)
+}
+
+/// The unit that is a thousandth of the given unit.
+fn smaller_unit(unit: Unit) -> Option<Unit> {
+    match unit {
+        Unit::Tonne => Some(Unit::Kilogram),
+        Unit::Kilogram => Some(Unit::Gram),
+        Unit::Gram => Some(Unit::Milligram),
+        Unit::CubicMetre => Some(Unit::Litre),
+        Unit::Litre => Some(Unit::Millilitre),
+        Unit::Piece | Unit::Milligram | Unit::Millilitre => None,
+    }
+}
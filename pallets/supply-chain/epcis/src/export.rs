@@ -0,0 +1,190 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Export of the history of components as EPCIS document.
+//!
+//! Audits whose claims are an EPCIS event, e.g. because they were imported, are exported as
+//! that event. Other audits become events that describe what the chain recorded: the first
+//! audit of a component commissions it, or transforms its parts into it, and later audits
+//! observe it. Their claims are kept in the `greenaureus:claims` extension field. Revoked and
+//! superseded audits are exported with an error declaration.
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use pallet_supply_chain::types::{AuditReason, AuditStatus, ComponentId, PartUsage, Quantity};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    document::{
+        Action, EpcisBody, EpcisDocument, ErrorDeclaration, Event, EventHeader, ObjectEvent,
+        QuantityElement, TransformationEvent, DOCUMENT_TYPE, EPCIS_CONTEXT, SCHEMA_VERSION,
+    },
+    Identifier,
+};
+
+/// Prefix of the extension fields of exported events.
+const NAMESPACE_PREFIX: &str = "greenaureus";
+/// The namespace of the extension fields of exported events.
+const NAMESPACE: &str = "https://greenaureus.de/epcis/";
+/// The extension field with the claims of an audit that is no EPCIS event.
+const CLAIMS_FIELD: &str = "greenaureus:claims";
+/// Claims of the JWT that describe the token rather than the event.
+const REGISTERED_CLAIMS: [&str; 7] = ["iss", "sub", "aud", "exp", "nbf", "iat", "jti"];
+
+/// The recorded history of a component.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    /// Id of the component.
+    pub id: ComponentId,
+    /// The parts the component was assembled from, with how much of each part went into it.
+    pub parts: Vec<(ComponentId, Option<PartUsage>)>,
+    /// How much of the component was produced, if that was recorded.
+    pub quantity: Option<Quantity>,
+    /// The audits of the component, ordered by their index.
+    pub audits: Vec<AuditRecord>,
+}
+
+/// An audit of a component.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuditRecord {
+    /// When the audit was added, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The claims of the audit JWT.
+    pub claims: Map<String, Value>,
+    /// Whether the audit is still valid.
+    pub status: AuditStatus,
+}
+
+/// The EPCIS document of the histories of components, created at `creation_date`, in
+/// milliseconds. Events are ordered by the time their audits were added, events that were
+/// added to several components are exported once.
+pub fn export(histories: &[History], creation_date: u64) -> EpcisDocument {
+    let creation_date = time(creation_date);
+    let mut events: Vec<(u64, Event)> = Vec::new();
+    for history in histories {
+        let mut history_events: Vec<Event> = history
+            .audits
+            .iter()
+            .enumerate()
+            .map(|(index, audit)| event(history, index, audit))
+            .collect();
+        let ids: Vec<Option<String>> =
+            history_events.iter().map(|event| event.header().event_id.clone()).collect();
+        for (audit, event) in history.audits.iter().zip(history_events.iter_mut()) {
+            if let Some(declaration) = error_declaration(audit.status, &ids, &creation_date) {
+                event.header_mut().error_declaration = Some(declaration);
+            }
+        }
+        for (audit, event) in history.audits.iter().zip(history_events) {
+            if !events.iter().any(|(_, known)| *known == event) {
+                events.push((audit.timestamp, event));
+            }
+        }
+    }
+    events.sort_by_key(|(timestamp, _)| *timestamp);
+
+    EpcisDocument {
+        context: json!([EPCIS_CONTEXT, { NAMESPACE_PREFIX: NAMESPACE }]),
+        document_type: DOCUMENT_TYPE.into(),
+        schema_version: SCHEMA_VERSION.into(),
+        creation_date,
+        body: EpcisBody { event_list: events.into_iter().map(|(_, event)| event).collect() },
+    }
+}
+
+/// The event of the audit at `index` of a component.
+fn event(history: &History, index: usize, audit: &AuditRecord) -> Event {
+    let claims: Map<String, Value> = audit
+        .claims
+        .iter()
+        .filter(|(name, _)| !REGISTERED_CLAIMS.contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    if let Ok(event) = serde_json::from_value(Value::Object(claims.clone())) {
+        return event;
+    }
+
+    let mut header = EventHeader {
+        event_id: Some(format!("urn:greenaureus:audit:{}:{}", history.id, index)),
+        event_time: time(audit.timestamp),
+        event_time_zone_offset: "+00:00".into(),
+        ..Default::default()
+    };
+    if !claims.is_empty() {
+        header.other.insert(CLAIMS_FIELD.into(), Value::Object(claims));
+    }
+    if index == 0 && !history.parts.is_empty() {
+        let (input_epcs, input_quantities) = objects(
+            history.parts.iter().map(|(id, usage)| (id, usage.map(|usage| usage.quantity))),
+        );
+        let (output_epcs, output_quantities) = objects([(&history.id, history.quantity)]);
+        return Event::TransformationEvent(TransformationEvent {
+            input_epcs,
+            input_quantities,
+            output_epcs,
+            output_quantities,
+            header,
+        });
+    }
+    let (action, biz_step, quantity) = match index {
+        0 => (Action::Add, "commissioning", history.quantity),
+        _ => (Action::Observe, "inspecting", None),
+    };
+    header.biz_step = Some(biz_step.into());
+    let (epcs, quantities) = objects([(&history.id, quantity)]);
+    Event::ObjectEvent(ObjectEvent { action, epcs, quantities, header })
+}
+
+/// Split components into objects and quantities of object classes. Components with a GS1
+/// identifier of a single object are objects, other components are object classes if their
+/// quantity is known.
+fn objects<'a>(
+    components: impl IntoIterator<Item = (&'a ComponentId, Option<Quantity>)>,
+) -> (Vec<String>, Vec<QuantityElement>) {
+    let mut epcs = Vec::new();
+    let mut quantities = Vec::new();
+    for (id, quantity) in components {
+        let is_class = Identifier::parse(id)
+            .map_or(quantity.is_some(), |identifier| identifier.is_class());
+        if is_class {
+            quantities.push(QuantityElement::new(id.clone(), quantity));
+        } else {
+            epcs.push(id.clone());
+        }
+    }
+    (epcs, quantities)
+}
+
+/// The error declaration of an audit that was revoked or superseded. `ids` are the event ids
+/// of the component's audits.
+fn error_declaration(
+    status: AuditStatus,
+    ids: &[Option<String>],
+    declaration_time: &str,
+) -> Option<ErrorDeclaration> {
+    let (reason, corrective_event_ids) = match status {
+        AuditStatus::Valid => return None,
+        AuditStatus::Revoked(reason) => (reason, Vec::new()),
+        AuditStatus::Superseded(index, reason) => {
+            (reason, ids.get(index as usize).cloned().flatten().into_iter().collect())
+        }
+    };
+    let reason = match reason {
+        AuditReason::DataError | AuditReason::WrongComponent => Some("incorrect_data"),
+        AuditReason::Fraud => Some("did_not_occur"),
+        AuditReason::Withdrawn | AuditReason::Other => None,
+    };
+    Some(ErrorDeclaration {
+        declaration_time: declaration_time.into(),
+        reason: reason.map(String::from),
+        corrective_event_ids,
+    })
+}
+
+/// A timestamp in milliseconds as date and time in UTC.
+fn time(timestamp: u64) -> String {
+    Utc.timestamp_millis(timestamp as i64).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
@@ -0,0 +1,204 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! GS1 identifiers of traded items, lots and logistic units.
+//!
+//! Identifiers are parsed from EPC URIs, e.g. `urn:epc:id:sgtin:0614141.812345.6789`, and
+//! GS1 Digital Link URIs, e.g. `https://id.gs1.org/01/80614141123458/21/6789`. Components are
+//! identified on-chain by the canonical Digital Link URI, since it doesn't depend on the length
+//! of the GS1 company prefix.
+
+use std::fmt;
+
+/// The host of canonical GS1 Digital Link URIs.
+const DIGITAL_LINK_HOST: &str = "https://id.gs1.org";
+/// Maximum length of serial numbers (AI 21) and lot numbers (AI 10).
+const MAX_SERIAL_LENGTH: usize = 20;
+
+/// Reasons why an identifier is not a valid GS1 identifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Gs1Error {
+    /// The identifier is neither an EPC URI nor a GS1 Digital Link URI of a supported type.
+    UnsupportedScheme,
+    /// A part of the identifier has the wrong length or contains invalid characters.
+    Malformed,
+    /// The check digit of the GTIN or SSCC is wrong.
+    CheckDigit,
+}
+
+impl fmt::Display for Gs1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Gs1Error::UnsupportedScheme => "not an SGTIN, LGTIN or SSCC",
+            Gs1Error::Malformed => "malformed GS1 identifier",
+            Gs1Error::CheckDigit => "wrong GS1 check digit",
+        })
+    }
+}
+
+/// A GS1 identifier.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Identifier {
+    /// Serialised Global Trade Item Number: a single trade item.
+    Sgtin {
+        /// The GTIN-14 of the item.
+        gtin: String,
+        /// The serial number of the item.
+        serial: String,
+    },
+    /// A lot of a trade item, identified by GTIN and lot number.
+    Lgtin {
+        /// The GTIN-14 of the item.
+        gtin: String,
+        /// The lot or batch number.
+        lot: String,
+    },
+    /// Serial Shipping Container Code: a logistic unit, e.g. a pallet.
+    Sscc(String),
+}
+
+impl Identifier {
+    /// Parse and validate an EPC URI or a GS1 Digital Link URI.
+    pub fn parse(uri: &str) -> Result<Self, Gs1Error> {
+        if let Some(epc) = uri.strip_prefix("urn:epc:id:sgtin:") {
+            let [company_prefix, item_reference, serial] = epc_parts(epc)?;
+            let (indicator, item_reference) = split_first_digit(item_reference)?;
+            let gtin = with_check_digit(indicator, company_prefix, item_reference, 13)?;
+            Ok(Identifier::Sgtin { gtin, serial: serial_number(serial)? })
+        } else if let Some(epc) = uri.strip_prefix("urn:epc:class:lgtin:") {
+            let [company_prefix, item_reference, lot] = epc_parts(epc)?;
+            let (indicator, item_reference) = split_first_digit(item_reference)?;
+            let gtin = with_check_digit(indicator, company_prefix, item_reference, 13)?;
+            Ok(Identifier::Lgtin { gtin, lot: serial_number(lot)? })
+        } else if let Some(epc) = uri.strip_prefix("urn:epc:id:sscc:") {
+            let (company_prefix, serial_reference) =
+                epc.split_once('.').ok_or(Gs1Error::Malformed)?;
+            let (extension, serial_reference) = split_first_digit(serial_reference)?;
+            Ok(Identifier::Sscc(with_check_digit(
+                extension,
+                company_prefix,
+                serial_reference,
+                17,
+            )?))
+        } else if let Some(path) = digital_link_path(uri) {
+            let segments: Vec<&str> = path.split('/').collect();
+            match segments[..] {
+                ["01", gtin, "21", serial] => Ok(Identifier::Sgtin {
+                    gtin: checked_number(gtin, 14)?,
+                    serial: serial_number(serial)?,
+                }),
+                ["01", gtin, "10", lot] => Ok(Identifier::Lgtin {
+                    gtin: checked_number(gtin, 14)?,
+                    lot: serial_number(lot)?,
+                }),
+                ["00", sscc] => Ok(Identifier::Sscc(checked_number(sscc, 18)?)),
+                _ => Err(Gs1Error::UnsupportedScheme),
+            }
+        } else {
+            Err(Gs1Error::UnsupportedScheme)
+        }
+    }
+
+    /// Whether the identifier names a quantity of items rather than a single object.
+    pub fn is_class(&self) -> bool {
+        matches!(self, Identifier::Lgtin { .. })
+    }
+}
+
+impl fmt::Display for Identifier {
+    /// The canonical GS1 Digital Link URI.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identifier::Sgtin { gtin, serial } => {
+                write!(f, "{}/01/{}/21/{}", DIGITAL_LINK_HOST, gtin, serial)
+            }
+            Identifier::Lgtin { gtin, lot } => {
+                write!(f, "{}/01/{}/10/{}", DIGITAL_LINK_HOST, gtin, lot)
+            }
+            Identifier::Sscc(sscc) => write!(f, "{}/00/{}", DIGITAL_LINK_HOST, sscc),
+        }
+    }
+}
+
+/// The GS1 check digit of a number, given without check digit.
+pub fn check_digit(digits: &str) -> Option<char> {
+    let mut sum = 0;
+    // Digits are weighted 3 and 1 alternately, starting with 3 at the rightmost digit.
+    for (position, digit) in digits.chars().rev().enumerate() {
+        let digit = digit.to_digit(10)?;
+        sum += if position % 2 == 0 { digit * 3 } else { digit };
+    }
+    char::from_digit((10 - sum % 10) % 10, 10)
+}
+
+/// The company prefix, the reference and the serial number of an EPC URI.
+fn epc_parts(epc: &str) -> Result<[&str; 3], Gs1Error> {
+    let mut parts = epc.splitn(3, '.');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(company_prefix), Some(reference), Some(serial)) => {
+            Ok([company_prefix, reference, serial])
+        }
+        _ => Err(Gs1Error::Malformed),
+    }
+}
+
+/// Split the indicator or extension digit off a reference of an EPC URI.
+fn split_first_digit(reference: &str) -> Result<(&str, &str), Gs1Error> {
+    match reference.chars().next() {
+        Some(digit) if digit.is_ascii_digit() => Ok(reference.split_at(1)),
+        _ => Err(Gs1Error::Malformed),
+    }
+}
+
+/// Assemble a GTIN or SSCC from the parts of an EPC URI, which have `length` digits in total,
+/// and append the check digit. GS1 company prefixes have 6 to 12 digits.
+fn with_check_digit(
+    first: &str,
+    company_prefix: &str,
+    reference: &str,
+    length: usize,
+) -> Result<String, Gs1Error> {
+    let digits = format!("{}{}{}", first, company_prefix, reference);
+    if !(6..=12).contains(&company_prefix.len())
+        || digits.len() != length
+        || !digits.bytes().all(|digit| digit.is_ascii_digit())
+    {
+        return Err(Gs1Error::Malformed);
+    }
+    let check = check_digit(&digits).ok_or(Gs1Error::Malformed)?;
+    Ok(format!("{}{}", digits, check))
+}
+
+/// Validate a GTIN or SSCC with `length` digits, including its check digit.
+fn checked_number(number: &str, length: usize) -> Result<String, Gs1Error> {
+    if number.len() != length || !number.bytes().all(|digit| digit.is_ascii_digit()) {
+        return Err(Gs1Error::Malformed);
+    }
+    let (digits, check) = number.split_at(length - 1);
+    if check_digit(digits).map(String::from).as_deref() != Some(check) {
+        return Err(Gs1Error::CheckDigit);
+    }
+    Ok(number.into())
+}
+
+/// Validate a serial or lot number. Only characters that need no escaping in URIs are
+/// supported.
+fn serial_number(serial: &str) -> Result<String, Gs1Error> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_');
+    if serial.is_empty() || serial.len() > MAX_SERIAL_LENGTH || !serial.chars().all(valid) {
+        return Err(Gs1Error::Malformed);
+    }
+    Ok(serial.into())
+}
+
+/// The path of a GS1 Digital Link URI, without leading slash. Any host may resolve GS1
+/// Digital Links.
+fn digital_link_path(uri: &str) -> Option<&str> {
+    let rest = uri.strip_prefix("https://")?;
+    let (_, path) = rest.split_once('/')?;
+    Some(path.trim_end_matches('/'))
+}
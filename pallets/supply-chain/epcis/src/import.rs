@@ -0,0 +1,240 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Import of EPCIS documents as supply chain extrinsics.
+
+use pallet_supply_chain::{
+    types::{ComponentId, PartUsage, Quantity},
+    Call, Config,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    document::{Action, EpcisDocument, Event, QuantityElement, DOCUMENT_TYPE, SCHEMA_VERSION},
+    EpcisError, Gs1Error, Identifier,
+};
+
+/// A supply chain extrinsic that records an EPCIS event, with its parameters except the
+/// audit data.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "call")]
+pub enum Operation {
+    /// Add an audit to a component.
+    Audit { component_id: ComponentId },
+    /// Create a component from parts.
+    AuditAssembly {
+        component_id: ComponentId,
+        components: Vec<(ComponentId, Option<PartUsage>)>,
+    },
+    /// Turn a quantity of a component into a new component.
+    TransformComponent {
+        component_id: ComponentId,
+        consumed: Quantity,
+        new_component_id: ComponentId,
+        produced: Quantity,
+    },
+    /// Record how much of a component was produced.
+    RecordQuantity { component_id: ComponentId, quantity: Quantity },
+    /// Record that a component was destroyed.
+    DestroyComponent { component_id: ComponentId },
+}
+
+impl Operation {
+    /// Whether the extrinsic adds an audit and therefore takes audit data.
+    pub fn is_audit(&self) -> bool {
+        !matches!(self, Operation::RecordQuantity { .. } | Operation::DestroyComponent { .. })
+    }
+
+    /// The extrinsic, with the given audit JWT. Extrinsics that don't add an audit ignore
+    /// `audit_data`.
+    pub fn into_call<T: Config>(self, audit_data: String) -> Call<T> {
+        match self {
+            Operation::Audit { component_id } => Call::audit { audit_data, component_id },
+            Operation::AuditAssembly { component_id, components } => {
+                Call::audit_assembly { audit_data, components, component_id }
+            }
+            Operation::TransformComponent {
+                component_id,
+                consumed,
+                new_component_id,
+                produced,
+            } => Call::transform_component {
+                audit_data,
+                component_id,
+                consumed,
+                new_component_id,
+                produced,
+            },
+            Operation::RecordQuantity { component_id, quantity } => {
+                Call::record_quantity { component_id, quantity }
+            }
+            Operation::DestroyComponent { component_id } => {
+                Call::destroy_component { component_id }
+            }
+        }
+    }
+}
+
+/// An extrinsic that records an imported event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Import {
+    /// Index of the event in the document.
+    pub event: usize,
+    /// The event as claims of the audit JWT.
+    pub claims: Map<String, Value>,
+    /// The extrinsic.
+    pub operation: Operation,
+}
+
+/// The extrinsics that record the events of an EPCIS document, in the order they have to be
+/// submitted.
+pub fn import(json: &str) -> Result<Vec<Import>, EpcisError> {
+    let document: EpcisDocument =
+        serde_json::from_str(json).map_err(|error| EpcisError::Malformed(error.to_string()))?;
+    if document.document_type != DOCUMENT_TYPE {
+        return Err(EpcisError::Malformed(format!("unexpected type {}", document.document_type)));
+    }
+    if document.schema_version != SCHEMA_VERSION
+        && !document.schema_version.starts_with("2.0.")
+    {
+        return Err(EpcisError::UnsupportedVersion(document.schema_version));
+    }
+
+    let mut imports = Vec::new();
+    for (index, event) in document.body.event_list.iter().enumerate() {
+        let operations = operations(event)?;
+        if operations.is_empty() {
+            return Err(EpcisError::EmptyEvent(index));
+        }
+        // Serializing an event to JSON can't fail.
+        let claims = match serde_json::to_value(event) {
+            Ok(Value::Object(claims)) => claims,
+            _ => Map::new(),
+        };
+        imports.extend(operations.into_iter().map(|operation| Import {
+            event: index,
+            claims: claims.clone(),
+            operation,
+        }));
+    }
+    Ok(imports)
+}
+
+/// The extrinsics that record an event. Empty if the event names no objects.
+pub fn operations(event: &Event) -> Result<Vec<Operation>, EpcisError> {
+    let mut operations = Vec::new();
+    match event {
+        Event::ObjectEvent(event) => {
+            for (component_id, quantity) in objects(&event.epcs, &event.quantities)? {
+                operations.push(Operation::Audit { component_id: component_id.clone() });
+                match (event.action, quantity) {
+                    (Action::Add, Some(quantity)) => {
+                        operations.push(Operation::RecordQuantity { component_id, quantity })
+                    }
+                    (Action::Delete, _) => {
+                        operations.push(Operation::DestroyComponent { component_id })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Event::AggregationEvent(event) => {
+            let component_id = component_id(&event.parent_id)?;
+            if event.action == Action::Add {
+                let components: Vec<_> = objects(&event.child_epcs, &event.child_quantities)?
+                    .into_iter()
+                    .map(|(child, quantity)| (child, quantity.map(usage)))
+                    .collect();
+                if !components.is_empty() {
+                    operations.push(Operation::AuditAssembly { component_id, components });
+                }
+            } else {
+                operations.push(Operation::Audit { component_id });
+            }
+        }
+        Event::TransformationEvent(event) => {
+            let inputs = objects(&event.input_epcs, &event.input_quantities)?;
+            let outputs = objects(&event.output_epcs, &event.output_quantities)?;
+            if inputs.is_empty() || outputs.is_empty() {
+                return Ok(operations);
+            }
+            // A transformation can't produce more than it consumes of the same dimension.
+            if let ([(input, Some(consumed))], [(output, Some(produced))]) =
+                (&inputs[..], &outputs[..])
+            {
+                if consumed.is_compatible(produced)
+                    && produced.base_amount() > consumed.base_amount()
+                {
+                    operations.push(Operation::AuditAssembly {
+                        component_id: output.clone(),
+                        components: vec![(input.clone(), Some(usage(*consumed)))],
+                    });
+                    operations.push(Operation::RecordQuantity {
+                        component_id: output.clone(),
+                        quantity: *produced,
+                    });
+                    return Ok(operations);
+                }
+                operations.push(Operation::TransformComponent {
+                    component_id: input.clone(),
+                    consumed: *consumed,
+                    new_component_id: output.clone(),
+                    produced: *produced,
+                });
+                return Ok(operations);
+            }
+            // The consumed quantities can't be attributed to one of several outputs.
+            let single_output = outputs.len() == 1;
+            for (component_id, produced) in outputs {
+                let components = inputs
+                    .iter()
+                    .map(|(input, consumed)| {
+                        (input.clone(), consumed.filter(|_| single_output).map(usage))
+                    })
+                    .collect();
+                operations.push(Operation::AuditAssembly {
+                    component_id: component_id.clone(),
+                    components,
+                });
+                if let Some(quantity) = produced {
+                    operations.push(Operation::RecordQuantity { component_id, quantity });
+                }
+            }
+        }
+    }
+    Ok(operations)
+}
+
+/// The id of the component an EPC or EPC class identifies. GS1 identifiers are validated and
+/// identified by their canonical Digital Link URI, other identifiers are used as they are.
+pub fn component_id(uri: &str) -> Result<ComponentId, EpcisError> {
+    match Identifier::parse(uri) {
+        Ok(identifier) => Ok(identifier.to_string()),
+        Err(Gs1Error::UnsupportedScheme) => Ok(uri.into()),
+        Err(error) => Err(EpcisError::InvalidIdentifier(uri.into(), error)),
+    }
+}
+
+/// The components of objects and quantities of object classes, with the quantities.
+fn objects(
+    epcs: &[String],
+    quantities: &[QuantityElement],
+) -> Result<Vec<(ComponentId, Option<Quantity>)>, EpcisError> {
+    let mut objects = Vec::with_capacity(epcs.len() + quantities.len());
+    for epc in epcs {
+        objects.push((component_id(epc)?, None));
+    }
+    for element in quantities {
+        objects.push((component_id(&element.epc_class)?, element.to_quantity()?));
+    }
+    Ok(objects)
+}
+
+fn usage(quantity: Quantity) -> PartUsage {
+    PartUsage { quantity, yield_factor: None }
+}
@@ -0,0 +1,70 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Mapping between GS1 EPCIS 2.0 events and the supply chain pallet.
+//!
+//! EPCIS documents are imported as supply chain extrinsics whose audits carry the events as
+//! JWT claims, and the history of components is exported as EPCIS document again. Events are
+//! mapped as follows:
+//!
+//! * `ObjectEvent` with action `ADD` or `OBSERVE`: an audit of each object. Recorded
+//!   quantities of added object classes are recorded as the quantity of the component. With
+//!   action `DELETE`, each object is audited and destroyed.
+//! * `AggregationEvent` with action `ADD`: an assembly of the children into the parent. With
+//!   other actions, an audit of the parent.
+//! * `TransformationEvent`: a transformation of a single input quantity into a single output
+//!   quantity that is not larger, otherwise an assembly of the inputs into each output.
+//!
+//! GS1 identifiers (SGTIN, LGTIN and SSCC) are validated and identify components by their
+//! canonical GS1 Digital Link URI. Other identifiers are used as they are.
+
+pub mod document;
+pub mod export;
+pub mod gs1;
+pub mod import;
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+pub use document::EpcisDocument;
+pub use export::{export, AuditRecord, History};
+pub use gs1::{Gs1Error, Identifier};
+pub use import::{import, Import, Operation};
+
+/// Reasons why an EPCIS document could not be imported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EpcisError {
+    /// The document is no EPCIS document in JSON-LD or contains events of unsupported types.
+    Malformed(String),
+    /// The document has an EPCIS version other than 2.0.
+    UnsupportedVersion(String),
+    /// An identifier claims to be a GS1 identifier but is not valid.
+    InvalidIdentifier(String, Gs1Error),
+    /// The quantity of the object class has a negative amount or an unknown unit of measure,
+    /// or it is too precise to be recorded.
+    InvalidQuantity(String),
+    /// The event with the given index does not name any objects.
+    EmptyEvent(usize),
+}
+
+impl fmt::Display for EpcisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EpcisError::Malformed(error) => write!(f, "Malformed EPCIS document: {}", error),
+            EpcisError::UnsupportedVersion(version) => {
+                write!(f, "EPCIS version {} is not supported, only 2.0.", version)
+            }
+            EpcisError::InvalidIdentifier(id, error) => write!(f, "{}: {}", id, error),
+            EpcisError::InvalidQuantity(class) => write!(f, "Invalid quantity of {}", class),
+            EpcisError::EmptyEvent(index) => write!(f, "Event {} names no objects", index),
+        }
+    }
+}
+
+impl std::error::Error for EpcisError {}
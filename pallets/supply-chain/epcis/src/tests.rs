@@ -0,0 +1,281 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+use crate::{
+    document::{Action, Event},
+    export, gs1, import, AuditRecord, EpcisError, Gs1Error, History, Identifier, Operation,
+};
+use pallet_supply_chain::types::{AuditReason, AuditStatus, PartUsage, Quantity, Unit};
+use serde_json::{json, Value};
+
+const SGTIN: &str = "https://id.gs1.org/01/80614141123458/21/6789";
+const LGTIN: &str = "https://id.gs1.org/01/04012345123456/10/998877";
+const SSCC: &str = "https://id.gs1.org/00/106141412345678908";
+
+fn document(events: Value) -> String {
+    json!({
+        "@context": ["https://ref.gs1.org/standards/epcis/2.0.0/epcis-context.jsonld"],
+        "type": "EPCISDocument",
+        "schemaVersion": "2.0",
+        "creationDate": "2022-01-10T10:00:00.000Z",
+        "epcisBody": { "eventList": events },
+    })
+    .to_string()
+}
+
+fn kilograms(amount: u64) -> Quantity {
+    Quantity { amount, unit: Unit::Kilogram }
+}
+
+#[test]
+fn gs1_identifiers_are_validated() {
+    assert_eq!(gs1::check_digit("950600013435"), Some('2'));
+    assert_eq!(
+        Identifier::parse("urn:epc:id:sgtin:0614141.812345.6789").unwrap().to_string(),
+        SGTIN
+    );
+    assert_eq!(
+        Identifier::parse("urn:epc:class:lgtin:4012345.012345.998877").unwrap().to_string(),
+        LGTIN
+    );
+    assert_eq!(Identifier::parse("urn:epc:id:sscc:0614141.1234567890").unwrap().to_string(), SSCC);
+    assert_eq!(Identifier::parse(SGTIN).unwrap().to_string(), SGTIN);
+    assert!(Identifier::parse(LGTIN).unwrap().is_class());
+    assert_eq!(
+        Identifier::parse("https://example.com/00/106141412345678908").unwrap().to_string(),
+        SSCC
+    );
+
+    assert_eq!(
+        Identifier::parse("https://id.gs1.org/01/80614141123459/21/6789"),
+        Err(Gs1Error::CheckDigit)
+    );
+    assert_eq!(
+        Identifier::parse("urn:epc:id:sgtin:0614141.8123456.6789"),
+        Err(Gs1Error::Malformed)
+    );
+    assert_eq!(
+        Identifier::parse("urn:epc:id:sgtin:0614141.812345.serial/1"),
+        Err(Gs1Error::Malformed)
+    );
+    assert_eq!(
+        Identifier::parse("urn:epc:id:sgln:0614141.12345.0"),
+        Err(Gs1Error::UnsupportedScheme)
+    );
+}
+
+#[test]
+fn epcis_events_are_imported_as_extrinsics() {
+    let json = document(json!([
+        {
+            "type": "ObjectEvent",
+            "eventTime": "2022-01-10T08:00:00.000Z",
+            "eventTimeZoneOffset": "+01:00",
+            "action": "ADD",
+            "bizStep": "commissioning",
+            "quantityList": [
+                {
+                    "epcClass": "urn:epc:class:lgtin:4012345.012345.998877",
+                    "quantity": 2.5,
+                    "uom": "KGM",
+                }
+            ],
+            "readPoint": { "id": "urn:epc:id:sgln:4012345.00001.0" },
+        },
+        {
+            "type": "TransformationEvent",
+            "eventTime": "2022-01-10T09:00:00.000Z",
+            "eventTimeZoneOffset": "+01:00",
+            "inputQuantityList": [{ "epcClass": LGTIN, "quantity": 2, "uom": "KGM" }],
+            "outputEPCList": ["urn:epc:id:sgtin:0614141.812345.6789"],
+        },
+        {
+            "type": "AggregationEvent",
+            "eventTime": "2022-01-10T10:00:00.000Z",
+            "eventTimeZoneOffset": "+01:00",
+            "action": "ADD",
+            "parentID": "urn:epc:id:sscc:0614141.1234567890",
+            "childEPCs": [SGTIN],
+        },
+    ]));
+
+    let imports = import(&json).unwrap();
+    let operations: Vec<_> = imports.iter().map(|import| import.operation.clone()).collect();
+    assert_eq!(
+        operations,
+        vec![
+            Operation::Audit { component_id: LGTIN.into() },
+            Operation::RecordQuantity {
+                component_id: LGTIN.into(),
+                quantity: Quantity { amount: 2500, unit: Unit::Gram },
+            },
+            Operation::AuditAssembly {
+                component_id: SGTIN.into(),
+                components: vec![(
+                    LGTIN.into(),
+                    Some(PartUsage { quantity: kilograms(2), yield_factor: None })
+                )],
+            },
+            Operation::AuditAssembly {
+                component_id: SSCC.into(),
+                components: vec![(SGTIN.into(), None)],
+            },
+        ]
+    );
+    // The audits carry the events, including the fields the mapping doesn't read.
+    assert_eq!(imports[0].event, 0);
+    assert_eq!(imports[0].claims["type"], "ObjectEvent");
+    assert_eq!(imports[0].claims["readPoint"]["id"], "urn:epc:id:sgln:4012345.00001.0");
+    assert_eq!(imports[3].event, 2);
+    assert!(!Operation::RecordQuantity { component_id: LGTIN.into(), quantity: kilograms(1) }
+        .is_audit());
+
+    // A transformation that produces more than it consumes is imported as an assembly.
+    let json = document(json!([{
+        "type": "TransformationEvent",
+        "eventTime": "2022-01-10T09:00:00.000Z",
+        "eventTimeZoneOffset": "+01:00",
+        "inputQuantityList": [{ "epcClass": LGTIN, "quantity": 2, "uom": "KGM" }],
+        "outputQuantityList": [{ "epcClass": SGTIN, "quantity": 3, "uom": "KGM" }],
+    }]));
+    let operations: Vec<_> =
+        import(&json).unwrap().into_iter().map(|import| import.operation).collect();
+    assert_eq!(
+        operations,
+        vec![
+            Operation::AuditAssembly {
+                component_id: SGTIN.into(),
+                components: vec![(
+                    LGTIN.into(),
+                    Some(PartUsage { quantity: kilograms(2), yield_factor: None })
+                )],
+            },
+            Operation::RecordQuantity { component_id: SGTIN.into(), quantity: kilograms(3) },
+        ]
+    );
+}
+
+#[test]
+fn epcis_import_rejects_invalid_documents() {
+    let event = |epc: &str| {
+        json!([{
+            "type": "ObjectEvent",
+            "eventTime": "2022-01-10T08:00:00.000Z",
+            "eventTimeZoneOffset": "+01:00",
+            "action": "OBSERVE",
+            "epcList": [epc],
+        }])
+    };
+    assert_eq!(
+        import(&document(event("urn:epc:id:sgtin:0614141.812345.6789"))).unwrap()[0].operation,
+        Operation::Audit { component_id: SGTIN.into() }
+    );
+    assert_eq!(
+        import(&document(event("https://id.gs1.org/00/106141412345678909"))),
+        Err(EpcisError::InvalidIdentifier(
+            "https://id.gs1.org/00/106141412345678909".into(),
+            Gs1Error::CheckDigit
+        ))
+    );
+    let json = document(json!([{
+        "type": "ObjectEvent",
+        "eventTime": "2022-01-10T08:00:00.000Z",
+        "eventTimeZoneOffset": "+01:00",
+        "action": "OBSERVE",
+    }]));
+    assert_eq!(import(&json), Err(EpcisError::EmptyEvent(0)));
+    let json = document(json!([{
+        "type": "ObjectEvent",
+        "eventTime": "2022-01-10T08:00:00.000Z",
+        "eventTimeZoneOffset": "+01:00",
+        "action": "ADD",
+        "quantityList": [{ "epcClass": LGTIN, "quantity": 1, "uom": "LBR" }],
+    }]));
+    assert_eq!(import(&json), Err(EpcisError::InvalidQuantity(LGTIN.into())));
+    let json = document(json!([])).replace("\"2.0\"", "\"1.2\"");
+    assert_eq!(import(&json), Err(EpcisError::UnsupportedVersion("1.2".into())));
+}
+
+#[test]
+fn component_histories_are_exported_as_epcis() {
+    let imported = import(&document(json!([{
+        "type": "ObjectEvent",
+        "eventID": "ni:///sha-256;abc",
+        "eventTime": "2022-01-10T08:00:00.000Z",
+        "eventTimeZoneOffset": "+01:00",
+        "action": "OBSERVE",
+        "epcList": [SGTIN],
+    }])))
+    .unwrap();
+    let mut claims = imported[0].claims.clone();
+    let issuer = "did:greenaureus:5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    claims.insert("iss".into(), json!(issuer));
+    let lot = History {
+        id: LGTIN.into(),
+        quantity: Some(kilograms(3)),
+        audits: vec![AuditRecord {
+            timestamp: 1_000,
+            claims: json!({ "result": "passed" }).as_object().cloned().unwrap(),
+            status: AuditStatus::Valid,
+        }],
+        ..Default::default()
+    };
+    let item = History {
+        id: SGTIN.into(),
+        parts: vec![(
+            LGTIN.into(),
+            Some(PartUsage { quantity: kilograms(2), yield_factor: None }),
+        )],
+        audits: vec![
+            AuditRecord {
+                timestamp: 2_000,
+                status: AuditStatus::Superseded(1, AuditReason::DataError),
+                ..Default::default()
+            },
+            AuditRecord { timestamp: 3_000, claims, status: AuditStatus::Valid },
+        ],
+        ..Default::default()
+    };
+
+    let document = export(&[item, lot], 4_000);
+    assert_eq!(document.creation_date, "1970-01-01T00:00:04.000Z");
+    let events = &document.body.event_list;
+    assert_eq!(events.len(), 3);
+
+    match &events[0] {
+        Event::ObjectEvent(event) => {
+            assert_eq!(event.action, Action::Add);
+            assert!(event.epcs.is_empty());
+            assert_eq!(event.quantities[0].epc_class, LGTIN);
+            assert_eq!(event.quantities[0].quantity, Some(3.0));
+            assert_eq!(event.quantities[0].uom.as_deref(), Some("KGM"));
+            assert_eq!(event.header.event_time, "1970-01-01T00:00:01.000Z");
+            assert_eq!(event.header.other["greenaureus:claims"], json!({ "result": "passed" }));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    match &events[1] {
+        Event::TransformationEvent(event) => {
+            assert_eq!(event.input_quantities[0].epc_class, LGTIN);
+            assert_eq!(event.input_quantities[0].quantity, Some(2.0));
+            assert_eq!(event.output_epcs, vec![SGTIN.to_string()]);
+            let declaration = event.header.error_declaration.as_ref().unwrap();
+            assert_eq!(declaration.reason.as_deref(), Some("incorrect_data"));
+            assert_eq!(declaration.corrective_event_ids, vec!["ni:///sha-256;abc".to_string()]);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    // The imported event is exported as it was imported, without the registered claims.
+    match &events[2] {
+        Event::ObjectEvent(event) => {
+            assert_eq!(event.header.event_id.as_deref(), Some("ni:///sha-256;abc"));
+            assert_eq!(event.action, Action::Observe);
+            assert!(event.header.other.is_empty());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! W3C DID documents of authorities and W3C Verifiable Credentials of audits.
+//!
+//! Authorities are resolved as `did:greenaureus:<address>` with their registered keys as
+//! verification methods. Audits are exported as credentials whose proof is the audit JWT
+//! (`JwtProof2020`). Credentials are imported by adding their JWT as audit: the chain accepts
+//! VC-JWTs whose `iss` claim and `kid` header reference the auditor's DID.
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use pallet_supply_chain::{
+    did,
+    jwt::Jwt,
+    types::{AuditStatus, AuthorityStatus, KeyStatus, VerificationKey},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{text, Authority};
+
+/// JSON-LD context of DID documents.
+const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+/// JSON-LD context of `Multikey` verification methods.
+const MULTIKEY_CONTEXT: &str = "https://w3id.org/security/multikey/v1";
+/// JSON-LD context of Verifiable Credentials.
+const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+/// The type of the credentials of audits, besides `VerifiableCredential`.
+const AUDIT_CREDENTIAL_TYPE: &str = "SupplyChainAudit";
+/// Claims of the JWT that describe the token rather than the audited component.
+const REGISTERED_CLAIMS: [&str; 7] = ["iss", "sub", "aud", "exp", "nbf", "iat", "jti"];
+/// The claim of a VC-JWT that contains the credential.
+const CREDENTIAL_CLAIM: &str = "vc";
+
+/// The DID document of an authority.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    /// The JSON-LD contexts of the document.
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    /// The DID.
+    pub id: String,
+    /// The website of the organization, if it registered one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_known_as: Vec<String>,
+    /// The keys of the organization that verify its audits, including rotated keys.
+    pub verification_method: Vec<VerificationMethod>,
+    /// The keys that verify new audits. Empty while the organization is suspended.
+    pub assertion_method: Vec<String>,
+}
+
+/// A key of an authority.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    /// The DID URL of the key.
+    pub id: String,
+    /// The type of the verification method, always `Multikey`.
+    #[serde(rename = "type")]
+    pub method_type: String,
+    /// The DID of the authority.
+    pub controller: String,
+    /// The public key, prefixed with its multicodec and encoded as multibase base58btc.
+    pub public_key_multibase: String,
+}
+
+/// An audit as W3C Verifiable Credential.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential {
+    /// The JSON-LD contexts of the credential.
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    /// URN of the audit.
+    pub id: String,
+    /// The types of the credential.
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    /// The DID of the auditor.
+    pub issuer: String,
+    /// When the audit was added to the chain.
+    pub issuance_date: String,
+    /// The claims of the audit about the component.
+    pub credential_subject: Map<String, Value>,
+    /// Whether the audit is still valid.
+    pub credential_status: CredentialStatus,
+    /// The audit JWT, signed by the auditor.
+    pub proof: CredentialProof,
+}
+
+/// The status of an audit credential on the chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStatus {
+    /// Id of the status.
+    pub id: String,
+    /// The type of the status, always `SupplyChainAuditStatus`.
+    #[serde(rename = "type")]
+    pub status_type: String,
+    /// Whether the audit was revoked or superseded.
+    pub status: AuditStatus,
+}
+
+/// The proof of an audit credential.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProof {
+    /// The type of the proof, always `JwtProof2020`.
+    #[serde(rename = "type")]
+    pub proof_type: String,
+    /// The DID URL of the key the JWT was verified against.
+    pub verification_method: String,
+    /// The audit JWT.
+    pub jwt: String,
+}
+
+/// The DID document of the authority with the given DID.
+pub fn did_document(did: &str, authority: &Authority) -> DidDocument {
+    let usable = authority.keys.iter().filter(|key| key.status != KeyStatus::Revoked);
+    let verification_method = usable
+        .clone()
+        .map(|key| VerificationMethod {
+            id: did::key_url(did, &text(&key.kid)),
+            method_type: "Multikey".into(),
+            controller: did.into(),
+            public_key_multibase: multikey(&key.key),
+        })
+        .collect();
+    let assertion_method = usable
+        .filter(|key| {
+            key.status == KeyStatus::Active && authority.status == AuthorityStatus::Active
+        })
+        .map(|key| did::key_url(did, &text(&key.kid)))
+        .collect();
+
+    DidDocument {
+        context: vec![DID_CONTEXT.into(), MULTIKEY_CONTEXT.into()],
+        id: did.into(),
+        also_known_as: authority.website.iter().map(|website| text(website)).collect(),
+        verification_method,
+        assertion_method,
+    }
+}
+
+/// The credential of the audit at `index` of a component, added by the authority with the
+/// given DID at `timestamp`, in milliseconds.
+pub fn audit_credential(
+    component_id: &str,
+    index: u32,
+    issuer: String,
+    timestamp: u64,
+    key_id: &str,
+    jwt: String,
+    status: AuditStatus,
+) -> VerifiableCredential {
+    let id = format!("urn:greenaureus:audit:{}:{}", component_id, index);
+    let claims = Jwt::decode(&jwt).map(|jwt| jwt.claims).unwrap_or_default();
+    let mut types = vec!["VerifiableCredential".to_string(), AUDIT_CREDENTIAL_TYPE.to_string()];
+    // VC-JWTs carry the credential in a claim, other JWTs are claims about the component.
+    let mut subject = match claims.get(CREDENTIAL_CLAIM).and_then(Value::as_object) {
+        Some(credential) => {
+            let credential_types = credential.get("type").and_then(Value::as_array);
+            for credential_type in credential_types.into_iter().flatten() {
+                if let Some(credential_type) = credential_type.as_str() {
+                    if !types.iter().any(|known| known == credential_type) {
+                        types.push(credential_type.into());
+                    }
+                }
+            }
+            let subject = credential.get("credentialSubject").and_then(Value::as_object);
+            subject.cloned().unwrap_or_default()
+        }
+        None => claims
+            .into_iter()
+            .filter(|(name, _)| !REGISTERED_CLAIMS.contains(&name.as_str()))
+            .collect(),
+    };
+    subject
+        .entry("id")
+        .or_insert_with(|| format!("urn:greenaureus:component:{}", component_id).into());
+    let issuance_date = Utc
+        .timestamp_millis(timestamp as i64)
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    VerifiableCredential {
+        context: vec![CREDENTIALS_CONTEXT.into()],
+        credential_status: CredentialStatus {
+            id: format!("{}#status", id),
+            status_type: "SupplyChainAuditStatus".into(),
+            status,
+        },
+        id,
+        types,
+        proof: CredentialProof {
+            proof_type: "JwtProof2020".into(),
+            verification_method: did::key_url(&issuer, key_id),
+            jwt,
+        },
+        issuer,
+        issuance_date,
+        credential_subject: subject,
+    }
+}
+
+/// The public key as multibase base58btc with its multicodec prefix.
+fn multikey(key: &VerificationKey) -> String {
+    let (codec, public_key): ([u8; 2], &[u8]) = match key {
+        VerificationKey::Ed25519(public) => ([0xed, 0x01], &public[..]),
+        VerificationKey::Sr25519(public) => ([0xef, 0x01], &public[..]),
+        VerificationKey::Secp256k1(public) => ([0xe7, 0x01], &public[..]),
+    };
+    let mut bytes = codec.to_vec();
+    bytes.extend_from_slice(public_key);
+    format!("{}{}", did::BASE58_PREFIX, did::encode_base58(&bytes))
+}
@@ -0,0 +1,61 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Helpers for selectively disclosed audit claims, shared by the RPC and the command line.
+//! Auditors conceal claims before signing an audit JWT and keep the disclosures; holders
+//! present the audit JWT with some of the disclosures, `<jwt>~<disclosure>~...~`.
+
+use pallet_supply_chain::{
+    disclosure::{self, DisclosureError},
+    jwt::Jwt,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Number of random bytes of a salt.
+const SALT_LENGTH: usize = 16;
+
+/// Claims with some of them concealed, and the disclosures of the concealed claims.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcealedClaims {
+    /// The claims to sign as audit JWT, with the digests of the concealed claims.
+    pub claims: Map<String, Value>,
+    /// The disclosures of the concealed claims, by claim name.
+    pub disclosures: Map<String, Value>,
+}
+
+/// Conceal the claims with the given names, each with a random salt.
+pub fn conceal(
+    mut claims: Map<String, Value>,
+    names: &[String],
+) -> Result<ConcealedClaims, String> {
+    let mut disclosures = Map::new();
+    for name in names {
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let encoded = disclosure::conceal(&mut claims, name, &salt)
+            .ok_or_else(|| format!("There is no claim `{}` to conceal.", name))?;
+        disclosures.insert(name.clone(), Value::String(encoded));
+    }
+    Ok(ConcealedClaims { claims, disclosures })
+}
+
+/// Check the disclosures of a presentation against the digests in its JWT. Returns the JWT and
+/// the claims the disclosures reveal. The signature of the JWT is not verified.
+pub fn verify(presentation: &str) -> Result<(&str, Map<String, Value>), String> {
+    let (jwt, disclosures) = disclosure::split_presentation(presentation);
+    let claims = Jwt::decode(jwt).map_err(|_| "The JWT is malformed.".to_string())?.claims;
+    let disclosed = disclosure::disclose(&claims, &disclosures).map_err(|error| match error {
+        DisclosureError::Malformed => "A disclosure or the `_sd` claim is malformed.",
+        DisclosureError::UnsupportedAlgorithm => "The digests are not SHA-256 digests.",
+        DisclosureError::UnknownDigest => "A disclosure does not match any digest of the JWT.",
+        DisclosureError::DuplicateClaim => "A disclosure reveals a claim twice.",
+    })?;
+    Ok((jwt, disclosed))
+}
@@ -0,0 +1,177 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Local store of documents that are attached to audits, served in the `supplyChain`
+//! namespace. Documents are stored as files named by their CID, such that the CIDs that audits
+//! reference on-chain can be looked up and checked against the content.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_supply_chain::cid;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+
+/// Upper bound of the size of a document, in bytes.
+pub const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Error code of failed document store operations.
+const STORE_ERROR: i64 = 2;
+
+/// Documents stored in a directory of the node.
+pub struct DocumentStore {
+    path: PathBuf,
+}
+
+impl DocumentStore {
+    /// Open the store in the given directory, creating the directory if it doesn't exist.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    /// The directory the documents are stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Store a document and return its CID. Storing a document twice keeps a single copy.
+    pub fn put(&self, content: &[u8]) -> io::Result<String> {
+        let cid = cid::cid(content);
+        let path = self.path.join(&cid);
+        if !path.exists() {
+            // Write to a temporary file first, such that no partial document is ever served.
+            let partial = self.path.join(format!("{}.partial", cid));
+            fs::write(&partial, content)?;
+            fs::rename(&partial, &path)?;
+        }
+        Ok(cid)
+    }
+
+    /// The document with the given CID, unless it isn't stored.
+    pub fn get(&self, cid: &str) -> io::Result<Option<Vec<u8>>> {
+        // Only well-formed CIDs name files of the store.
+        if cid::digest(cid).is_none() {
+            return Ok(None);
+        }
+        match fs::read(self.path.join(cid)) {
+            Ok(content) => Ok(Some(content)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Check the local copy of a document against its reference.
+    pub fn verify(&self, document: DocumentDetails) -> Result<DocumentVerification> {
+        let status = match self.get(&document.cid).map_err(store_error)? {
+            None => DocumentStatus::Missing,
+            Some(content)
+                if content.len() as u64 == document.size && cid::cid(&content) == document.cid =>
+            {
+                DocumentStatus::Verified
+            }
+            Some(_) => DocumentStatus::Mismatch,
+        };
+        Ok(DocumentVerification { document, status })
+    }
+}
+
+/// A reference to a stored document, as it is attached to audits.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDetails {
+    /// Content identifier of the document.
+    pub cid: String,
+    /// Size of the document in bytes.
+    pub size: u64,
+    /// Media type of the document.
+    pub media_type: String,
+}
+
+/// Whether the local copy of a document matches the reference that is stored on-chain.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentStatus {
+    /// The local copy has the referenced hash and size.
+    Verified,
+    /// The document is not stored locally.
+    Missing,
+    /// The local copy differs from the reference, e.g. because it was corrupted.
+    Mismatch,
+}
+
+/// A document attached to an audit and the state of its local copy.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentVerification {
+    /// The reference stored on-chain.
+    pub document: DocumentDetails,
+    /// Whether the local copy matches the reference.
+    pub status: DocumentStatus,
+}
+
+/// Document store RPC methods.
+#[rpc]
+pub trait DocumentApi {
+    /// Store a document in the node's document store. Returns the reference to attach to an
+    /// audit with `attach_document`.
+    #[rpc(name = "supplyChain_uploadDocument")]
+    fn upload_document(&self, content: Bytes, media_type: String) -> Result<DocumentDetails>;
+
+    /// The document with the given CID, unless the node doesn't store it.
+    #[rpc(name = "supplyChain_document")]
+    fn document(&self, cid: String) -> Result<Option<Bytes>>;
+}
+
+/// Implements the [`DocumentApi`] RPC trait for storing and fetching documents.
+pub struct Documents {
+    store: Arc<DocumentStore>,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl Documents {
+    /// Create new `Documents` serving the given store. Uploads are unsafe calls, since they
+    /// write to the node's disk.
+    pub fn new(store: Arc<DocumentStore>, deny_unsafe: DenyUnsafe) -> Self {
+        Self { store, deny_unsafe }
+    }
+}
+
+impl DocumentApi for Documents {
+    fn upload_document(&self, content: Bytes, media_type: String) -> Result<DocumentDetails> {
+        self.deny_unsafe.check_if_safe()?;
+        if content.len() > MAX_DOCUMENT_SIZE {
+            return Err(RpcError {
+                code: ErrorCode::InvalidParams,
+                message: format!("Documents must not exceed {} bytes.", MAX_DOCUMENT_SIZE),
+                data: None,
+            });
+        }
+        let cid = self.store.put(&content).map_err(store_error)?;
+        Ok(DocumentDetails { cid, size: content.len() as u64, media_type })
+    }
+
+    fn document(&self, cid: String) -> Result<Option<Bytes>> {
+        let content = self.store.get(&cid).map_err(store_error)?;
+        Ok(content.map(Into::into))
+    }
+}
+
+fn store_error(error: io::Error) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(STORE_ERROR),
+        message: "Unable to access the document store.".into(),
+        data: Some(error.to_string().into()),
+    }
+}
@@ -0,0 +1,380 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Sealing and opening of confidential audit data on the client. The plaintext never passes
+//! through a node: auditors seal it for the recipients and submit it with
+//! `audit_confidential`, recipients open it with the secret key of their registered key.
+//!
+//! The content is the 32 byte salt of the commitment followed by the plaintext, such that
+//! recipients can disclose the plaintext. It is encrypted with a random 256 bit content key
+//! and a random nonce, without associated data. The ciphertext is the nonce followed by the
+//! encrypted content and its authentication tag.
+//!
+//! The content key is wrapped for each recipient with an ephemeral key pair. The shared secret
+//! of the ephemeral key and the recipient's registered key is
+//!
+//! * `X25519`: X25519 of the ephemeral scalar and the Montgomery form of the Ed25519 key. The
+//!   recipient's scalar is the clamped first half of the SHA-512 hash of its Ed25519 seed.
+//! * `Ristretto255`: the encoded product of the ephemeral scalar and the Sr25519 key.
+//! * `Secp256k1`: the x-coordinate of the product of the ephemeral scalar and the key.
+//!
+//! The wrapping key is derived from the shared secret with HKDF-SHA-256, salted with the
+//! ephemeral public key followed by the recipient's public key in the same encoding, with the
+//! info `GreenAureus/KeyWrapping/v1`. The wrapped key is the ephemeral public key, a random
+//! 24 byte nonce and the content key encrypted with XChaCha20-Poly1305 under the wrapping
+//! key, with its authentication tag.
+
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    XChaCha20Poly1305,
+};
+use curve25519_dalek::{
+    constants::{RISTRETTO_BASEPOINT_POINT, X25519_BASEPOINT},
+    edwards::CompressedEdwardsY,
+    ristretto::CompressedRistretto,
+    scalar::Scalar,
+};
+use hkdf::Hkdf;
+use pallet_supply_chain::types::{ContentCipher, KeyWrapping, VerificationKey, WrappedKey};
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{ConfidentialData, ConfidentialDetails};
+
+/// Length of the content key, of wrapping keys and of the salt of the commitment.
+const KEY_LENGTH: usize = 32;
+/// Length of the nonce of a wrapped key.
+const WRAPPING_NONCE_LENGTH: usize = 24;
+/// HKDF info of wrapping keys.
+const WRAPPING_INFO: &[u8] = b"GreenAureus/KeyWrapping/v1";
+
+/// A recipient of confidential data: an organization and one of its registered keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recipient<AccountId> {
+    /// Account of the organization.
+    pub account: AccountId,
+    /// Id of the key as registered for the organization. The key must be active when the data
+    /// is submitted.
+    pub kid: String,
+    /// The registered key.
+    pub key: VerificationKey,
+}
+
+/// The secret key of a recipient's registered key.
+#[derive(Clone)]
+pub enum SecretKey {
+    /// The seed of an Ed25519 key.
+    Ed25519([u8; 32]),
+    /// The secret scalar of an Sr25519 key, i.e. the first half of its raw secret key.
+    Sr25519([u8; 32]),
+    /// A secp256k1 secret key.
+    Secp256k1([u8; 32]),
+}
+
+/// Confidential data sealed for its recipients. Everything but the salt is passed to
+/// `audit_confidential`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sealed<AccountId> {
+    /// The cipher the content is encrypted with.
+    pub cipher: ContentCipher,
+    /// The nonce, the encrypted content and its authentication tag.
+    pub ciphertext: Vec<u8>,
+    /// The content key, wrapped for each recipient.
+    pub recipients: Vec<WrappedKey<AccountId, String>>,
+    /// Commitment to the plaintext.
+    pub commitment: [u8; 32],
+    /// The salt of the commitment. The auditor keeps it to disclose the plaintext later.
+    pub salt: [u8; 32],
+}
+
+impl<AccountId> Sealed<AccountId> {
+    /// The `commitment` claim the audit JWT must contain.
+    pub fn commitment_claim(&self) -> String {
+        let hex: String = self
+            .commitment
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("0x{}", hex)
+    }
+}
+
+/// Seal a plaintext for the recipients, with a random content key and salt.
+pub fn seal<AccountId: Clone>(
+    plaintext: &[u8],
+    cipher: ContentCipher,
+    recipients: &[Recipient<AccountId>],
+) -> Result<Sealed<AccountId>, String> {
+    seal_with(&mut rand::thread_rng(), plaintext, cipher, recipients)
+}
+
+/// Open confidential data as `recipient`. Returns the salt and the plaintext, after checking
+/// them against the commitment.
+pub fn open<AccountId: PartialEq>(
+    data: &ConfidentialDetails<AccountId>,
+    recipient: &AccountId,
+    secret: &SecretKey,
+) -> Result<([u8; 32], Vec<u8>), String> {
+    let wrapped = data
+        .recipients
+        .iter()
+        .find(|wrapped| wrapped.recipient == *recipient)
+        .ok_or_else(|| "The data is not sealed for the recipient.".to_string())?;
+    let content_key = unwrap(wrapped.wrapping, &wrapped.wrapped_key, secret)?;
+    let content = decrypt(data.cipher, &content_key, &data.ciphertext)?;
+    if content.len() < KEY_LENGTH {
+        return Err("The content has no salt.".into());
+    }
+    let (salt, plaintext) = content.split_at(KEY_LENGTH);
+    if ConfidentialData::<()>::commitment_of(salt, plaintext)[..] != data.commitment[..] {
+        return Err("The plaintext does not match the commitment.".into());
+    }
+    let mut disclosed_salt = [0u8; KEY_LENGTH];
+    disclosed_salt.copy_from_slice(salt);
+    Ok((disclosed_salt, plaintext.to_vec()))
+}
+
+/// Seal a plaintext, taking the content key, the salt, the nonces and the ephemeral keys from
+/// `rng` in this order.
+pub(crate) fn seal_with<AccountId: Clone>(
+    rng: &mut impl RngCore,
+    plaintext: &[u8],
+    cipher: ContentCipher,
+    recipients: &[Recipient<AccountId>],
+) -> Result<Sealed<AccountId>, String> {
+    let mut content_key = [0u8; KEY_LENGTH];
+    rng.fill_bytes(&mut content_key);
+    let mut salt = [0u8; KEY_LENGTH];
+    rng.fill_bytes(&mut salt);
+    let mut content = salt.to_vec();
+    content.extend_from_slice(plaintext);
+    let ciphertext = encrypt(rng, cipher, &content_key, &content)?;
+
+    let recipients = recipients
+        .iter()
+        .map(|recipient| {
+            let (wrapping, wrapped_key) = wrap(rng, &recipient.key, &content_key)?;
+            Ok(WrappedKey {
+                recipient: recipient.account.clone(),
+                kid: recipient.kid.clone(),
+                wrapping,
+                wrapped_key: wrapped_key
+                    .try_into()
+                    .map_err(|_| "The wrapped key is too long.".to_string())?,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(Sealed {
+        cipher,
+        ciphertext,
+        recipients,
+        commitment: ConfidentialData::<()>::commitment_of(&salt, plaintext),
+        salt,
+    })
+}
+
+/// Encrypt the content, prefixed with a random nonce.
+fn encrypt(
+    rng: &mut impl RngCore,
+    cipher: ContentCipher,
+    key: &[u8; KEY_LENGTH],
+    content: &[u8],
+) -> Result<Vec<u8>, String> {
+    let mut nonce = vec![0u8; nonce_length(cipher)];
+    rng.fill_bytes(&mut nonce);
+    let key = GenericArray::from_slice(key);
+    let encrypted = match cipher {
+        ContentCipher::XChaCha20Poly1305 => {
+            XChaCha20Poly1305::new(key).encrypt(GenericArray::from_slice(&nonce), content)
+        }
+        ContentCipher::Aes256Gcm => {
+            Aes256Gcm::new(key).encrypt(GenericArray::from_slice(&nonce), content)
+        }
+    }
+    .map_err(|_| "The content can't be encrypted.".to_string())?;
+    nonce.extend_from_slice(&encrypted);
+    Ok(nonce)
+}
+
+/// Decrypt a ciphertext that is prefixed with its nonce.
+fn decrypt(cipher: ContentCipher, key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    if ciphertext.len() < nonce_length(cipher) {
+        return Err("The ciphertext has no nonce.".into());
+    }
+    let (nonce, encrypted) = ciphertext.split_at(nonce_length(cipher));
+    let key = GenericArray::from_slice(key);
+    match cipher {
+        ContentCipher::XChaCha20Poly1305 => {
+            XChaCha20Poly1305::new(key).decrypt(GenericArray::from_slice(nonce), encrypted)
+        }
+        ContentCipher::Aes256Gcm => {
+            Aes256Gcm::new(key).decrypt(GenericArray::from_slice(nonce), encrypted)
+        }
+    }
+    .map_err(|_| "The content can't be decrypted, it was altered or sealed for another key.".into())
+}
+
+fn nonce_length(cipher: ContentCipher) -> usize {
+    match cipher {
+        ContentCipher::XChaCha20Poly1305 => 24,
+        ContentCipher::Aes256Gcm => 12,
+    }
+}
+
+/// Wrap the content key for a recipient's key with a new ephemeral key.
+fn wrap(
+    rng: &mut impl RngCore,
+    key: &VerificationKey,
+    content_key: &[u8; KEY_LENGTH],
+) -> Result<(KeyWrapping, Vec<u8>), String> {
+    let invalid = || "The recipient's key is not a valid public key.".to_string();
+    let (wrapping, ephemeral, recipient, shared) = match key {
+        VerificationKey::Ed25519(key) => {
+            let recipient = CompressedEdwardsY(*key)
+                .decompress()
+                .ok_or_else(invalid)?
+                .to_montgomery();
+            let mut secret = [0u8; 32];
+            rng.fill_bytes(&mut secret);
+            let secret = clamp(secret);
+            let ephemeral = X25519_BASEPOINT * secret;
+            let shared = recipient * secret;
+            let shared = shared.to_bytes().to_vec();
+            (
+                KeyWrapping::X25519,
+                ephemeral.to_bytes().to_vec(),
+                recipient.to_bytes().to_vec(),
+                shared,
+            )
+        }
+        VerificationKey::Sr25519(key) => {
+            let recipient = CompressedRistretto(*key).decompress().ok_or_else(invalid)?;
+            let mut secret = [0u8; 64];
+            rng.fill_bytes(&mut secret);
+            let secret = Scalar::from_bytes_mod_order_wide(&secret);
+            let ephemeral = (RISTRETTO_BASEPOINT_POINT * secret).compress();
+            let shared = (recipient * secret).compress();
+            (
+                KeyWrapping::Ristretto255,
+                ephemeral.to_bytes().to_vec(),
+                key.to_vec(),
+                shared.to_bytes().to_vec(),
+            )
+        }
+        VerificationKey::Secp256k1(key) => {
+            let mut shared =
+                libsecp256k1::PublicKey::parse_compressed(key).map_err(|_| invalid())?;
+            let secret = loop {
+                let mut secret = [0u8; 32];
+                rng.fill_bytes(&mut secret);
+                if let Ok(secret) = libsecp256k1::SecretKey::parse(&secret) {
+                    break secret;
+                }
+            };
+            let ephemeral = libsecp256k1::PublicKey::from_secret_key(&secret);
+            shared.tweak_mul_assign(&secret).map_err(|_| invalid())?;
+            (
+                KeyWrapping::Secp256k1,
+                ephemeral.serialize_compressed().to_vec(),
+                key.to_vec(),
+                shared.serialize_compressed()[1..].to_vec(),
+            )
+        }
+    };
+    if shared.iter().all(|byte| *byte == 0) {
+        return Err(invalid());
+    }
+
+    let wrapping_key = wrapping_key(&shared, &ephemeral, &recipient);
+    let mut nonce = [0u8; WRAPPING_NONCE_LENGTH];
+    rng.fill_bytes(&mut nonce);
+    let encrypted = XChaCha20Poly1305::new(GenericArray::from_slice(&wrapping_key))
+        .encrypt(GenericArray::from_slice(&nonce), &content_key[..])
+        .map_err(|_| "The content key can't be wrapped.".to_string())?;
+    Ok((wrapping, [ephemeral, nonce.to_vec(), encrypted].concat()))
+}
+
+/// Unwrap a content key with the secret key it was wrapped for.
+fn unwrap(
+    wrapping: KeyWrapping,
+    wrapped_key: &[u8],
+    secret: &SecretKey,
+) -> Result<Vec<u8>, String> {
+    let malformed = || "The wrapped key is malformed.".to_string();
+    if wrapped_key.len() != wrapping.wrapped_key_length() {
+        return Err(malformed());
+    }
+    let ephemeral_length = wrapped_key.len() - WRAPPING_NONCE_LENGTH - KEY_LENGTH - 16;
+    let (ephemeral, rest) = wrapped_key.split_at(ephemeral_length);
+    let (nonce, encrypted) = rest.split_at(WRAPPING_NONCE_LENGTH);
+
+    let (recipient, shared) = match (wrapping, secret) {
+        (KeyWrapping::X25519, SecretKey::Ed25519(seed)) => {
+            let mut scalar = [0u8; 32];
+            scalar.copy_from_slice(&Sha512::digest(seed)[..32]);
+            let scalar = clamp(scalar);
+            let mut point = [0u8; 32];
+            point.copy_from_slice(ephemeral);
+            let shared = curve25519_dalek::montgomery::MontgomeryPoint(point) * scalar;
+            (
+                (X25519_BASEPOINT * scalar).to_bytes().to_vec(),
+                shared.to_bytes().to_vec(),
+            )
+        }
+        (KeyWrapping::Ristretto255, SecretKey::Sr25519(scalar)) => {
+            let scalar = Scalar::from_bytes_mod_order(*scalar);
+            let point = CompressedRistretto::from_slice(ephemeral)
+                .decompress()
+                .ok_or_else(malformed)?;
+            let recipient = (RISTRETTO_BASEPOINT_POINT * scalar).compress();
+            (
+                recipient.to_bytes().to_vec(),
+                (point * scalar).compress().to_bytes().to_vec(),
+            )
+        }
+        (KeyWrapping::Secp256k1, SecretKey::Secp256k1(secret)) => {
+            let secret = libsecp256k1::SecretKey::parse(secret)
+                .map_err(|_| "The secret key is not a valid secp256k1 key.".to_string())?;
+            let mut point = [0u8; 33];
+            point.copy_from_slice(ephemeral);
+            let mut shared =
+                libsecp256k1::PublicKey::parse_compressed(&point).map_err(|_| malformed())?;
+            shared.tweak_mul_assign(&secret).map_err(|_| malformed())?;
+            let recipient = libsecp256k1::PublicKey::from_secret_key(&secret);
+            (
+                recipient.serialize_compressed().to_vec(),
+                shared.serialize_compressed()[1..].to_vec(),
+            )
+        }
+        _ => return Err("The secret key does not fit the key wrapping.".into()),
+    };
+
+    let wrapping_key = wrapping_key(&shared, ephemeral, &recipient);
+    XChaCha20Poly1305::new(GenericArray::from_slice(&wrapping_key))
+        .decrypt(GenericArray::from_slice(nonce), encrypted)
+        .map_err(|_| "The content key can't be unwrapped with the secret key.".into())
+}
+
+/// Derive the wrapping key from the shared secret of a key agreement.
+fn wrapping_key(shared: &[u8], ephemeral: &[u8], recipient: &[u8]) -> [u8; KEY_LENGTH] {
+    let salt = [ephemeral, recipient].concat();
+    let mut key = [0u8; KEY_LENGTH];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(WRAPPING_INFO, &mut key)
+        .expect("the key is shorter than 255 hashes; qed");
+    key
+}
+
+/// The X25519 scalar of 32 random bytes.
+fn clamp(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bits(bytes)
+}
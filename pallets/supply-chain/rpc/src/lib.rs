@@ -0,0 +1,887 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! JSON-RPC interface of the supply chain, served in the `supplyChain` namespace.
+//!
+//! The methods wrap the `SupplyChainApi` runtime API and return plain JSON. Ids and names are
+//! returned as text and the header and claims of audit JWTs are decoded, such that clients
+//! don't need a SCALE decoder.
+//!
+//! The [`documents`] module serves the node's store of documents that are attached to audits.
+//! The [`disclosures`] module conceals audit claims and checks their disclosures.
+//! The [`envelope`] module seals confidential audit data for its recipients and opens it.
+//! The [`credentials`] module represents authorities as DID documents and audits as Verifiable
+//! Credentials.
+
+use std::{convert::Infallible, fmt::Debug, marker::PhantomData, sync::Arc};
+
+pub mod credentials;
+pub mod disclosures;
+pub mod documents;
+pub mod envelope;
+
+#[cfg(test)]
+mod tests;
+
+use codec::Codec;
+use credentials::{DidDocument, VerifiableCredential};
+use documents::{DocumentDetails, DocumentStore, DocumentVerification};
+use frame_support::Parameter;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_supply_chain::{
+    did,
+    jwt::{Header, Jwt},
+    types::{
+        self, AuditStatus, AuthorityStatus, BoundedString, ContentCipher, KeyStatus, KeyWrapping,
+        MaxAuditSize, MaxAuditorNameLength, MaxAuthorityKeys, MaxCiphertextSize,
+        MaxComponentIdLength, MaxComponents, MaxKeyIdLength, MaxPayloadIdLength, MaxRecipients,
+        MaxWebsiteLength, PartUsage, Quantity, Role, VerificationKey,
+    },
+};
+pub use pallet_supply_chain_runtime_api::SupplyChainApi as SupplyChainRuntimeApi;
+use pallet_supply_chain_runtime_api::{Direction, ProvenanceNode};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{crypto::default_ss58_version, Bytes};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{AtLeast32Bit, Block as BlockT, UniqueSaturatedInto},
+};
+
+// The RPC serves runtimes that configure the pallet with the default limits of
+// `pallet_supply_chain::types`.
+
+/// The id of a component as it is passed to the runtime API.
+pub type ComponentId = BoundedString<MaxComponentIdLength>;
+/// A component as it is returned by the runtime API.
+pub type Component = types::Component<MaxComponentIdLength, MaxComponents>;
+/// An audit as it is returned by the runtime API.
+pub type Audit<AccountId, Moment> =
+    types::Audit<AccountId, Moment, MaxAuditorNameLength, MaxKeyIdLength, MaxAuditSize>;
+/// The structured payload of an audit as it is returned by the runtime API.
+pub type AuditPayload<Moment> = types::AuditPayload<Moment, BoundedString<MaxPayloadIdLength>>;
+/// The confidential data of an audit as it is returned by the runtime API.
+pub type ConfidentialData<AccountId> =
+    types::ConfidentialData<AccountId, MaxKeyIdLength, MaxRecipients, MaxCiphertextSize>;
+/// An authorized organization as it is returned by the runtime API.
+pub type Authority =
+    types::AuthorityInfo<MaxAuditorNameLength, MaxKeyIdLength, MaxAuthorityKeys, MaxWebsiteLength>;
+
+/// Error code of failed runtime API calls.
+const RUNTIME_ERROR: i64 = 1;
+/// Depth up to which the assembly graph is traversed, unless the caller passes `maxDepth`.
+const DEFAULT_MAX_DEPTH: u32 = 16;
+/// Number of items a query returns, unless the caller passes `limit`.
+const DEFAULT_LIMIT: u32 = 100;
+/// Number of audits a traversal returns for each component, unless the caller passes
+/// `auditLimit`.
+const DEFAULT_AUDIT_LIMIT: u32 = 10;
+/// Upper bound of the number of items a single query returns.
+const MAX_LIMIT: u32 = 1000;
+
+/// The parts of a component and the components it is part of.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDetails {
+    /// Components that are part of the component.
+    pub parts: Vec<PartDetails>,
+    /// Components the component is part of.
+    pub used_in: Vec<String>,
+    /// How much of the component was produced, if that was recorded.
+    pub quantity: Option<Quantity>,
+    /// How much of the component went into assemblies, in milligrams, millilitres or pieces.
+    pub consumed: u128,
+}
+
+/// A part of a component.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartDetails {
+    /// Id of the part.
+    pub id: String,
+    /// How much of the part went into the component, if that was recorded.
+    pub usage: Option<PartUsage>,
+}
+
+impl From<Component> for ComponentDetails {
+    fn from(component: Component) -> Self {
+        Self {
+            parts: component
+                .components
+                .iter()
+                .map(|(id, usage)| PartDetails { id: text(id), usage: *usage })
+                .collect(),
+            used_in: component.component_of.iter().map(|id| text(id)).collect(),
+            quantity: component.quantity,
+            consumed: component.consumed,
+        }
+    }
+}
+
+/// An audit with its JWT decoded.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditDetails<AccountId, Moment> {
+    /// Account of the auditor organization.
+    pub issuer: AccountId,
+    /// Name of the auditor organization.
+    pub auditor: String,
+    /// Timestamp of the block the audit was added in.
+    pub timestamp: Moment,
+    /// Id of the auditor's key the JWT was verified against.
+    pub key_id: String,
+    /// Whether the audit is still valid.
+    pub status: AuditStatus,
+    /// The JWT as it was submitted.
+    pub jwt: String,
+    /// The decoded JOSE header, unless the JWT can't be decoded.
+    pub header: Option<Header>,
+    /// The decoded claims, unless the JWT can't be decoded.
+    pub claims: Option<Map<String, Value>>,
+}
+
+impl<AccountId, Moment> From<Audit<AccountId, Moment>> for AuditDetails<AccountId, Moment>
+where
+    AccountId: Clone + Default + Eq + Debug,
+    Moment: AtLeast32Bit + Parameter + Default + Copy,
+{
+    fn from(audit: Audit<AccountId, Moment>) -> Self {
+        let jwt = text(&audit.audit_data);
+        // Audits that were added before JWTs were verified may not be decodable.
+        let (header, claims) = match Jwt::decode(&jwt) {
+            Ok(decoded) => (Some(decoded.header), Some(decoded.claims)),
+            Err(_) => (None, None),
+        };
+
+        Self {
+            issuer: audit.issuer,
+            auditor: text(&audit.auditor),
+            timestamp: audit.timestamp,
+            key_id: text(&audit.key_id),
+            status: audit.status,
+            jwt,
+            header,
+            claims,
+        }
+    }
+}
+
+/// An audit together with the component it was added to.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocatedAudit<AccountId, Moment> {
+    /// Id of the component the audit was added to.
+    pub component_id: String,
+    /// Index of the audit within the component's audits.
+    pub index: u32,
+    /// The audit.
+    pub audit: AuditDetails<AccountId, Moment>,
+}
+
+/// A component reached while traversing the assembly graph, together with a page of its
+/// audits.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceNodeDetails<AccountId, Moment> {
+    /// Id of the component.
+    pub id: String,
+    /// Number of assembly steps between the component and the component the traversal
+    /// started at.
+    pub depth: u32,
+    /// The component through which the component was reached first.
+    pub parent: Option<String>,
+    /// The first audits of the component, at most `auditLimit`.
+    pub audits: Vec<AuditDetails<AccountId, Moment>>,
+    /// Number of audits of the component. `supplyChain_audits` returns the audits beyond
+    /// `audits` page by page.
+    pub audit_count: u32,
+}
+
+impl<AccountId, Moment> From<ProvenanceNode<ComponentId, Audit<AccountId, Moment>>>
+    for ProvenanceNodeDetails<AccountId, Moment>
+where
+    AccountId: Clone + Default + Eq + Debug,
+    Moment: AtLeast32Bit + Parameter + Default + Copy,
+{
+    fn from(node: ProvenanceNode<ComponentId, Audit<AccountId, Moment>>) -> Self {
+        Self {
+            id: text(&node.id),
+            depth: node.depth,
+            parent: node.parent.map(|parent| text(&parent)),
+            audits: node.audits.into_iter().map(Into::into).collect(),
+            audit_count: node.audit_count,
+        }
+    }
+}
+
+/// A component certified under a standard.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertifiedComponent {
+    /// Id of the component.
+    pub component_id: String,
+    /// Index of the audit that certifies the component.
+    pub index: u32,
+}
+
+/// The encrypted confidential data of an audit.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidentialDetails<AccountId> {
+    /// The cipher the content is encrypted with.
+    pub cipher: ContentCipher,
+    /// The nonce of the cipher followed by the encrypted content and its authentication tag.
+    pub ciphertext: Bytes,
+    /// The content key, wrapped for each recipient.
+    pub recipients: Vec<RecipientDetails<AccountId>>,
+    /// Commitment to the plaintext.
+    pub commitment: Bytes,
+}
+
+/// The content key of confidential data, wrapped for one recipient.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipientDetails<AccountId> {
+    /// Account of the recipient organization.
+    pub recipient: AccountId,
+    /// Id of the recipient's key the content key is wrapped with.
+    pub kid: String,
+    /// How the content key is wrapped.
+    pub wrapping: KeyWrapping,
+    /// The ephemeral public key, the nonce and the encrypted content key.
+    pub wrapped_key: Bytes,
+}
+
+impl<AccountId> From<ConfidentialData<AccountId>> for ConfidentialDetails<AccountId>
+where
+    AccountId: Clone + Eq + Debug,
+{
+    fn from(data: ConfidentialData<AccountId>) -> Self {
+        Self {
+            cipher: data.cipher,
+            ciphertext: data.ciphertext.into_inner().into(),
+            recipients: data
+                .recipients
+                .into_iter()
+                .map(|wrapped| RecipientDetails {
+                    recipient: wrapped.recipient,
+                    kid: text(&wrapped.kid),
+                    wrapping: wrapped.wrapping,
+                    wrapped_key: wrapped.wrapped_key.into_inner().into(),
+                })
+                .collect(),
+            commitment: data.commitment.to_vec().into(),
+        }
+    }
+}
+
+/// An authorized organization.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorityDetails {
+    /// Display name of the organization.
+    pub name: String,
+    /// Website of the organization.
+    pub website: Option<String>,
+    /// Legal Entity Identifier (ISO 17442) of the organization.
+    pub lei: Option<String>,
+    /// Whether the organization is currently allowed to add audits.
+    pub status: AuthorityStatus,
+    /// The roles granted to the organization.
+    pub roles: Vec<Role>,
+    /// Keys the organization's audit JWTs are verified against.
+    pub keys: Vec<KeyDetails>,
+}
+
+impl From<Authority> for AuthorityDetails {
+    fn from(info: Authority) -> Self {
+        Self {
+            name: text(&info.name),
+            website: info.website.map(|website| text(&website)),
+            lei: info.lei.map(|lei| text(&lei)),
+            status: info.status,
+            roles: info.roles.iter().collect(),
+            keys: info
+                .keys
+                .iter()
+                .map(|key| {
+                    let (algorithm, public_key) = match key.key {
+                        VerificationKey::Ed25519(public) => ("EdDSA", public.to_vec()),
+                        VerificationKey::Sr25519(public) => ("Sr25519", public.to_vec()),
+                        VerificationKey::Secp256k1(public) => ("ES256K", public.to_vec()),
+                    };
+                    KeyDetails {
+                        kid: text(&key.kid),
+                        algorithm: algorithm.into(),
+                        public_key: public_key.into(),
+                        status: key.status,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A verification key of an organization.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyDetails {
+    /// Key id, referenced by the `kid` header of audit JWTs.
+    pub kid: String,
+    /// The `alg` of JWTs that are verified against the key.
+    pub algorithm: String,
+    /// The public key.
+    pub public_key: Bytes,
+    /// Whether the key may still be used to verify new audits.
+    pub status: KeyStatus,
+}
+
+/// Supply chain RPC methods.
+#[rpc]
+pub trait SupplyChainApi<BlockHash, AccountId, Hash, Moment> {
+    /// The component with the given id, if it was created or audited.
+    #[rpc(name = "supplyChain_component")]
+    fn component(&self, id: String, at: Option<BlockHash>) -> Result<Option<ComponentDetails>>;
+
+    /// At most `limit` audits of a component, starting at index `offset`. Without `limit`,
+    /// a page of the default size is returned.
+    #[rpc(name = "supplyChain_audits")]
+    fn audits(
+        &self,
+        id: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<AuditDetails<AccountId, Moment>>>;
+
+    /// The audit with the given id and the component it was added to.
+    #[rpc(name = "supplyChain_auditById")]
+    fn audit_by_id(
+        &self,
+        id: Hash,
+        at: Option<BlockHash>,
+    ) -> Result<Option<LocatedAudit<AccountId, Moment>>>;
+
+    /// The authorized organization with the given account.
+    #[rpc(name = "supplyChain_authority")]
+    fn authority(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<AuthorityDetails>>;
+
+    /// The components that were assembled into a component.
+    #[rpc(name = "supplyChain_partsOf")]
+    fn parts_of(&self, id: String, at: Option<BlockHash>) -> Result<Vec<String>>;
+
+    /// The components a component was assembled into.
+    #[rpc(name = "supplyChain_usedIn")]
+    fn used_in(&self, id: String, at: Option<BlockHash>) -> Result<Vec<String>>;
+
+    /// Whether a component was created or audited.
+    #[rpc(name = "supplyChain_exists")]
+    fn exists(&self, id: String, at: Option<BlockHash>) -> Result<bool>;
+
+    /// The bill of materials of a component: the component and, recursively, the parts it was
+    /// assembled from, breadth first and with the first `auditLimit` of their audits.
+    #[rpc(name = "supplyChain_billOfMaterials")]
+    fn bill_of_materials(
+        &self,
+        id: String,
+        max_depth: Option<u32>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        audit_limit: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<ProvenanceNodeDetails<AccountId, Moment>>>;
+
+    /// The where-used tree of a component: the component and, recursively, the components it
+    /// was assembled into, breadth first and with the first `auditLimit` of their audits.
+    #[rpc(name = "supplyChain_whereUsed")]
+    fn where_used(
+        &self,
+        id: String,
+        max_depth: Option<u32>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        audit_limit: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<ProvenanceNodeDetails<AccountId, Moment>>>;
+
+    /// The structured payload of the audit at `index` of a component, if the audit was added
+    /// with one.
+    #[rpc(name = "supplyChain_auditPayload")]
+    fn audit_payload(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Option<types::AuditPayload<Moment, String>>>;
+
+    /// The components whose latest audit for a standard passed and is valid at `valid_at`,
+    /// a timestamp in milliseconds.
+    #[rpc(name = "supplyChain_certifiedComponents")]
+    fn certified_components(
+        &self,
+        standard: String,
+        valid_at: Moment,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<CertifiedComponent>>;
+
+    /// The documents attached to the audit at `index` of a component, each checked against
+    /// the copy in the node's document store.
+    #[rpc(name = "supplyChain_verifyDocuments")]
+    fn verify_documents(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<DocumentVerification>>;
+
+    /// The encrypted confidential data of the audit at `index` of a component, if the audit
+    /// was added with some. Recipients decrypt it off-chain with their keys.
+    #[rpc(name = "supplyChain_confidentialData")]
+    fn confidential_data(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ConfidentialDetails<AccountId>>>;
+
+    /// Check a presentation, the JWT of the audit at `index` of a component followed by
+    /// disclosures of some of its concealed claims, `<jwt>~<disclosure>~...~`. Returns the
+    /// disclosed claims. Presentations of revoked or superseded audits are rejected.
+    #[rpc(name = "supplyChain_verifyPresentation")]
+    fn verify_presentation(
+        &self,
+        id: String,
+        index: u32,
+        presentation: String,
+        at: Option<BlockHash>,
+    ) -> Result<Map<String, Value>>;
+
+    /// Whether disclosed confidential data, i.e. the salt and the plaintext, matches the
+    /// commitment of the audit at `index` of a component. False if the audit has no
+    /// confidential data.
+    #[rpc(name = "supplyChain_verifyDisclosure")]
+    fn verify_disclosure(
+        &self,
+        id: String,
+        index: u32,
+        salt: Bytes,
+        plaintext: Bytes,
+        at: Option<BlockHash>,
+    ) -> Result<bool>;
+
+    /// The DID document of the authority with the given DID, `did:greenaureus:<address>`.
+    #[rpc(name = "supplyChain_resolveDid")]
+    fn resolve_did(&self, did: String, at: Option<BlockHash>) -> Result<Option<DidDocument>>;
+
+    /// The audit at `index` of a component as W3C Verifiable Credential, with the audit JWT
+    /// as proof.
+    #[rpc(name = "supplyChain_auditCredential")]
+    fn audit_credential(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Option<VerifiableCredential>>;
+}
+
+/// Implements the [`SupplyChainApi`] RPC trait for querying the supply chain.
+pub struct SupplyChain<C, Block> {
+    client: Arc<C>,
+    documents: Arc<DocumentStore>,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> SupplyChain<C, Block> {
+    /// Create new `SupplyChain` with the given reference to the client and the store that
+    /// attached documents are checked against.
+    pub fn new(client: Arc<C>, documents: Arc<DocumentStore>) -> Self {
+        Self {
+            client,
+            documents,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> SupplyChain<C, Block>
+where
+    Block: BlockT,
+    C: HeaderBackend<Block>,
+{
+    /// The given block or, if none is given, the best block.
+    fn at(&self, at: Option<Block::Hash>) -> BlockId<Block> {
+        BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+    }
+}
+
+impl<C, Block, AccountId, Hash, Moment>
+    SupplyChainApi<<Block as BlockT>::Hash, AccountId, Hash, Moment> for SupplyChain<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: SupplyChainRuntimeApi<
+        Block,
+        AccountId,
+        Hash,
+        Moment,
+        ComponentId,
+        Component,
+        Audit<AccountId, Moment>,
+        AuditPayload<Moment>,
+        ConfidentialData<AccountId>,
+        Authority,
+    >,
+    AccountId: Codec + Clone + Default + Eq + Debug,
+    Hash: Codec,
+    Moment: AtLeast32Bit + Parameter + Default + Copy,
+{
+    fn component(
+        &self,
+        id: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ComponentDetails>> {
+        let component = self
+            .client
+            .runtime_api()
+            .component(&self.at(at), component_id(id)?)
+            .map_err(runtime_error)?;
+        Ok(component.map(Into::into))
+    }
+
+    fn audits(
+        &self,
+        id: String,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<AuditDetails<AccountId, Moment>>> {
+        let audits = self
+            .client
+            .runtime_api()
+            .audits(
+                &self.at(at),
+                component_id(id)?,
+                offset.unwrap_or_default(),
+                page_size(limit),
+            )
+            .map_err(runtime_error)?;
+        Ok(audits.into_iter().map(Into::into).collect())
+    }
+
+    fn audit_by_id(
+        &self,
+        id: Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<LocatedAudit<AccountId, Moment>>> {
+        let audit = self
+            .client
+            .runtime_api()
+            .audit_by_id(&self.at(at), id)
+            .map_err(runtime_error)?;
+        Ok(audit.map(|(component_id, index, audit)| LocatedAudit {
+            component_id: text(&component_id),
+            index,
+            audit: audit.into(),
+        }))
+    }
+
+    fn authority(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<AuthorityDetails>> {
+        let authority = self
+            .client
+            .runtime_api()
+            .authority(&self.at(at), account)
+            .map_err(runtime_error)?;
+        Ok(authority.map(Into::into))
+    }
+
+    fn parts_of(&self, id: String, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<String>> {
+        let parts = self
+            .client
+            .runtime_api()
+            .parts_of(&self.at(at), component_id(id)?)
+            .map_err(runtime_error)?;
+        Ok(parts.iter().map(|id| text(id)).collect())
+    }
+
+    fn used_in(&self, id: String, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<String>> {
+        let used_in = self
+            .client
+            .runtime_api()
+            .used_in(&self.at(at), component_id(id)?)
+            .map_err(runtime_error)?;
+        Ok(used_in.iter().map(|id| text(id)).collect())
+    }
+
+    fn exists(&self, id: String, at: Option<<Block as BlockT>::Hash>) -> Result<bool> {
+        self.client
+            .runtime_api()
+            .exists(&self.at(at), component_id(id)?)
+            .map_err(runtime_error)
+    }
+
+    fn bill_of_materials(
+        &self,
+        id: String,
+        max_depth: Option<u32>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        audit_limit: Option<u32>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<ProvenanceNodeDetails<AccountId, Moment>>> {
+        let nodes = self
+            .client
+            .runtime_api()
+            .provenance(
+                &self.at(at),
+                component_id(id)?,
+                Direction::Parts,
+                max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+                offset.unwrap_or_default(),
+                page_size(limit),
+                audit_limit.unwrap_or(DEFAULT_AUDIT_LIMIT).min(MAX_LIMIT),
+            )
+            .map_err(runtime_error)?;
+        Ok(nodes.into_iter().map(Into::into).collect())
+    }
+
+    fn where_used(
+        &self,
+        id: String,
+        max_depth: Option<u32>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        audit_limit: Option<u32>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<ProvenanceNodeDetails<AccountId, Moment>>> {
+        let nodes = self
+            .client
+            .runtime_api()
+            .provenance(
+                &self.at(at),
+                component_id(id)?,
+                Direction::UsedIn,
+                max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+                offset.unwrap_or_default(),
+                page_size(limit),
+                audit_limit.unwrap_or(DEFAULT_AUDIT_LIMIT).min(MAX_LIMIT),
+            )
+            .map_err(runtime_error)?;
+        Ok(nodes.into_iter().map(Into::into).collect())
+    }
+
+    fn audit_payload(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<types::AuditPayload<Moment, String>>> {
+        let payload = self
+            .client
+            .runtime_api()
+            .audit_payload(&self.at(at), component_id(id)?, index)
+            .map_err(runtime_error)?;
+        Ok(payload.map(|payload| {
+            let payload = payload.try_map_ids(|id| Ok::<_, Infallible>(text(&id)));
+            payload.unwrap_or_else(|never| match never {})
+        }))
+    }
+
+    fn certified_components(
+        &self,
+        standard: String,
+        valid_at: Moment,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<CertifiedComponent>> {
+        let certified = self
+            .client
+            .runtime_api()
+            .certified_components(
+                &self.at(at),
+                standard.into_bytes(),
+                valid_at,
+                offset.unwrap_or_default(),
+                page_size(limit),
+            )
+            .map_err(runtime_error)?;
+        Ok(certified
+            .into_iter()
+            .map(|(id, index)| CertifiedComponent { component_id: text(&id), index })
+            .collect())
+    }
+
+    fn verify_documents(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<DocumentVerification>> {
+        let documents = self
+            .client
+            .runtime_api()
+            .audit_documents(&self.at(at), component_id(id)?, index)
+            .map_err(runtime_error)?;
+        documents
+            .into_iter()
+            .map(|document| {
+                self.documents.verify(DocumentDetails {
+                    cid: text(&document.cid),
+                    size: document.size,
+                    media_type: text(&document.media_type),
+                })
+            })
+            .collect()
+    }
+
+    fn confidential_data(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ConfidentialDetails<AccountId>>> {
+        let data = self
+            .client
+            .runtime_api()
+            .confidential_data(&self.at(at), component_id(id)?, index)
+            .map_err(runtime_error)?;
+        Ok(data.map(Into::into))
+    }
+
+    fn verify_disclosure(
+        &self,
+        id: String,
+        index: u32,
+        salt: Bytes,
+        plaintext: Bytes,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<bool> {
+        let data = self
+            .client
+            .runtime_api()
+            .confidential_data(&self.at(at), component_id(id)?, index)
+            .map_err(runtime_error)?;
+        Ok(data.map_or(false, |data| data.is_disclosed_by(&salt, &plaintext)))
+    }
+
+    fn verify_presentation(
+        &self,
+        id: String,
+        index: u32,
+        presentation: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Map<String, Value>> {
+        let (jwt, disclosed) = disclosures::verify(&presentation).map_err(invalid_params)?;
+        let audit = self
+            .client
+            .runtime_api()
+            .audits(&self.at(at), component_id(id)?, index, 1)
+            .map_err(runtime_error)?;
+        match audit.first() {
+            Some(audit) if &audit.audit_data[..] != jwt.as_bytes() => {
+                Err(invalid_params("The JWT is not the JWT of the audit."))
+            }
+            // The digests were signed by the auditor, since the chain verified the audit JWT.
+            Some(audit) => match audit.status {
+                AuditStatus::Valid => Ok(disclosed),
+                AuditStatus::Revoked(_) => Err(invalid_params("The audit was revoked.")),
+                AuditStatus::Superseded(index, _) => Err(invalid_params(format!(
+                    "The audit was superseded by the audit at index {}.",
+                    index
+                ))),
+            },
+            None => Err(invalid_params("There is no such audit.")),
+        }
+    }
+
+    fn resolve_did(
+        &self,
+        did: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<DidDocument>> {
+        let account = did::account(&did)
+            .and_then(|account| AccountId::decode(&mut &account[..]).ok())
+            .ok_or_else(|| invalid_params("The DID is not a DID of an authority."))?;
+        let authority = self
+            .client
+            .runtime_api()
+            .authority(&self.at(at), account)
+            .map_err(runtime_error)?;
+        Ok(authority.map(|authority| credentials::did_document(&did, &authority)))
+    }
+
+    fn audit_credential(
+        &self,
+        id: String,
+        index: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<VerifiableCredential>> {
+        let audits = self
+            .client
+            .runtime_api()
+            .audits(&self.at(at), component_id(id.clone())?, index, 1)
+            .map_err(runtime_error)?;
+        let audit = match audits.into_iter().next() {
+            Some(audit) => audit,
+            None => return Ok(None),
+        };
+        let issuer: [u8; 32] = audit.issuer.encode().try_into().map_err(runtime_error)?;
+        Ok(Some(credentials::audit_credential(
+            &id,
+            index,
+            did::did(&issuer, default_ss58_version().into()),
+            audit.timestamp.unique_saturated_into(),
+            &text(&audit.key_id),
+            text(&audit.audit_data),
+            audit.status,
+        )))
+    }
+}
+
+/// Convert a component id that is passed to the RPC into the representation of the runtime.
+fn component_id(id: String) -> Result<ComponentId> {
+    id.into_bytes().try_into().map_err(|_| RpcError {
+        code: ErrorCode::InvalidParams,
+        message: "The component id is too long.".into(),
+        data: None,
+    })
+}
+
+/// The number of items a query returns, at most `MAX_LIMIT`.
+fn page_size(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+}
+
+/// Text that is stored as UTF-8 bytes.
+pub(crate) fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn invalid_params(message: impl Into<String>) -> RpcError {
+    RpcError { code: ErrorCode::InvalidParams, message: message.into(), data: None }
+}
+
+fn runtime_error(error: impl Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Unable to query the supply chain.".into(),
+        data: Some(format!("{:?}", error).into()),
+    }
+}
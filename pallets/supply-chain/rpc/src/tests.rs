@@ -0,0 +1,216 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+use crate::{
+    envelope::{open, seal, seal_with, Recipient, Sealed, SecretKey},
+    ConfidentialDetails, RecipientDetails,
+};
+use pallet_supply_chain::types::{ContentCipher, KeyWrapping, VerificationKey};
+use rand::rngs::mock::StepRng;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+
+const PLAINTEXT: &[u8] = br#"{"price":"12.50 EUR/kg"}"#;
+
+/// The recipients 1, 2 and 3 with an Ed25519, an Sr25519 and a secp256k1 key, and their
+/// secret keys.
+fn recipients() -> (Vec<Recipient<u64>>, Vec<SecretKey>) {
+    let ed25519 = ed25519::Pair::from_seed(&[1; 32]);
+    let sr25519 = sr25519::Pair::from_seed(&[2; 32]);
+    let secp256k1 = ecdsa::Pair::from_seed(&[3; 32]);
+    let mut scalar = [0; 32];
+    scalar.copy_from_slice(&sr25519.to_raw_vec()[..32]);
+    let recipient = |account, kid: &str, key| Recipient {
+        account,
+        kid: kid.into(),
+        key,
+    };
+    (
+        vec![
+            recipient(1, "ed", VerificationKey::Ed25519(ed25519.public().0)),
+            recipient(2, "sr", VerificationKey::Sr25519(sr25519.public().0)),
+            recipient(3, "k1", VerificationKey::Secp256k1(secp256k1.public().0)),
+        ],
+        vec![
+            SecretKey::Ed25519([1; 32]),
+            SecretKey::Sr25519(scalar),
+            SecretKey::Secp256k1([3; 32]),
+        ],
+    )
+}
+
+/// The confidential data as the node returns it after `sealed` was submitted.
+fn details(sealed: &Sealed<u64>) -> ConfidentialDetails<u64> {
+    ConfidentialDetails {
+        cipher: sealed.cipher,
+        ciphertext: sealed.ciphertext.clone().into(),
+        recipients: sealed
+            .recipients
+            .iter()
+            .map(|wrapped| RecipientDetails {
+                recipient: wrapped.recipient,
+                kid: wrapped.kid.clone(),
+                wrapping: wrapped.wrapping,
+                wrapped_key: wrapped.wrapped_key.to_vec().into(),
+            })
+            .collect(),
+        commitment: sealed.commitment.to_vec().into(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn sealed_data_is_opened_by_each_recipient() {
+    let (recipients, secrets) = recipients();
+    for cipher in [ContentCipher::XChaCha20Poly1305, ContentCipher::Aes256Gcm] {
+        let sealed = seal(PLAINTEXT, cipher, &recipients).unwrap();
+        let nonce = if cipher == ContentCipher::Aes256Gcm {
+            12
+        } else {
+            24
+        };
+        assert_eq!(sealed.ciphertext.len(), nonce + 32 + PLAINTEXT.len() + 16);
+        let wrappings: Vec<_> = sealed
+            .recipients
+            .iter()
+            .map(|wrapped| wrapped.wrapping)
+            .collect();
+        assert_eq!(
+            wrappings,
+            vec![
+                KeyWrapping::X25519,
+                KeyWrapping::Ristretto255,
+                KeyWrapping::Secp256k1
+            ]
+        );
+        for wrapped in &sealed.recipients {
+            assert_eq!(
+                wrapped.wrapped_key.len(),
+                wrapped.wrapping.wrapped_key_length()
+            );
+        }
+
+        let data = details(&sealed);
+        for (recipient, secret) in recipients.iter().zip(&secrets) {
+            assert_eq!(
+                open(&data, &recipient.account, secret),
+                Ok((sealed.salt, PLAINTEXT.to_vec()))
+            );
+        }
+        assert_eq!(
+            sealed.commitment_claim(),
+            format!("0x{}", hex(&sealed.commitment))
+        );
+    }
+}
+
+#[test]
+fn sealed_data_is_not_opened_by_others() {
+    let (recipients, secrets) = recipients();
+    let sealed = seal(
+        PLAINTEXT,
+        ContentCipher::XChaCha20Poly1305,
+        &recipients[..2],
+    )
+    .unwrap();
+    let data = details(&sealed);
+
+    assert!(open(&data, &3, &secrets[2]).is_err());
+    assert!(open(&data, &1, &SecretKey::Ed25519([9; 32])).is_err());
+    assert!(open(&data, &1, &secrets[1]).is_err());
+
+    let mut tampered = data.clone();
+    let last = tampered.ciphertext.0.len() - 1;
+    tampered.ciphertext.0[last] ^= 1;
+    assert!(open(&tampered, &1, &secrets[0]).is_err());
+    let mut tampered = data.clone();
+    tampered.commitment.0[0] ^= 1;
+    assert!(open(&tampered, &1, &secrets[0]).is_err());
+    let mut tampered = data;
+    tampered.recipients[1].wrapped_key.0[40] ^= 1;
+    assert!(open(&tampered, &2, &secrets[1]).is_err());
+
+    let invalid = Recipient {
+        account: 4,
+        kid: "k1".into(),
+        key: VerificationKey::Secp256k1([0; 33]),
+    };
+    assert!(seal(PLAINTEXT, ContentCipher::Aes256Gcm, &[invalid]).is_err());
+}
+
+// The X25519 and secp256k1 wrappings and both ciphertexts agree with an independent
+// implementation of the format.
+#[test]
+fn sealing_matches_known_answers() {
+    let (recipients, secrets) = recipients();
+    let sealed = seal_with(
+        &mut StepRng::new(1, 1),
+        PLAINTEXT,
+        ContentCipher::XChaCha20Poly1305,
+        &recipients,
+    )
+    .unwrap();
+    assert_eq!(
+        hex(&sealed.commitment),
+        "42995492c86393121504c43d636ee96f06393d461e1b347d678181726a0e15e6"
+    );
+    assert_eq!(
+        hex(&sealed.ciphertext),
+        concat!(
+            "09000000000000000a000000000000000b000000000000001aa74730f5524c4a3139c67614277bed",
+            "08de7e192a803d4e36add60b78391373b6ee5632003f619db3c09ba2f3e3e3429dfb9d875ea70718",
+            "9e76a6a8f7544b9501314456a761bf0e",
+        )
+    );
+    let wrapped_keys: Vec<_> = sealed
+        .recipients
+        .iter()
+        .map(|wrapped| hex(&wrapped.wrapped_key))
+        .collect();
+    assert_eq!(
+        wrapped_keys,
+        vec![
+            concat!(
+                "9c11581e6b1bd9a0b83f8c99a663822c7a115693b802f54dbeec723ae7aeb53c1000000000000000",
+                "11000000000000001200000000000000cc3011654c0919b80eefcc87d9a9e6954d09839c5098d074",
+                "245bb5422b783f047ee889f053cdc1b342ccfa441a328eb4",
+            ),
+            concat!(
+                "8c200119f4085bc813e2b9f2cc7c6dc76c2c7185eb40a9fcb43c038759e7072a1b00000000000000",
+                "1c000000000000001d00000000000000a9680604fd0b92d5bc9ee4b25f86bebda5f8e042339602a6",
+                "b9b299134b3ca9094880a34fff82d8e12cb3b21eda8c3c14",
+            ),
+            concat!(
+                "03890a14a5f90562ed71751eadb37b49374da7e929fcbfb6ff7d5200312baf8dad22000000000000",
+                "0023000000000000002400000000000000a485a21f08d99494278482edd6b0f66b182f1cb8dd725f",
+                "9397b234edd2e398c6b6f4d91505d988fa8f689c22a7407eca",
+            ),
+        ]
+    );
+
+    let sealed = seal_with(
+        &mut StepRng::new(1, 1),
+        PLAINTEXT,
+        ContentCipher::Aes256Gcm,
+        &recipients[..1],
+    )
+    .unwrap();
+    assert_eq!(
+        hex(&sealed.ciphertext),
+        concat!(
+            "09000000000000000a000000e6575ded1a24344aa3d97250ba0f1a42bfe8ea0492537e5fa369a0be",
+            "b9d42db691969d67f5fe4be6c62565646c7dbcb5bc9982a2d20ccb03826c5d53c6f0fe7e8bf682c9",
+            "352b3957",
+        )
+    );
+    assert_eq!(
+        open(&details(&sealed), &1, &secrets[0]),
+        Ok((sealed.salt, PLAINTEXT.to_vec()))
+    );
+}
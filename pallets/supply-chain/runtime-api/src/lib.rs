@@ -0,0 +1,110 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Runtime API definition for the supply chain pallet.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_supply_chain::types::{Direction, DocumentRef, ProvenanceNode};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Queries of the supply chain state.
+    ///
+    /// The stored types depend on the limits the runtime configures for the pallet, e.g.
+    /// `pallet_supply_chain::ComponentIdOf<Runtime>` and `pallet_supply_chain::AuditOf<Runtime>`.
+    ///
+    /// Version 2 added the component, audit and authority queries besides `audit_by_id`.
+    /// Version 3 added `provenance`.
+    /// Version 4 added `audit_payload` and `certified_components`.
+    /// Version 5 added `audit_documents`.
+    /// Version 6 added `confidential_data`.
+    /// Version 7 returns the number of audits of the components `provenance` reaches instead
+    /// of the audits.
+    /// Version 8 returns a page of the audits of the components `provenance` reaches besides
+    /// their number.
+    #[api_version(8)]
+    pub trait SupplyChainApi<
+        AccountId,
+        Hash,
+        Moment,
+        ComponentId,
+        Component,
+        Audit,
+        Payload,
+        Confidential,
+        Authority,
+    >
+    where
+        AccountId: Codec,
+        Hash: Codec,
+        Moment: Codec,
+        ComponentId: Codec,
+        Component: Codec,
+        Audit: Codec,
+        Payload: Codec,
+        Confidential: Codec,
+        Authority: Codec,
+    {
+        /// The audit with the given id, the component it was added to and its index within
+        /// the component's audits.
+        fn audit_by_id(id: Hash) -> Option<(ComponentId, u32, Audit)>;
+
+        /// The component with the given id, if it was created or audited.
+        fn component(id: ComponentId) -> Option<Component>;
+
+        /// At most `limit` audits of a component, starting at index `offset`.
+        fn audits(id: ComponentId, offset: u32, limit: u32) -> Vec<Audit>;
+
+        /// The authorized organization with the given account.
+        fn authority(account: AccountId) -> Option<Authority>;
+
+        /// The components that were assembled into a component.
+        fn parts_of(id: ComponentId) -> Vec<ComponentId>;
+
+        /// The components a component was assembled into.
+        fn used_in(id: ComponentId) -> Vec<ComponentId>;
+
+        /// Whether a component was created or audited.
+        fn exists(id: ComponentId) -> bool;
+
+        /// The components reachable from a component in the given direction, breadth first and
+        /// with the number of their audits and at most `audit_limit` of them, starting with the
+        /// first. Components deeper than `max_depth` are omitted and each component is returned
+        /// once. Of the reached components, the first `offset` are skipped and at most `limit`
+        /// are returned.
+        fn provenance(
+            id: ComponentId,
+            direction: Direction,
+            max_depth: u32,
+            offset: u32,
+            limit: u32,
+            audit_limit: u32,
+        ) -> Vec<ProvenanceNode<ComponentId, Audit>>;
+
+        /// The structured payload of the audit at `index` of a component, if the audit was
+        /// added with one.
+        fn audit_payload(id: ComponentId, index: u32) -> Option<Payload>;
+
+        /// The components whose latest audit for the standard passed and is valid at `at`,
+        /// with the index of that audit. Of these components, the first `offset` are skipped
+        /// and at most `limit` are returned.
+        fn certified_components(
+            standard: Vec<u8>,
+            at: Moment,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ComponentId, u32)>;
+
+        /// The documents attached to the audit at `index` of a component.
+        fn audit_documents(id: ComponentId, index: u32) -> Vec<DocumentRef<Vec<u8>>>;
+
+        /// The encrypted confidential data of the audit at `index` of a component, if the audit
+        /// was added with some.
+        fn confidential_data(id: ComponentId, index: u32) -> Option<Confidential>;
+    }
+}
@@ -7,13 +7,544 @@
 
 //! Benchmarking setup for pallet-supply-chain
 
+extern crate alloc;
+
 use super::*;
 
 #[allow(unused)]
 use crate::Pallet as SupplyChain;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use crate::types::{
+    AuditReason, AuditStatus, AuthorityInfo, AuthorityKey, AuthorityStatus, ComponentId,
+    ComponentStatus, Custody, DocumentRef, FieldType, KeyStatus, PartUsage, Quantity, Recall,
+    RecallReason, Role, Roles, SchemaField, Unit, VerificationKey,
+};
+use alloc::{format, string::String, vec::Vec};
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::{EnsureOrigin, Get};
 use frame_system::RawOrigin;
+use sp_core::{crypto::KeyTypeId, ed25519};
+use sp_runtime::Perbill;
+
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"scbm");
+const SEED: u32 = 0;
+/// The schema the audits of the benchmarks follow.
+const SCHEMA: &str = "benchmark";
+
+/// Authorize the whitelisted caller with every role and an ed25519 key from the keystore, and
+/// register the schema its audits follow.
+fn setup_auditor<T: Config>() -> (T::AccountId, ed25519::Public) {
+    let caller: T::AccountId = whitelisted_caller();
+    let public = sp_io::crypto::ed25519_generate(KEY_TYPE, None);
+    let name = b"AUDITOR".to_vec().try_into().unwrap();
+    let mut info = AuthorityInfo::new(name, Roles::all());
+    info.keys
+        .try_push(AuthorityKey {
+            kid: b"key-0".to_vec().try_into().unwrap(),
+            key: VerificationKey::Ed25519(public.0),
+            status: KeyStatus::Active,
+        })
+        .unwrap();
+    <Authorities<T>>::insert(&caller, info);
+    let fields = schema_fields::<T>(T::MaxSchemaFields::get(), false);
+    SupplyChain::<T>::register_schema(T::AdminOrigin::successful_origin(), SCHEMA.into(), fields)
+        .expect("the schema is valid; qed");
+    (caller, public)
+}
+
+/// `n` schema fields, each with the longest name.
+fn schema_fields<T: Config>(n: u32, required: bool) -> Vec<SchemaField<String>> {
+    (0..n)
+        .map(|i| SchemaField {
+            name: field_name::<T>(i),
+            field_type: FieldType::String,
+            required,
+            concealable: false,
+        })
+        .collect()
+}
+
+/// The longest field name, unique for each `i`.
+fn field_name<T: Config>(i: u32) -> String {
+    format!("{:0>1$}", i, T::MaxPayloadIdLength::get() as usize)
+}
+
+/// The longest key id, unique for each `i`.
+fn kid<T: Config>(i: u32) -> String {
+    format!("{:0>1$}", i, T::MaxKeyIdLength::get() as usize)
+}
+
+/// Fill the record of `account` up to `keys` keys and the longest website. Extrinsics that
+/// change the record decode and encode all of it.
+fn fill_authority<T: Config>(account: &T::AccountId, public: &ed25519::Public, keys: u32) {
+    <Authorities<T>>::mutate(account, |info| {
+        let info = info.as_mut().unwrap();
+        for i in 1..keys {
+            info.keys
+                .try_push(AuthorityKey {
+                    kid: kid::<T>(i).into_bytes().try_into().unwrap(),
+                    key: VerificationKey::Ed25519(public.0),
+                    status: KeyStatus::Rotated,
+                })
+                .unwrap();
+        }
+        let website = "a".repeat(T::MaxWebsiteLength::get() as usize);
+        info.website = Some(website.into_bytes().try_into().unwrap());
+        info.lei = Some(*b"5493001KJTIIGC8Y1R12");
+    });
+}
+
+/// An audit JWT signed by `public` that follows the benchmark schema, whose claims are padded
+/// such that the token is about `len` bytes long. The padding consists of as many schema
+/// fields as fit, such that checking the audit against the schema takes longer the longer
+/// the token is.
+fn audit_jwt<T: Config>(public: &ed25519::Public, len: u32) -> String {
+    let encode = |segment: &[u8]| base64::encode_config(segment, base64::URL_SAFE_NO_PAD);
+    let mut token = encode(br#"{"alg":"EdDSA","kid":"key-0"}"#);
+    token.push('.');
+    // The signature and the unpadded claims take up to 160 bytes of the token.
+    let padding = (len as usize).saturating_sub(token.len() + 160) * 3 / 4;
+    let mut claims = format!(r#"{{"iss":"AUDITOR","schema":"{}""#, SCHEMA);
+    let end = claims.len() + padding;
+    for i in 0..T::MaxSchemaFields::get() {
+        let field = format!(r#","{}":"0""#, field_name::<T>(i));
+        if claims.len() + field.len() > end {
+            break;
+        }
+        claims.push_str(&field);
+    }
+    claims.push_str(r#","data":""#);
+    claims.push_str(&"0".repeat(end.saturating_sub(claims.len())));
+    claims.push_str(r#""}"#);
+    token.push_str(&encode(claims.as_bytes()));
+    let signature = sp_io::crypto::ed25519_sign(KEY_TYPE, public, token.as_bytes())
+        .expect("the key was generated in the keystore; qed");
+    token.push('.');
+    token.push_str(&encode(&signature.0));
+    token
+}
+
+/// The stored representation of a component id.
+fn component<T: Config>(id: ComponentId) -> ComponentIdOf<T> {
+    id.into_bytes().try_into().unwrap()
+}
+
+/// The longest component id, unique for each `i`.
+fn long_id<T: Config>(i: u32) -> ComponentIdOf<T> {
+    component::<T>(format!("{:0>1$}", i, T::MaxComponentIdLength::get() as usize))
+}
+
+/// Give the record of a component the most parts and let it be part of `assemblies`
+/// assemblies, such that it takes longest to decode and encode. The parts don't exist.
+fn fill_component<T: Config>(id: &ComponentIdOf<T>, assemblies: u32) {
+    let max = T::MaxComponents::get();
+    let quantity = Quantity { amount: u64::MAX, unit: Unit::Kilogram };
+    let usage = PartUsage { quantity, yield_factor: Some(Perbill::one()) };
+    <Components<T>>::mutate(id, |component| {
+        for i in 0..max {
+            component.components.try_insert(long_id::<T>(max + i), Some(usage)).unwrap();
+        }
+        for i in 0..assemblies {
+            component.component_of.try_insert(long_id::<T>(2 * max + i)).unwrap();
+        }
+    });
+}
+
+/// A reference to document `i`, with the longest media type.
+fn document<T: Config>(i: u32) -> DocumentRef<String> {
+    DocumentRef {
+        cid: crate::cid::cid(&i.to_le_bytes()),
+        size: u64::MAX,
+        media_type: "a".repeat(T::MaxDocumentRefLength::get() as usize),
+    }
+}
+
+/// Let `custodian` hold a component with the given status.
+fn hold<T: Config>(component_id: &ComponentIdOf<T>, custodian: T::AccountId) {
+    let status = ComponentStatus::Created;
+    <ComponentCustody<T>>::insert(component_id, Custody { custodian, pending: None, status });
+}
+
+/// Queue `n` recalls to be propagated, each of a component that is part of no assembly and
+/// whose record takes longest to decode.
+fn pending_recalls<T: Config>(n: u32) {
+    for i in 0..n {
+        let id = long_id::<T>(i + 1);
+        fill_component::<T>(&id, 0);
+        let recall = Recall { source: id.clone(), reason: RecallReason::Contamination };
+        <Recalls<T>>::insert(&id, recall.clone());
+        <PendingRecalls<T>>::insert(&id, (recall, None::<ComponentIdOf<T>>));
+    }
+}
 
 benchmarks! {
+    authorize {
+        let n in 1 .. T::MaxAuditorNameLength::get();
+        let account: T::AccountId = account("authority", 0, SEED);
+        let name = "a".repeat(n as usize);
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, account.clone(), name, Role::ALL.to_vec())
+    verify {
+        assert!(<Authorities<T>>::contains_key(&account));
+    }
+
+    deauthorize {
+        let (auditor, public) = setup_auditor::<T>();
+        fill_authority::<T>(&auditor, &public, T::MaxAuthorityKeys::get());
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, auditor.clone())
+    verify {
+        assert!(!<Authorities<T>>::contains_key(&auditor));
+    }
+
+    grant_role {
+        let (auditor, public) = setup_auditor::<T>();
+        fill_authority::<T>(&auditor, &public, T::MaxAuthorityKeys::get());
+        <Authorities<T>>::mutate(&auditor, |info| info.as_mut().unwrap().roles = Roles::default());
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, auditor.clone(), Role::Certifier)
+    verify {
+        assert!(SupplyChain::<T>::authorities(&auditor).unwrap().roles.contains(Role::Certifier));
+    }
+
+    revoke_role {
+        let (auditor, public) = setup_auditor::<T>();
+        fill_authority::<T>(&auditor, &public, T::MaxAuthorityKeys::get());
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, auditor.clone(), Role::Certifier)
+    verify {
+        assert!(!SupplyChain::<T>::authorities(&auditor).unwrap().roles.contains(Role::Certifier));
+    }
+
+    set_authority_status {
+        let (auditor, public) = setup_auditor::<T>();
+        fill_authority::<T>(&auditor, &public, T::MaxAuthorityKeys::get());
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, auditor.clone(), AuthorityStatus::Suspended)
+    verify {
+        let status = SupplyChain::<T>::authorities(&auditor).unwrap().status;
+        assert_eq!(status, AuthorityStatus::Suspended);
+    }
+
+    revoke_key {
+        let (auditor, public) = setup_auditor::<T>();
+        let keys = T::MaxAuthorityKeys::get();
+        fill_authority::<T>(&auditor, &public, keys);
+        // Keys are searched in the order they were added.
+        let kid = kid::<T>(keys - 1);
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, auditor.clone(), kid.clone())
+    verify {
+        let info = SupplyChain::<T>::authorities(&auditor).unwrap();
+        assert_eq!(info.key(kid.as_bytes()).unwrap().status, KeyStatus::Revoked);
+    }
+
+    update_authority {
+        let (auditor, public) = setup_auditor::<T>();
+        fill_authority::<T>(&auditor, &public, T::MaxAuthorityKeys::get());
+        let website = "b".repeat(T::MaxWebsiteLength::get() as usize);
+        let lei = String::from("529900T8BM49AURSDO55");
+    }: _(RawOrigin::Signed(auditor.clone()), Some(website), Some(lei))
+    verify {
+        let info = SupplyChain::<T>::authorities(&auditor).unwrap();
+        assert_eq!(info.lei, Some(*b"529900T8BM49AURSDO55"));
+    }
+
+    add_key {
+        let (auditor, public) = setup_auditor::<T>();
+        let keys = T::MaxAuthorityKeys::get();
+        // Leave room for one more key.
+        fill_authority::<T>(&auditor, &public, keys - 1);
+        let kid = kid::<T>(keys - 1);
+    }: _(RawOrigin::Signed(auditor.clone()), kid.clone(), VerificationKey::Ed25519(public.0))
+    verify {
+        let info = SupplyChain::<T>::authorities(&auditor).unwrap();
+        assert_eq!(info.key(kid.as_bytes()).unwrap().status, KeyStatus::Active);
+    }
+
+    rotate_key {
+        let (auditor, public) = setup_auditor::<T>();
+        let keys = T::MaxAuthorityKeys::get();
+        fill_authority::<T>(&auditor, &public, keys - 1);
+        let kid = kid::<T>(keys - 1);
+        let key = VerificationKey::Ed25519(public.0);
+    }: _(RawOrigin::Signed(auditor.clone()), String::from("key-0"), kid.clone(), key)
+    verify {
+        let info = SupplyChain::<T>::authorities(&auditor).unwrap();
+        assert_eq!(info.key(b"key-0").unwrap().status, KeyStatus::Rotated);
+        assert_eq!(info.key(kid.as_bytes()).unwrap().status, KeyStatus::Active);
+    }
+
+    // The component already has all but one of its audits, the cost of appending one must
+    // not depend on how many there are.
+    audit {
+        let d in 256 .. T::MaxAuditSize::get();
+        let (caller, public) = setup_auditor::<T>();
+        let component_id: ComponentId = "0".into();
+        let audit_data = audit_jwt::<T>(&public, 256);
+        let audits = T::MaxAudits::get() - 1;
+        for _ in 0..audits {
+            SupplyChain::<T>::checked_add_audit(
+                caller.clone(),
+                audit_data.clone(),
+                None,
+                component_id.clone(),
+            )?;
+        }
+        let audit_data = audit_jwt::<T>(&public, d);
+    }: _(RawOrigin::Signed(caller), audit_data, component_id.clone())
+    verify {
+        let component_id = component::<T>(component_id);
+        assert_eq!(SupplyChain::<T>::audit_count(&component_id), audits + 1);
+    }
+
+    // Adding the assembly to a part decodes and encodes the part's record, so every part has
+    // the record that takes longest to do so. Every part is checked for a recall before its
+    // custody is checked, and is used up by the assembly. Since such a record takes about a
+    // megabyte, the parts stop at 64; the weight is linear in them.
+    audit_assembly {
+        let c in 1 .. T::MaxComponents::get().min(64);
+        let d in 256 .. T::MaxAuditSize::get();
+        let (caller, public) = setup_auditor::<T>();
+        let quantity = Quantity { amount: 1, unit: Unit::Kilogram };
+        let usage = PartUsage { quantity, yield_factor: None };
+        let mut components: Vec<(ComponentId, Option<PartUsage>)> = Vec::new();
+        for i in 0..c {
+            let part = long_id::<T>(i);
+            fill_component::<T>(&part, T::MaxComponents::get() - 1);
+            <Components<T>>::mutate(&part, |component| component.quantity = Some(quantity));
+            hold::<T>(&part, caller.clone());
+            components.push((String::from_utf8(part.to_vec()).unwrap(), Some(usage)));
+        }
+        let component_id: ComponentId = "assembly".into();
+        let audit_data = audit_jwt::<T>(&public, d);
+    }: _(RawOrigin::Signed(caller), audit_data, components, component_id.clone())
+    verify {
+        let component_id = component::<T>(component_id);
+        assert_eq!(SupplyChain::<T>::components(&component_id).components.len() as u32, c);
+        let status = SupplyChain::<T>::custody(long_id::<T>(0)).unwrap().status;
+        assert_eq!(status, ComponentStatus::Consumed);
+    }
+
+    revoke_audit {
+        let (caller, public) = setup_auditor::<T>();
+        let id: ComponentId = "0".into();
+        let audit_data = audit_jwt::<T>(&public, T::MaxAuditSize::get());
+        SupplyChain::<T>::checked_add_audit(caller.clone(), audit_data, None, id.clone())?;
+    }: _(RawOrigin::Signed(caller), id.clone(), 0, AuditReason::Withdrawn)
+    verify {
+        let audit = SupplyChain::<T>::audit(&component::<T>(id), 0).unwrap();
+        assert_eq!(audit.status, AuditStatus::Revoked(AuditReason::Withdrawn));
+    }
+
+    // The amended audit is the longest one, and the amendment is the last audit the component
+    // can have.
+    amend_audit {
+        let d in 256 .. T::MaxAuditSize::get();
+        let (caller, public) = setup_auditor::<T>();
+        let component_id: ComponentId = "0".into();
+        let audit_data = audit_jwt::<T>(&public, T::MaxAuditSize::get());
+        let audits = T::MaxAudits::get() - 1;
+        for _ in 0..audits {
+            SupplyChain::<T>::checked_add_audit(
+                caller.clone(),
+                audit_data.clone(),
+                None,
+                component_id.clone(),
+            )?;
+        }
+        let audit_data = audit_jwt::<T>(&public, d);
+    }: _(RawOrigin::Signed(caller), component_id.clone(), 0, audit_data, AuditReason::DataError)
+    verify {
+        let audit = SupplyChain::<T>::audit(&component::<T>(component_id), 0).unwrap();
+        assert_eq!(audit.status, AuditStatus::Superseded(audits, AuditReason::DataError));
+    }
+
+    // The component has the record that takes longest to decode and encode.
+    record_quantity {
+        let (caller, public) = setup_auditor::<T>();
+        let id: ComponentId = "0".into();
+        let audit_data = audit_jwt::<T>(&public, T::MaxAuditSize::get());
+        SupplyChain::<T>::checked_add_audit(caller.clone(), audit_data, None, id.clone())?;
+        let component_id = component::<T>(id.clone());
+        fill_component::<T>(&component_id, T::MaxComponents::get());
+        <Components<T>>::mutate(&component_id, |component| {
+            component.quantity = Some(Quantity { amount: 1, unit: Unit::Kilogram });
+        });
+        let quantity = Quantity { amount: 2_000, unit: Unit::Gram };
+    }: _(RawOrigin::Signed(caller), id.clone(), quantity)
+    verify {
+        assert_eq!(SupplyChain::<T>::components(component_id).quantity, Some(quantity));
+    }
+
+    // Every lot carries the longest audit and decodes and encodes the record of the split
+    // component, which has the record that takes longest to do so. Since the record takes
+    // about a megabyte, the lots stop at 64; the weight is linear in them.
+    split_component {
+        let l in 1 .. T::MaxComponents::get().min(64);
+        let (caller, public) = setup_auditor::<T>();
+        let max = T::MaxComponents::get();
+        let id = long_id::<T>(0);
+        fill_component::<T>(&id, max - l);
+        <Components<T>>::mutate(&id, |component| {
+            component.quantity = Some(Quantity { amount: u64::MAX, unit: Unit::Gram });
+        });
+        hold::<T>(&id, caller.clone());
+        let lots: Vec<(ComponentId, Quantity)> = (0..l)
+            .map(|i| {
+                let lot = String::from_utf8(long_id::<T>(3 * max + i).to_vec()).unwrap();
+                (lot, Quantity { amount: 1, unit: Unit::Gram })
+            })
+            .collect();
+        let audit_data = audit_jwt::<T>(&public, T::MaxAuditSize::get());
+        let component_id = String::from_utf8(id.to_vec()).unwrap();
+    }: _(RawOrigin::Signed(caller), audit_data, component_id, lots)
+    verify {
+        assert_eq!(SupplyChain::<T>::components(&id).component_of.len() as u32, max);
+    }
+
+    // Like `audit_assembly`, every merged component has the record that takes longest to
+    // decode and encode, and is used up.
+    merge_components {
+        let c in 1 .. T::MaxComponents::get().min(64);
+        let d in 256 .. T::MaxAuditSize::get();
+        let (caller, public) = setup_auditor::<T>();
+        let quantity = Quantity { amount: 1, unit: Unit::Kilogram };
+        let mut components: Vec<(ComponentId, Quantity)> = Vec::new();
+        for i in 0..c {
+            let part = long_id::<T>(i);
+            fill_component::<T>(&part, T::MaxComponents::get() - 1);
+            <Components<T>>::mutate(&part, |component| component.quantity = Some(quantity));
+            hold::<T>(&part, caller.clone());
+            components.push((String::from_utf8(part.to_vec()).unwrap(), quantity));
+        }
+        let component_id: ComponentId = "merged".into();
+        let audit_data = audit_jwt::<T>(&public, d);
+    }: _(RawOrigin::Signed(caller), audit_data, components, component_id.clone())
+    verify {
+        let merged = SupplyChain::<T>::components(component::<T>(component_id));
+        assert_eq!(merged.quantity, Some(Quantity { amount: c as u64, unit: Unit::Kilogram }));
+    }
+
+    // The transformed component has the record that takes longest to decode and encode.
+    transform_component {
+        let d in 256 .. T::MaxAuditSize::get();
+        let (caller, public) = setup_auditor::<T>();
+        let id = long_id::<T>(0);
+        fill_component::<T>(&id, T::MaxComponents::get() - 1);
+        <Components<T>>::mutate(&id, |component| {
+            component.quantity = Some(Quantity { amount: 10, unit: Unit::Kilogram });
+        });
+        hold::<T>(&id, caller.clone());
+        let consumed = Quantity { amount: 10, unit: Unit::Kilogram };
+        let produced = Quantity { amount: 8_200, unit: Unit::Gram };
+        let new_component_id: ComponentId = "roasted".into();
+        let audit_data = audit_jwt::<T>(&public, d);
+        let component_id = String::from_utf8(id.to_vec()).unwrap();
+    }: _(
+        RawOrigin::Signed(caller),
+        audit_data,
+        component_id,
+        consumed,
+        new_component_id.clone(),
+        produced
+    )
+    verify {
+        let transformed = SupplyChain::<T>::components(component::<T>(new_component_id));
+        assert_eq!(transformed.quantity, Some(produced));
+    }
+
+    transfer_custody {
+        let (caller, _) = setup_auditor::<T>();
+        let to: T::AccountId = account("recipient", 0, SEED);
+        let name = b"RECIPIENT".to_vec().try_into().unwrap();
+        <Authorities<T>>::insert(&to, AuthorityInfo::new(name, Roles::all()));
+        let id = long_id::<T>(0);
+        hold::<T>(&id, caller.clone());
+        let component_id = String::from_utf8(id.to_vec()).unwrap();
+    }: _(RawOrigin::Signed(caller), component_id, to.clone())
+    verify {
+        assert_eq!(SupplyChain::<T>::custody(&id).unwrap().pending, Some(to));
+    }
+
+    accept_custody {
+        let (caller, _) = setup_auditor::<T>();
+        let from: T::AccountId = account("sender", 0, SEED);
+        let id = long_id::<T>(0);
+        let status = ComponentStatus::InTransit;
+        <ComponentCustody<T>>::insert(
+            &id,
+            Custody { custodian: from, pending: Some(caller.clone()), status },
+        );
+        let component_id = String::from_utf8(id.to_vec()).unwrap();
+    }: _(RawOrigin::Signed(caller.clone()), component_id)
+    verify {
+        assert_eq!(SupplyChain::<T>::custody(&id).unwrap().custodian, caller);
+    }
+
+    destroy_component {
+        let (caller, _) = setup_auditor::<T>();
+        let id = long_id::<T>(0);
+        hold::<T>(&id, caller.clone());
+        let component_id = String::from_utf8(id.to_vec()).unwrap();
+    }: _(RawOrigin::Signed(caller), component_id)
+    verify {
+        assert_eq!(SupplyChain::<T>::custody(&id).unwrap().status, ComponentStatus::Destroyed);
+    }
+
+    // Decoding the record of a component whose recall is propagated takes longer than marking
+    // a containing component as recalled, so besides the recalled component `c - 1` recalls
+    // are pending, each of a component with the record that takes longest to decode.
+    recall_component {
+        let c in 1 .. T::MaxRecallPropagation::get();
+        let (caller, _) = setup_auditor::<T>();
+        let id = long_id::<T>(0);
+        fill_component::<T>(&id, 0);
+        hold::<T>(&id, caller.clone());
+        pending_recalls::<T>(c - 1);
+        let component_id = String::from_utf8(id.to_vec()).unwrap();
+    }: _(RawOrigin::Signed(caller), component_id, RecallReason::Contamination)
+    verify {
+        assert_eq!(SupplyChain::<T>::custody(&id).unwrap().status, ComponentStatus::Recalled);
+        assert!(!SupplyChain::<T>::recall_pending());
+    }
+
+    // Like `recall_component`, every visited component has a pending recall.
+    propagate_recall {
+        let c in 1 .. T::MaxRecallPropagation::get();
+        pending_recalls::<T>(c);
+    }: _(RawOrigin::None)
+    verify {
+        assert!(!SupplyChain::<T>::recall_pending());
+    }
+
+    // The new document is compared with every document that is already attached.
+    attach_document {
+        let (caller, public) = setup_auditor::<T>();
+        let id: ComponentId = "0".into();
+        let audit_data = audit_jwt::<T>(&public, T::MaxAuditSize::get());
+        SupplyChain::<T>::checked_add_audit(caller.clone(), audit_data, None, id.clone())?;
+        let documents = T::MaxAuditDocuments::get();
+        for i in 1..documents {
+            let origin = RawOrigin::Signed(caller.clone()).into();
+            SupplyChain::<T>::attach_document(origin, id.clone(), 0, document::<T>(i))?;
+        }
+    }: _(RawOrigin::Signed(caller), id.clone(), 0, document::<T>(0))
+    verify {
+        let attached = SupplyChain::<T>::documents(&component::<T>(id), 0);
+        assert_eq!(attached.len() as u32, documents);
+    }
+
+    // Every field has the longest name.
+    register_schema {
+        let f in 0 .. T::MaxSchemaFields::get();
+        let schema_id = "a".repeat(T::MaxPayloadIdLength::get() as usize);
+        let fields = schema_fields::<T>(f, true);
+        let origin = T::AdminOrigin::successful_origin();
+    }: _<T::Origin>(origin, schema_id.clone(), fields)
+    verify {
+        let schema_id: PayloadIdOf<T> = schema_id.into_bytes().try_into().unwrap();
+        assert_eq!(SupplyChain::<T>::schema_version(&schema_id), 1);
+    }
+
     impl_benchmark_test_suite!(SupplyChain, crate::mock::new_test_ext(), crate::mock::Test);
 }
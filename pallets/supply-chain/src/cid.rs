@@ -0,0 +1,87 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Content identifiers of documents that are stored off-chain and attached to audits.
+//! Documents are addressed as CIDv1 of their raw content hashed with SHA-256, written in
+//! lowercase base32, e.g. `bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e` for the
+//! text `hello world`.
+
+extern crate alloc;
+
+use alloc::{string::String, vec, vec::Vec};
+
+/// Multibase prefix of lowercase base32 without padding.
+const BASE32_PREFIX: char = 'b';
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+/// The CID version.
+const CID_VERSION: u8 = 0x01;
+/// Multicodec of raw binary content.
+const RAW_CODEC: u8 = 0x55;
+/// Multihash code of SHA-256.
+const SHA2_256: u8 = 0x12;
+/// Length of a SHA-256 digest in bytes.
+const DIGEST_LENGTH: u8 = 32;
+
+/// The CID of a document's content.
+pub fn cid(content: &[u8]) -> String {
+    from_digest(&sp_io::hashing::sha2_256(content))
+}
+
+/// The CID of a document whose content has the given SHA-256 digest.
+pub fn from_digest(digest: &[u8; 32]) -> String {
+    let mut bytes = vec![CID_VERSION, RAW_CODEC, SHA2_256, DIGEST_LENGTH];
+    bytes.extend_from_slice(digest);
+    let mut cid = String::from(BASE32_PREFIX);
+    cid.push_str(&encode_base32(&bytes));
+    cid
+}
+
+/// The SHA-256 digest a CID references, unless the CID is not a base32 CIDv1 of raw content
+/// hashed with SHA-256.
+pub fn digest(cid: &str) -> Option<[u8; 32]> {
+    let bytes = decode_base32(cid.strip_prefix(BASE32_PREFIX)?)?;
+    match bytes.split_at(bytes.len().min(4)) {
+        ([CID_VERSION, RAW_CODEC, SHA2_256, DIGEST_LENGTH], digest) => digest.try_into().ok(),
+        _ => None,
+    }
+}
+
+fn encode_base32(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        text.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    text
+}
+
+fn decode_base32(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for character in text.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|c| *c == character)?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    // The remaining bits only pad the last character.
+    if buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(bytes)
+}
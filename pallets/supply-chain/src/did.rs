@@ -0,0 +1,138 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Decentralized identifiers of authorities. An authority is identified as
+//! `did:greenaureus:<address>`, where the address is the SS58 encoding of its account, and
+//! its keys as DID URLs with the key id as fragment, e.g.
+//! `did:greenaureus:5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY#key-0`.
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+/// The prefix of the DIDs of authorities.
+pub const DID_PREFIX: &str = "did:greenaureus:";
+/// Separates a DID and the id of a key of the DID's authority.
+pub const FRAGMENT_SEPARATOR: char = '#';
+/// Multibase prefix of base58btc.
+pub const BASE58_PREFIX: char = 'z';
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Prefix of the preimage of SS58 checksums.
+const SS58_CHECKSUM_PREFIX: &[u8] = b"SS58PRE";
+const SS58_CHECKSUM_LENGTH: usize = 2;
+
+/// The DID of the authority with the given account, for the given SS58 address format.
+pub fn did(account: &[u8; 32], ss58_prefix: u16) -> String {
+    format!("{}{}", DID_PREFIX, ss58_encode(account, ss58_prefix))
+}
+
+/// The account a DID identifies, unless it is not a DID of an authority. Addresses of any
+/// SS58 address format are accepted.
+pub fn account(did: &str) -> Option<[u8; 32]> {
+    ss58_decode(did.strip_prefix(DID_PREFIX)?)
+}
+
+/// The DID URL of a key of the authority with the given DID.
+pub fn key_url(did: &str, kid: &str) -> String {
+    format!("{}{}{}", did, FRAGMENT_SEPARATOR, kid)
+}
+
+/// Split the DID URL of a key into the account of the authority and the key id. Returns
+/// `None` if `url` is not a DID URL of an authority's key.
+pub fn split_key_url(url: &str) -> Option<([u8; 32], &str)> {
+    let (did, kid) = url.split_once(FRAGMENT_SEPARATOR)?;
+    Some((account(did)?, kid))
+}
+
+/// Encode bytes in base58 with the bitcoin alphabet.
+pub fn encode_base58(bytes: &[u8]) -> String {
+    // Digits of the number in base 58, least significant first.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in bytes {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    // Each leading zero byte is written as the first character of the alphabet.
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    let mut text = "1".repeat(zeros);
+    text.extend(digits.iter().rev().map(|digit| BASE58_ALPHABET[*digit as usize] as char));
+    text
+}
+
+/// Decode base58 text with the bitcoin alphabet.
+pub fn decode_base58(text: &str) -> Option<Vec<u8>> {
+    // Bytes of the number, least significant first.
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len() * 733 / 1000 + 1);
+    for character in text.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|c| *c == character)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = text.bytes().take_while(|character| *character == b'1').count();
+    bytes.resize(bytes.len() + zeros, 0);
+    bytes.reverse();
+    Some(bytes)
+}
+
+fn ss58_encode(account: &[u8; 32], prefix: u16) -> String {
+    let mut data = match prefix {
+        0..=63 => vec![prefix as u8],
+        _ => {
+            // The first byte carries the upper six bits of the prefix's lower byte, the second
+            // byte its lower two bits and the prefix's upper byte.
+            let first = ((prefix & 0b0000_0000_1111_1100) as u8) >> 2;
+            let second = ((prefix >> 8) as u8) | (((prefix & 0b0000_0000_0000_0011) as u8) << 6);
+            vec![first | 0b0100_0000, second]
+        }
+    };
+    data.extend_from_slice(account);
+    let checksum = ss58_checksum(&data);
+    data.extend_from_slice(&checksum[..SS58_CHECKSUM_LENGTH]);
+    encode_base58(&data)
+}
+
+fn ss58_decode(address: &str) -> Option<[u8; 32]> {
+    let data = decode_base58(address)?;
+    let prefix_length = match data.first()? {
+        0..=63 => 1,
+        64..=127 => 2,
+        _ => return None,
+    };
+    if data.len() != prefix_length + 32 + SS58_CHECKSUM_LENGTH {
+        return None;
+    }
+    let (payload, checksum) = data.split_at(prefix_length + 32);
+    if ss58_checksum(payload)[..SS58_CHECKSUM_LENGTH] != *checksum {
+        return None;
+    }
+    payload[prefix_length..].try_into().ok()
+}
+
+fn ss58_checksum(data: &[u8]) -> [u8; 64] {
+    let mut context = blake2_rfc::blake2b::Blake2b::new(64);
+    context.update(SS58_CHECKSUM_PREFIX);
+    context.update(data);
+    let mut checksum = [0u8; 64];
+    checksum.copy_from_slice(context.finalize().as_bytes());
+    checksum
+}
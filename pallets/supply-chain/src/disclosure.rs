@@ -0,0 +1,177 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to read
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify,
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Selective disclosure of audit claims, following SD-JWT. The auditor conceals claims before
+//! signing the audit JWT: each concealed claim is replaced by the SHA-256 digest of its
+//! disclosure, a base64url encoded JSON array `[salt, name, value]`, in the `_sd` claim.
+//! The holder of the disclosures can later reveal single claims of the audit by handing out
+//! their disclosures, which anyone can check against the digests of the stored audit.
+
+extern crate alloc;
+
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use serde_json::{Map, Value};
+
+/// The claim that contains the digests of the concealed claims.
+pub const DIGESTS_CLAIM: &str = "_sd";
+/// The claim that names the hash algorithm of the digests.
+pub const ALGORITHM_CLAIM: &str = "_sd_alg";
+/// The only supported hash algorithm, which also applies if `_sd_alg` is missing.
+pub const SHA_256: &str = "sha-256";
+/// Separates the JWT and the disclosures of a presentation, `<jwt>~<disclosure>~...~`.
+pub const SEPARATOR: char = '~';
+
+/// Reasons why concealed claims or their disclosures could not be accepted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisclosureError {
+    /// The `_sd` claim is not a list of distinct digests, or a disclosure is not a base64url
+    /// encoded array of a salt, a claim name and a value.
+    Malformed,
+    /// The `_sd_alg` claim names a hash algorithm other than SHA-256.
+    UnsupportedAlgorithm,
+    /// The digest of a disclosure is not one of the digests of the claims.
+    UnknownDigest,
+    /// A disclosure reveals a claim that is not concealed or that was disclosed before.
+    DuplicateClaim,
+}
+
+/// A concealed claim together with the salt that hides it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Disclosure {
+    /// The base64url encoded salt.
+    pub salt: String,
+    /// The name of the claim.
+    pub name: String,
+    /// The value of the claim.
+    pub value: Value,
+}
+
+impl Disclosure {
+    /// The disclosure of a claim, hidden by a salt of at least 16 random bytes.
+    pub fn new(salt: &[u8], name: impl Into<String>, value: Value) -> Self {
+        Self { salt: encode_segment(salt), name: name.into(), value }
+    }
+
+    /// The disclosure as it is handed out and hashed.
+    pub fn encode(&self) -> String {
+        let array = Value::Array(vec![
+            Value::String(self.salt.clone()),
+            Value::String(self.name.clone()),
+            self.value.clone(),
+        ]);
+        // Serializing JSON values can't fail.
+        encode_segment(&serde_json::to_vec(&array).unwrap_or_default())
+    }
+
+    /// Decode a disclosure as it is handed out.
+    pub fn decode(encoded: &str) -> Result<Self, DisclosureError> {
+        let bytes = base64::decode_config(encoded, base64::URL_SAFE_NO_PAD)
+            .map_err(|_| DisclosureError::Malformed)?;
+        match serde_json::from_slice(&bytes).map_err(|_| DisclosureError::Malformed)? {
+            Value::Array(array) => match <[Value; 3]>::try_from(array) {
+                Ok([Value::String(salt), Value::String(name), value]) => {
+                    Ok(Self { salt, name, value })
+                }
+                _ => Err(DisclosureError::Malformed),
+            },
+            _ => Err(DisclosureError::Malformed),
+        }
+    }
+}
+
+/// The digest of an encoded disclosure, as it is listed in the `_sd` claim.
+pub fn digest(encoded: &str) -> String {
+    encode_segment(&sp_io::hashing::sha2_256(encoded.as_bytes()))
+}
+
+/// The digests of the claims that an audit's claims conceal. Empty if no claim is concealed.
+pub fn digests(claims: &Map<String, Value>) -> Result<BTreeSet<String>, DisclosureError> {
+    match claims.get(ALGORITHM_CLAIM) {
+        None => {}
+        Some(Value::String(algorithm)) if algorithm == SHA_256 => {}
+        Some(_) => return Err(DisclosureError::UnsupportedAlgorithm),
+    }
+    let mut digests = BTreeSet::new();
+    match claims.get(DIGESTS_CLAIM) {
+        None => {}
+        Some(Value::Array(values)) => {
+            for value in values {
+                let digest = value.as_str().ok_or(DisclosureError::Malformed)?;
+                let bytes = base64::decode_config(digest, base64::URL_SAFE_NO_PAD)
+                    .map_err(|_| DisclosureError::Malformed)?;
+                if bytes.len() != 32 || !digests.insert(digest.to_string()) {
+                    return Err(DisclosureError::Malformed);
+                }
+            }
+        }
+        Some(_) => return Err(DisclosureError::Malformed),
+    }
+    Ok(digests)
+}
+
+/// Conceal the claim `name` with the given salt. The claim is replaced by the digest of its
+/// disclosure, which is returned encoded. Returns `None` if there is no such claim.
+///
+/// The digests are kept sorted, such that their order does not reveal which claim was
+/// concealed when.
+pub fn conceal(claims: &mut Map<String, Value>, name: &str, salt: &[u8]) -> Option<String> {
+    if name == DIGESTS_CLAIM || name == ALGORITHM_CLAIM {
+        return None;
+    }
+    let value = claims.remove(name)?;
+    let encoded = Disclosure::new(salt, name, value).encode();
+    let mut digests: Vec<Value> = match claims.remove(DIGESTS_CLAIM) {
+        Some(Value::Array(digests)) => digests,
+        _ => Vec::new(),
+    };
+    digests.push(Value::String(digest(&encoded)));
+    digests.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+    claims.insert(DIGESTS_CLAIM.into(), Value::Array(digests));
+    claims.insert(ALGORITHM_CLAIM.into(), Value::String(SHA_256.into()));
+    Some(encoded)
+}
+
+/// Check encoded disclosures against the digests of an audit's claims and return the claims
+/// they reveal.
+pub fn disclose(
+    claims: &Map<String, Value>,
+    disclosures: &[&str],
+) -> Result<Map<String, Value>, DisclosureError> {
+    let digests = digests(claims)?;
+    let mut disclosed = Map::new();
+    for encoded in disclosures {
+        if !digests.contains(&digest(encoded)) {
+            return Err(DisclosureError::UnknownDigest);
+        }
+        let Disclosure { name, value, .. } = Disclosure::decode(encoded)?;
+        if name == DIGESTS_CLAIM
+            || name == ALGORITHM_CLAIM
+            || claims.contains_key(&name)
+            || disclosed.contains_key(&name)
+        {
+            return Err(DisclosureError::DuplicateClaim);
+        }
+        disclosed.insert(name, value);
+    }
+    Ok(disclosed)
+}
+
+/// Split a presentation `<jwt>~<disclosure>~...~` into the JWT and the disclosures.
+pub fn split_presentation(presentation: &str) -> (&str, Vec<&str>) {
+    let mut parts = presentation.split(SEPARATOR);
+    let jwt = parts.next().unwrap_or_default();
+    (jwt, parts.filter(|part| !part.is_empty()).collect())
+}
+
+fn encode_segment(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
@@ -0,0 +1,173 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Minimal JSON Web Token (JWS compact serialization) decoding and signature verification.
+//! Only the algorithms that can be verified with the host functions of the runtime are
+//! supported: `EdDSA` (Ed25519), `Sr25519` and `ES256K` (secp256k1 with SHA-256).
+
+extern crate alloc;
+
+use super::types::VerificationKey;
+use alloc::{string::String, vec::Vec};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sp_core::{ed25519, sr25519};
+
+/// Reasons why a JWT could not be accepted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JwtError {
+    /// The token is not a well-formed JWS compact serialization.
+    Malformed,
+    /// The `alg` header contains an algorithm that is not supported.
+    UnsupportedAlgorithm,
+    /// The `alg` header does not match the type of the verification key.
+    AlgorithmMismatch,
+    /// The signature does not match the signing input and verification key.
+    InvalidSignature,
+}
+
+/// Signature algorithms that can be verified on-chain.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Algorithm {
+    /// Ed25519 signatures, `"alg": "EdDSA"`.
+    EdDsa,
+    /// Schnorrkel/Ristretto signatures, `"alg": "Sr25519"`.
+    Sr25519,
+    /// ECDSA over secp256k1 with SHA-256, `"alg": "ES256K"`.
+    Es256k,
+}
+
+impl Algorithm {
+    fn from_name(name: &str) -> Result<Self, JwtError> {
+        match name {
+            "EdDSA" => Ok(Algorithm::EdDsa),
+            "Sr25519" => Ok(Algorithm::Sr25519),
+            "ES256K" => Ok(Algorithm::Es256k),
+            _ => Err(JwtError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// The JOSE header of a JWT.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Header {
+    /// The signature algorithm.
+    pub alg: String,
+    /// The id of the key that was used to sign the token.
+    #[serde(default)]
+    pub kid: Option<String>,
+    /// The media type of the token.
+    #[serde(default)]
+    pub typ: Option<String>,
+}
+
+/// A decoded, but not necessarily verified, JWT.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Jwt<'a> {
+    /// The decoded JOSE header.
+    pub header: Header,
+    /// The decoded claims.
+    pub claims: Map<String, Value>,
+    algorithm: Algorithm,
+    signing_input: &'a [u8],
+    signature: Vec<u8>,
+}
+
+impl<'a> Jwt<'a> {
+    /// Decode a JWT in compact serialization (`header.payload.signature`).
+    ///
+    /// The header and the payload must be JSON objects. The signature is not verified.
+    pub fn decode(token: &'a str) -> Result<Self, JwtError> {
+        let mut parts = token.splitn(3, '.');
+        let (header, payload, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(h), Some(p), Some(s)) if !h.is_empty() && !p.is_empty() && !s.is_empty() => {
+                (h, p, s)
+            }
+            _ => return Err(JwtError::Malformed),
+        };
+        // The signature segment must not contain any further separators.
+        if signature.contains('.') {
+            return Err(JwtError::Malformed);
+        }
+
+        let header: Header =
+            serde_json::from_slice(&decode_segment(header)?).map_err(|_| JwtError::Malformed)?;
+        let algorithm = Algorithm::from_name(&header.alg)?;
+        let claims: Map<String, Value> =
+            serde_json::from_slice(&decode_segment(payload)?).map_err(|_| JwtError::Malformed)?;
+        let signing_input_len = token.len() - signature.len() - 1;
+
+        Ok(Self {
+            header,
+            claims,
+            algorithm,
+            signing_input: &token.as_bytes()[..signing_input_len],
+            signature: decode_segment(signature)?,
+        })
+    }
+
+    /// The algorithm the token claims to be signed with.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Verify the signature of the token against `key`.
+    pub fn verify(&self, key: &VerificationKey) -> Result<(), JwtError> {
+        let valid = match (self.algorithm, key) {
+            (Algorithm::EdDsa, VerificationKey::Ed25519(public)) => {
+                let signature = ed25519::Signature::from_raw(self.raw_signature()?);
+                sp_io::crypto::ed25519_verify(
+                    &signature,
+                    self.signing_input,
+                    &ed25519::Public::from_raw(*public),
+                )
+            }
+            (Algorithm::Sr25519, VerificationKey::Sr25519(public)) => {
+                let signature = sr25519::Signature::from_raw(self.raw_signature()?);
+                sp_io::crypto::sr25519_verify(
+                    &signature,
+                    self.signing_input,
+                    &sr25519::Public::from_raw(*public),
+                )
+            }
+            (Algorithm::Es256k, VerificationKey::Secp256k1(public)) => {
+                // JWS ES256K signatures do not carry a recovery id, so both candidates are
+                // tried and the recovered key is compared with the registered one.
+                let raw = self.raw_signature()?;
+                let hash = sp_io::hashing::sha2_256(self.signing_input);
+                let mut signature = [0u8; 65];
+                signature[..64].copy_from_slice(&raw);
+                (0u8..2).any(|recovery_id| {
+                    signature[64] = recovery_id;
+                    sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature, &hash)
+                        .map(|recovered| &recovered == public)
+                        .unwrap_or(false)
+                })
+            }
+            _ => return Err(JwtError::AlgorithmMismatch),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(JwtError::InvalidSignature)
+        }
+    }
+
+    fn raw_signature(&self) -> Result<[u8; 64], JwtError> {
+        let mut raw = [0u8; 64];
+        if self.signature.len() != raw.len() {
+            return Err(JwtError::InvalidSignature);
+        }
+        raw.copy_from_slice(&self.signature);
+        Ok(raw)
+    }
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, JwtError> {
+    base64::decode_config(segment, base64::URL_SAFE_NO_PAD).map_err(|_| JwtError::Malformed)
+}
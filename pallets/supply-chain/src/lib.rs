@@ -5,122 +5,596 @@
 // the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
 // merge, publish, distribute, sublicense, and/or sell copies of the Software.
 
-//! This module offers supply chain functionality. It allows the superuser to authorize
+//! This module offers supply chain functionality. It allows the admin origin to authorize
 //! organizations to add audits and new components. Authorized organizations can add
-//! audits to existing components or add new components.
+//! audits to existing components or add new components, depending on the roles they
+//! were granted. Audits are JWTs that must be
+//! signed with the verification key registered for the submitting organization.
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use pallet::*;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod cid;
+pub mod did;
+pub mod disclosure;
+pub mod jwt;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 pub mod types;
+pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
     extern crate alloc;
 
-    use super::types::{Audit, Component, ComponentId};
-    use alloc::{string::String, vec::Vec};
+    use super::{
+        cid, did, disclosure,
+        jwt::{Jwt, JwtError},
+        types::{
+            Audit, AuditPayload, AuditReason, AuditSchema, AuditStatus, AuthorityInfo, AuthorityKey,
+            AuthorityStatus, BoundedString, CertificationResult, Component, ComponentId,
+            ComponentStatus, ConfidentialData, ContentCipher, Custody, Direction, DocumentRef,
+            KeyId, KeyStatus, Location, PartUsage, ProvenanceNode, Quantity, Recall, RecallReason,
+            Role, Roles, SchemaField, VerificationKey, WrappedKey,
+        },
+        weights::WeightInfo,
+    };
+    use alloc::{
+        collections::{BTreeMap, BTreeSet, VecDeque},
+        format,
+        string::String,
+        vec,
+        vec::Vec,
+    };
     use frame_support::{pallet_prelude::*, traits::Time, transactional};
-    use frame_system::pallet_prelude::*;
-    use sp_runtime::SaturatedConversion;
+    use frame_system::{
+        offchain::{SendTransactionTypes, SubmitTransaction},
+        pallet_prelude::*,
+    };
+    use serde_json::{Map, Value};
+    use sp_core::hexdisplay::HexDisplay;
+    use sp_runtime::{traits::Hash as HashT, Perbill};
 
-    type MomentOf<T> = <<T as Config>::Timestamp as Time>::Moment;
+    pub(crate) type MomentOf<T> = <<T as Config>::Timestamp as Time>::Moment;
+    /// The id of a component as it is stored.
+    pub type ComponentIdOf<T> = BoundedString<<T as Config>::MaxComponentIdLength>;
+    /// The name of an organization as it is stored.
+    pub type NameOf<T> = BoundedString<<T as Config>::MaxAuditorNameLength>;
+    /// The id of a verification key as it is stored.
+    pub type KeyIdOf<T> = BoundedString<<T as Config>::MaxKeyIdLength>;
+    /// The id of an audit type or certification standard as it is stored.
+    pub type PayloadIdOf<T> = BoundedString<<T as Config>::MaxPayloadIdLength>;
+    pub type AuditPayloadOf<T> = AuditPayload<MomentOf<T>, PayloadIdOf<T>>;
+    /// The CID or media type of a document reference as it is stored.
+    pub type DocumentTextOf<T> = BoundedString<<T as Config>::MaxDocumentRefLength>;
+    pub type DocumentRefOf<T> = DocumentRef<DocumentTextOf<T>>;
+    pub type WrappedKeyOf<T> = WrappedKey<<T as frame_system::Config>::AccountId, KeyIdOf<T>>;
+    pub type ConfidentialDataOf<T> = ConfidentialData<
+        <T as frame_system::Config>::AccountId,
+        <T as Config>::MaxKeyIdLength,
+        <T as Config>::MaxRecipients,
+        <T as Config>::MaxCiphertextSize,
+    >;
+    pub type AuditSchemaOf<T> =
+        AuditSchema<<T as Config>::MaxPayloadIdLength, <T as Config>::MaxSchemaFields>;
+    pub type AuditOf<T> = Audit<
+        <T as frame_system::Config>::AccountId,
+        MomentOf<T>,
+        <T as Config>::MaxAuditorNameLength,
+        <T as Config>::MaxKeyIdLength,
+        <T as Config>::MaxAuditSize,
+    >;
+    pub type ComponentOf<T> =
+        Component<<T as Config>::MaxComponentIdLength, <T as Config>::MaxComponents>;
+    pub type CustodyOf<T> = Custody<<T as frame_system::Config>::AccountId>;
+    pub type RecallOf<T> = Recall<<T as Config>::MaxComponentIdLength>;
+    pub type AuthorityInfoOf<T> = AuthorityInfo<
+        <T as Config>::MaxAuditorNameLength,
+        <T as Config>::MaxKeyIdLength,
+        <T as Config>::MaxAuthorityKeys,
+        <T as Config>::MaxWebsiteLength,
+    >;
+
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    /// The claim of an audit JWT that names its issuer, the auditor's name or DID.
+    const ISSUER_CLAIM: &str = "iss";
+    /// The claim of an audit JWT that contains the commitment to the audit's confidential data.
+    const COMMITMENT_CLAIM: &str = "commitment";
+    /// The claim of an audit JWT that references the schema the audit follows.
+    const SCHEMA_CLAIM: &str = "schema";
+    /// The claim of an audit JWT that selects a version of the referenced schema. Without it,
+    /// the latest version applies.
+    const SCHEMA_VERSION_CLAIM: &str = "schemaVersion";
+
+    /// Priority of the unsigned transactions that continue recalls.
+    const RECALL_PRIORITY: TransactionPriority = TransactionPriority::max_value() / 2;
 
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
         /// The event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
+        /// The origin that can authorize organizations and manage their roles and keys.
+        type AdminOrigin: EnsureOrigin<Self::Origin>;
+
         /// The maximum length of the auditor name.
         #[pallet::constant]
-        type MaxAuditorNameLength: Get<u16>;
+        type MaxAuditorNameLength: Get<u32>;
 
         /// The maximum number of audits a product can have.
         #[pallet::constant]
-        type MaxAudits: Get<u16>;
+        type MaxAudits: Get<u32>;
 
         /// The maximum size (in bytes) the audit data can have.
         #[pallet::constant]
-        type MaxAuditSize: Get<u16>;
+        type MaxAuditSize: Get<u32>;
+
+        /// The maximum number of verification keys an organization can have, including
+        /// rotated and revoked ones.
+        #[pallet::constant]
+        type MaxAuthorityKeys: Get<u32>;
 
         /// The maximum length of the component id.
         #[pallet::constant]
-        type MaxComponentIdLength: Get<u16>;
+        type MaxComponentIdLength: Get<u32>;
 
         /// The maximum number of parts a product can have and be part of.
         #[pallet::constant]
-        type MaxComponents: Get<u16>;
+        type MaxComponents: Get<u32>;
+
+        /// The maximum length of a verification key id.
+        #[pallet::constant]
+        type MaxKeyIdLength: Get<u32>;
+
+        /// The maximum number of components a recall marks within one transaction. Recalls
+        /// that reach more components are continued by the offchain worker.
+        #[pallet::constant]
+        type MaxRecallPropagation: Get<u32>;
+
+        /// The maximum length of the audit type and certification standard of an audit payload,
+        /// and of the ids and field names of audit schemas.
+        #[pallet::constant]
+        type MaxPayloadIdLength: Get<u32>;
+
+        /// The maximum number of fields of an audit schema.
+        #[pallet::constant]
+        type MaxSchemaFields: Get<u32>;
+
+        /// The maximum number of documents attached to an audit.
+        #[pallet::constant]
+        type MaxAuditDocuments: Get<u32>;
+
+        /// The maximum length of the CID and the media type of a document reference.
+        #[pallet::constant]
+        type MaxDocumentRefLength: Get<u32>;
+
+        /// The maximum number of recipients of an audit's confidential data.
+        #[pallet::constant]
+        type MaxRecipients: Get<u32>;
+
+        /// The maximum size of the encrypted confidential data of an audit.
+        #[pallet::constant]
+        type MaxCiphertextSize: Get<u32>;
+
+        /// The maximum length of an organization's website.
+        #[pallet::constant]
+        type MaxWebsiteLength: Get<u32>;
 
         /// Pallet that is used to retrieve timestamps.
         type Timestamp: Time;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::generate_storage_info]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
-    /// Maps a ComponentId to Component structure, which contains other parts audits.
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            super::migrations::migrate::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            super::migrations::pre_upgrade::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            super::migrations::post_upgrade::<T>()
+        }
+
+        /// Continue recalls that reached more components than a transaction may mark.
+        fn offchain_worker(_block_number: BlockNumberFor<T>) {
+            if Self::recall_pending() {
+                let call = Call::propagate_recall {};
+                if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+                    .is_err()
+                {
+                    frame_support::log::warn!(
+                        target: "runtime::supply-chain",
+                        "Failed to submit the continuation of a recall"
+                    );
+                }
+            }
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        /// Only continuations the offchain worker of this node submitted, or that are part of
+        /// a block, are valid. Continuations of the same progress of a recall are duplicates.
+        fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            if !matches!(source, TransactionSource::Local | TransactionSource::InBlock) {
+                return InvalidTransaction::Call.into();
+            }
+            match (call, <PendingRecalls<T>>::iter().next()) {
+                (Call::propagate_recall {}, Some((component_id, (_, last)))) => {
+                    ValidTransaction::with_tag_prefix("SupplyChainRecall")
+                        .priority(RECALL_PRIORITY)
+                        .and_provides((component_id, last))
+                        .longevity(5)
+                        .propagate(false)
+                        .build()
+                }
+                _ => InvalidTransaction::Call.into(),
+            }
+        }
+    }
+
+    /// Maps a ComponentId to Component structure, which contains the other parts.
     #[pallet::storage]
     #[pallet::getter(fn components)]
-    pub(super) type Components<T> =
-        StorageMap<_, Blake2_128Concat, ComponentId, Component<MomentOf<T>>, ValueQuery>;
+    pub(super) type Components<T: Config> =
+        StorageMap<_, Blake2_128Concat, ComponentIdOf<T>, ComponentOf<T>, ValueQuery>;
+
+    /// Maps a ComponentId to the organization that holds the component and the component's
+    /// lifecycle status. Components from before custody was recorded have no entry.
+    #[pallet::storage]
+    #[pallet::getter(fn custody)]
+    pub(super) type ComponentCustody<T: Config> =
+        StorageMap<_, Blake2_128Concat, ComponentIdOf<T>, CustodyOf<T>, OptionQuery>;
+
+    /// Maps a recalled ComponentId to the recall.
+    #[pallet::storage]
+    #[pallet::getter(fn recall)]
+    pub(super) type Recalls<T: Config> =
+        StorageMap<_, Blake2_128Concat, ComponentIdOf<T>, RecallOf<T>, OptionQuery>;
+
+    /// Maps a recalled ComponentId, whose containing components are yet to be recalled, to its
+    /// recall and the last containing component that was recalled already.
+    #[pallet::storage]
+    pub(super) type PendingRecalls<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ComponentIdOf<T>,
+        (RecallOf<T>, Option<ComponentIdOf<T>>),
+        OptionQuery,
+    >;
+
+    /// Maps a ComponentId and the index of an audit to the audit.
+    #[pallet::storage]
+    #[pallet::getter(fn audit)]
+    pub(super) type Audits<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ComponentIdOf<T>,
+        Twox64Concat,
+        u32,
+        AuditOf<T>,
+        OptionQuery,
+    >;
+
+    /// Maps a ComponentId to the number of audits of the component.
+    #[pallet::storage]
+    #[pallet::getter(fn audit_count)]
+    pub(super) type AuditCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, ComponentIdOf<T>, u32, ValueQuery>;
+
+    /// Maps a ComponentId and the index of an audit to the audit's structured payload. Only
+    /// audits that were added with a payload have an entry.
+    #[pallet::storage]
+    #[pallet::getter(fn audit_payload)]
+    pub(super) type AuditPayloads<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ComponentIdOf<T>,
+        Twox64Concat,
+        u32,
+        AuditPayloadOf<T>,
+        OptionQuery,
+    >;
+
+    /// Maps a certification standard and a ComponentId to the index of the component's latest
+    /// audit that certifies the component under the standard.
+    #[pallet::storage]
+    #[pallet::getter(fn certification)]
+    pub(super) type Certifications<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        PayloadIdOf<T>,
+        Blake2_128Concat,
+        ComponentIdOf<T>,
+        u32,
+        OptionQuery,
+    >;
+
+    /// Maps a ComponentId and the index of an audit to the documents attached to the audit.
+    #[pallet::storage]
+    #[pallet::getter(fn audit_documents)]
+    pub(super) type AuditDocuments<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ComponentIdOf<T>,
+        Twox64Concat,
+        u32,
+        BoundedVec<DocumentRefOf<T>, T::MaxAuditDocuments>,
+        ValueQuery,
+    >;
+
+    /// Maps a ComponentId and the index of an audit to the audit's confidential data. Only
+    /// audits that were added with confidential data have an entry.
+    #[pallet::storage]
+    #[pallet::getter(fn confidential_data)]
+    pub(super) type ConfidentialAudits<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        ComponentIdOf<T>,
+        Twox64Concat,
+        u32,
+        ConfidentialDataOf<T>,
+        OptionQuery,
+    >;
+
+    /// Maps a schema id and version to the audit schema.
+    #[pallet::storage]
+    #[pallet::getter(fn schema)]
+    pub(super) type Schemas<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        PayloadIdOf<T>,
+        Twox64Concat,
+        u32,
+        AuditSchemaOf<T>,
+        OptionQuery,
+    >;
+
+    /// Maps a schema id to its latest version. Versions start at 1.
+    #[pallet::storage]
+    #[pallet::getter(fn schema_version)]
+    pub(super) type SchemaVersions<T: Config> =
+        StorageMap<_, Blake2_128Concat, PayloadIdOf<T>, u32, ValueQuery>;
+
+    /// Maps the id of an audit to the component it was added to and its index within the
+    /// component's audits.
+    #[pallet::storage]
+    #[pallet::getter(fn audit_location)]
+    pub(super) type AuditsById<T: Config> =
+        StorageMap<_, Identity, T::Hash, (ComponentIdOf<T>, u32), OptionQuery>;
 
-    /// Maps an organization account to an organization name. Will be used when an audit is added.
+    /// Maps an organization account to the organization's details and verification keys.
+    /// Will be used when an audit is added.
     #[pallet::storage]
     #[pallet::getter(fn authorities)]
-    pub(super) type Authorities<T> =
-        StorageMap<_, Blake2_128Concat, <T as frame_system::Config>::AccountId, String, ValueQuery>;
+    pub(super) type Authorities<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        <T as frame_system::Config>::AccountId,
+        AuthorityInfoOf<T>,
+        OptionQuery,
+    >;
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// An account was mapped to an organization and granted permission to add audits.
-        /// \[account, organization\]
-        AuthorityAdded(T::AccountId, String),
+        /// \[account, organization, roles\]
+        AuthorityAdded(T::AccountId, NameOf<T>, Roles),
         /// An account was removed from the set of authorized accounts.
-        /// \[account, organization\]
-        AuthorityRemoved(T::AccountId, String),
+        /// \[account, organization, roles\]
+        AuthorityRemoved(T::AccountId, NameOf<T>, Roles),
+        /// A role was granted to an organization. \[account, role\]
+        RoleGranted(T::AccountId, Role),
+        /// A role was revoked from an organization. \[account, role\]
+        RoleRevoked(T::AccountId, Role),
         /// A ComponentId was seen for the first time and a Component entry was created. \[part_id\]
-        ComponentCreated(ComponentId),
-        /// An audit was added to a part \[part_id, organization\]
-        AuditAdded(ComponentId, String),
+        ComponentCreated(ComponentIdOf<T>),
+        /// An audit was added to a part \[part_id, organization, audit_id\]
+        AuditAdded(ComponentIdOf<T>, NameOf<T>, T::Hash),
+        /// An audit was revoked by its auditor. \[part_id, audit_index, reason\]
+        AuditRevoked(ComponentIdOf<T>, u32, AuditReason),
+        /// An audit was replaced by a new audit of its auditor.
+        /// \[part_id, audit_index, new_audit_index, reason\]
+        AuditAmended(ComponentIdOf<T>, u32, u32, AuditReason),
+        /// The details of an organization were updated. \[account\]
+        AuthorityUpdated(T::AccountId),
+        /// The status of an organization was changed. \[account, status\]
+        AuthorityStatusChanged(T::AccountId, AuthorityStatus),
+        /// An organization added a verification key. \[account, key_id\]
+        KeyAdded(T::AccountId, KeyIdOf<T>),
+        /// An organization replaced a verification key. \[account, old_key_id, new_key_id\]
+        KeyRotated(T::AccountId, KeyIdOf<T>, KeyIdOf<T>),
+        /// The admin origin revoked a verification key of an organization. \[account, key_id\]
+        KeyRevoked(T::AccountId, KeyIdOf<T>),
+        /// The produced quantity of a component was recorded. \[part_id, quantity\]
+        QuantityRecorded(ComponentIdOf<T>, Quantity),
+        /// A component was split into lots. \[part_id, lot_ids\]
+        ComponentSplit(ComponentIdOf<T>, Vec<ComponentIdOf<T>>),
+        /// Components were merged into a new component. \[part_ids, merged_part_id\]
+        ComponentsMerged(Vec<ComponentIdOf<T>>, ComponentIdOf<T>),
+        /// A component was transformed into a new component. \[part_id, new_part_id\]
+        ComponentTransformed(ComponentIdOf<T>, ComponentIdOf<T>),
+        /// The custodian of a component started to hand it over. \[part_id, from, to\]
+        CustodyOffered(ComponentIdOf<T>, T::AccountId, T::AccountId),
+        /// An organization took over custody of a component. \[part_id, from, to\]
+        CustodyAccepted(ComponentIdOf<T>, T::AccountId, T::AccountId),
+        /// The lifecycle status of a component changed. \[part_id, status\]
+        ComponentStatusChanged(ComponentIdOf<T>, ComponentStatus),
+        /// A component was recalled, either directly or because it contains a recalled
+        /// component. \[part_id, source_part_id, reason\]
+        ComponentRecalled(ComponentIdOf<T>, ComponentIdOf<T>, RecallReason),
+        /// All components containing the recalled component were recalled as well. \[part_id\]
+        RecallPropagated(ComponentIdOf<T>),
+        /// A version of an audit schema was registered. \[schema_id, version\]
+        SchemaRegistered(PayloadIdOf<T>, u32),
+        /// A document was attached to an audit. \[part_id, audit_index, cid\]
+        DocumentAttached(ComponentIdOf<T>, u32, DocumentTextOf<T>),
     }
 
     #[pallet::error]
     pub enum Error<T> {
         /// Tried to authorize an account that is already authorized.
         AlreadyAuthorized,
+        /// The component was already recalled.
+        AlreadyRecalled,
+        /// A claim of the audit does not have the type its schema requires.
+        AuditFieldTypeMismatch,
+        /// The component has no audit with the given index.
+        AuditNotFound,
+        /// The audit was already revoked or superseded.
+        AuditNotValid,
         /// The name of the auditing organization is too long.
         AuditorNameTooLong,
         /// Audit size is too big.
         AuditTooBig,
         /// Tried to remove authorization from an account that is not authorized.
         AuthorityNotFound,
+        /// The organization is suspended and can't add audits.
+        AuthoritySuspended,
+        /// The `commitment` claim of the audit JWT does not match the commitment of the
+        /// confidential data.
+        CommitmentMismatch,
         /// It was tried to create a component that already exists.
         ComponentAlreadyExists,
         /// The component's id is too long.
         ComponentIdTooLong,
+        /// The component was neither created nor audited.
+        ComponentNotFound,
+        /// The component was consumed, destroyed or recalled, or is being handed over.
+        ComponentUnavailable,
+        /// An assembly lists itself as one of its parts.
+        CycleDetected,
+        /// The `iss` claim or the `kid` header of the audit JWT is a DID of another organization,
+        /// or a malformed DID.
+        DidMismatch,
+        /// The document is already attached to the audit.
+        DocumentAlreadyAttached,
+        /// The CID or media type of a document reference is too long.
+        DocumentRefTooLong,
+        /// A key with the same id is already registered for the organization.
+        DuplicateKeyId,
+        /// A component is listed more than once.
+        DuplicatePart,
+        /// An audit schema lists a field more than once.
+        DuplicateSchemaField,
         /// Some data that was provided was empty.
         EmptyDataProvided,
+        /// More of a part was consumed by assemblies than was recorded as produced.
+        InsufficientQuantity,
+        /// The CID is not a base32 CIDv1 of raw content hashed with SHA-256.
+        InvalidCid,
+        /// The confidential data is empty or bigger than the maximum size.
+        InvalidCiphertext,
+        /// The `_sd` claim of the audit JWT is not a list of distinct SHA-256 digests, or its
+        /// `_sd_alg` claim names another hash algorithm.
+        InvalidDisclosureDigests,
+        /// The audit data is not a well-formed JWT.
+        InvalidJwt,
+        /// The signature of the audit JWT does not match the verification key of the auditor.
+        InvalidJwtSignature,
+        /// The Legal Entity Identifier is not 20 alphanumeric characters.
+        InvalidLei,
+        /// The coordinates are out of range, or the GLN has more than 13 digits or a wrong
+        /// check digit.
+        InvalidLocation,
+        /// Confidential data has no recipients, more than the maximum number of recipients, or
+        /// a recipient more than once.
+        InvalidRecipients,
+        /// The `schema` claim of the audit is not text or its `schemaVersion` is not a version.
+        InvalidSchemaReference,
+        /// The audit payload is valid until before it was issued.
+        InvalidValidityPeriod,
+        /// A content key is wrapped with a key wrapping that does not fit the recipient's key,
+        /// or it does not have the length of the key wrapping.
+        InvalidWrappedKey,
+        /// The algorithm of the audit JWT does not match the verification key of the auditor.
+        JwtAlgorithmMismatch,
+        /// The key id is too long.
+        KeyIdTooLong,
+        /// The key referenced by the audit JWT is not active.
+        KeyNotActive,
+        /// The audit has reached the maximum number of attached documents.
+        MaxAuditDocumentsReached,
         /// Maximum number of audits reached.
         MaxAuditsReached,
+        /// The organization has reached the maximum number of keys.
+        MaxAuthorityKeysReached,
         /// A component has reached the maximum number of components.
         MaxComponentsReached,
         /// A component has reached the maximum number of components it is part of.
         MaxComponentOfReached,
+        /// An audit schema has more than the maximum number of fields.
+        MaxSchemaFieldsReached,
+        /// A required field of the audit's schema is missing from the audit's claims.
+        MissingAuditField,
+        /// The audit JWT has no `kid` header, but the auditor has several active keys.
+        MissingKeyId,
+        /// The organization does not have the role required for this action.
+        MissingRole,
+        /// The component is not being handed over to the organization.
+        NoPendingTransfer,
+        /// Only the auditor that added an audit can revoke or amend it.
+        NotAuditIssuer,
+        /// Only the custodian of a component can hand it over or use it, and only the custodian
+        /// and certifiers can audit it.
+        NotCustodian,
+        /// The audit type or certification standard of an audit payload, or the id or a field
+        /// name of an audit schema, is too long.
+        PayloadIdTooLong,
+        /// A transformation produces more than it consumes of a quantity that measures the
+        /// same.
+        ProducedExceedsConsumed,
+        /// The sum of quantities exceeds the largest amount that can be recorded.
+        QuantityOverflow,
+        /// The role was already granted to the organization.
+        RoleAlreadyGranted,
         /// No permission to add audits.
         Unauthorized,
+        /// The units of two quantities measure different things, e.g. mass and volume.
+        UnitMismatch,
+        /// The key id referenced by the audit JWT is not registered for the auditor.
+        UnknownKeyId,
+        /// An assembly lists a part that was neither created nor audited.
+        UnknownPart,
+        /// An assembly consumes a quantity of a part whose produced quantity was not recorded.
+        UnknownQuantity,
+        /// The audit references a schema or schema version that is not registered.
+        UnknownSchema,
+        /// The audit JWT is signed with an algorithm that is not supported.
+        UnsupportedJwtAlgorithm,
+        /// The auditor has no key to verify audit JWTs against.
+        VerificationKeyNotFound,
+        /// The organization's website is too long.
+        WebsiteTooLong,
     }
 
     #[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
-		pub initial_authorities: Vec<(T::AccountId, String)>,
+		pub initial_authorities: Vec<(T::AccountId, String, Vec<Role>)>,
 	}
 
 	#[cfg(feature = "std")]
@@ -133,68 +607,236 @@ pub mod pallet {
 	#[pallet::genesis_build]
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-            for (acc, name) in &self.initial_authorities {
-                <Authorities<T>>::insert(acc, name);
+            for (acc, name, roles) in &self.initial_authorities {
+                let name = <Pallet<T>>::bounded(name.clone(), <Error<T>>::AuditorNameTooLong)
+                    .expect("the names of the initial authorities must not be too long");
+                <Authorities<T>>::insert(acc, AuthorityInfo::new(name, roles[..].into()));
             }
 		}
 	}
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// The superuser can authorize organizations to submit audits.
+        /// The admin origin can authorize organizations to submit audits.
         ///
         /// # Parameters
         ///
         /// * `account`: Account id to authorize.
         /// * `name`: Name associated with that account_id.
-        #[pallet::weight(1_000_000 + T::DbWeight::get().reads(1) + T::DbWeight::get().writes(1))]
+        /// * `roles`: Roles granted to the organization.
+        #[pallet::weight(T::WeightInfo::authorize(name.len() as u32))]
         pub fn authorize(
             origin: OriginFor<T>,
             account: T::AccountId,
             name: String,
+            roles: Vec<Role>,
         ) -> DispatchResult {
-            ensure_root(origin)?;
+            T::AdminOrigin::ensure_origin(origin)?;
+            let roles = Roles::from(&roles[..]);
             ensure!(
                 name.len() > 0 && name != String::default(),
                 <Error<T>>::EmptyDataProvided
             );
-            ensure!(
-                name.len() <= T::MaxAuditorNameLength::get().saturated_into(),
-                <Error<T>>::AuditorNameTooLong
-            );
+            let name: NameOf<T> = Self::bounded(name, <Error<T>>::AuditorNameTooLong)?;
 
             <Authorities<T>>::try_mutate(account.clone(), |val| -> DispatchResult {
-                ensure!(*val == String::default(), <Error<T>>::AlreadyAuthorized);
+                ensure!(val.is_none(), <Error<T>>::AlreadyAuthorized);
 
-                *val = name.clone();
+                *val = Some(AuthorityInfo::new(name.clone(), roles));
                 Ok(())
             })?;
 
-            Self::deposit_event(<Event<T>>::AuthorityAdded(account, name));
+            Self::deposit_event(<Event<T>>::AuthorityAdded(account, name, roles));
             Ok(())
         }
 
-        /// The superuser can de-authorize organizations, such that they can't submit audits.
+        /// The admin origin can de-authorize organizations, such that they can't submit audits.
         ///
         /// # Parameters
         ///
         /// * `account`: Account id to de-authorize.
-        #[pallet::weight(1_000_000 + T::DbWeight::get().reads(1))]
+        #[pallet::weight(T::WeightInfo::deauthorize())]
         pub fn deauthorize(origin: OriginFor<T>, account: T::AccountId) -> DispatchResult {
-            ensure_root(origin)?;
-            let name = <Authorities<T>>::take(account.clone());
-            ensure!(name != String::default(), <Error<T>>::AuthorityNotFound);
-            Self::deposit_event(<Event<T>>::AuthorityRemoved(account, name));
+            T::AdminOrigin::ensure_origin(origin)?;
+            let info = <Authorities<T>>::take(account.clone()).ok_or(<Error<T>>::AuthorityNotFound)?;
+            Self::deposit_event(<Event<T>>::AuthorityRemoved(account, info.name, info.roles));
+            Ok(())
+        }
+
+        /// The admin origin can grant a role to an organization.
+        ///
+        /// # Parameters
+        ///
+        /// * `account`: Account id of the organization.
+        /// * `role`: The role to grant.
+        #[pallet::weight(T::WeightInfo::grant_role())]
+        pub fn grant_role(origin: OriginFor<T>, account: T::AccountId, role: Role) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::mutate_authority(&account, |info| {
+                ensure!(info.roles.insert(role), <Error<T>>::RoleAlreadyGranted);
+                Ok(())
+            })?;
+            Self::deposit_event(<Event<T>>::RoleGranted(account, role));
+            Ok(())
+        }
+
+        /// The admin origin can revoke a role from an organization.
+        ///
+        /// # Parameters
+        ///
+        /// * `account`: Account id of the organization.
+        /// * `role`: The role to revoke.
+        #[pallet::weight(T::WeightInfo::revoke_role())]
+        pub fn revoke_role(origin: OriginFor<T>, account: T::AccountId, role: Role) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::mutate_authority(&account, |info| {
+                ensure!(info.roles.remove(role), <Error<T>>::MissingRole);
+                Ok(())
+            })?;
+            Self::deposit_event(<Event<T>>::RoleRevoked(account, role));
+            Ok(())
+        }
+
+        /// The admin origin can suspend an organization, such that it can't submit audits until
+        /// it is activated again.
+        ///
+        /// # Parameters
+        ///
+        /// * `account`: Account id of the organization.
+        /// * `status`: The new status of the organization.
+        #[pallet::weight(T::WeightInfo::set_authority_status())]
+        pub fn set_authority_status(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            status: AuthorityStatus,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::mutate_authority(&account, |info| {
+                info.status = status;
+                Ok(())
+            })?;
+            Self::deposit_event(<Event<T>>::AuthorityStatusChanged(account, status));
+            Ok(())
+        }
+
+        /// The admin origin can revoke a verification key of an organization, for example when it
+        /// was compromised. Audits verified against the key before remain on chain.
+        ///
+        /// # Parameters
+        ///
+        /// * `account`: Account id of the organization.
+        /// * `kid`: Id of the key to revoke.
+        #[pallet::weight(T::WeightInfo::revoke_key())]
+        pub fn revoke_key(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            kid: KeyId,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let kid: KeyIdOf<T> = Self::bounded(kid, <Error<T>>::UnknownKeyId)?;
+            Self::mutate_authority(&account, |info| {
+                let key = info
+                    .keys
+                    .iter_mut()
+                    .find(|k| k.kid == kid)
+                    .ok_or(<Error<T>>::UnknownKeyId)?;
+                key.status = KeyStatus::Revoked;
+                Ok(())
+            })?;
+            Self::deposit_event(<Event<T>>::KeyRevoked(account, kid));
+            Ok(())
+        }
+
+        /// An authorized organization can update its own details.
+        ///
+        /// # Parameters
+        ///
+        /// * `website`: Website of the organization.
+        /// * `lei`: Legal Entity Identifier (ISO 17442) of the organization.
+        #[pallet::weight(T::WeightInfo::update_authority())]
+        pub fn update_authority(
+            origin: OriginFor<T>,
+            website: Option<String>,
+            lei: Option<String>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let website: Option<BoundedString<T::MaxWebsiteLength>> = website
+                .map(|website| Self::bounded(website, <Error<T>>::WebsiteTooLong))
+                .transpose()?;
+            let lei = lei
+                .map(|lei| -> Result<[u8; 20], Error<T>> {
+                    ensure!(
+                        lei.bytes().all(|b| b.is_ascii_alphanumeric()),
+                        <Error<T>>::InvalidLei
+                    );
+                    lei.as_bytes().try_into().map_err(|_| <Error<T>>::InvalidLei)
+                })
+                .transpose()?;
+
+            Self::mutate_authority(&who, |info| {
+                info.website = website;
+                info.lei = lei;
+                Ok(())
+            })?;
+            Self::deposit_event(<Event<T>>::AuthorityUpdated(who));
             Ok(())
         }
 
-        /// Add an audit to a component.
+        /// An authorized organization can add a key its audit JWTs are verified against.
+        ///
+        /// # Parameters
+        ///
+        /// * `kid`: Key id, referenced by the `kid` header of audit JWTs.
+        /// * `key`: The public key.
+        #[pallet::weight(T::WeightInfo::add_key())]
+        pub fn add_key(origin: OriginFor<T>, kid: KeyId, key: VerificationKey) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let kid = Self::key_id(kid)?;
+            Self::mutate_authority(&who, |info| Self::push_key(info, kid.clone(), key))?;
+            Self::deposit_event(<Event<T>>::KeyAdded(who, kid));
+            Ok(())
+        }
+
+        /// An authorized organization can replace one of its keys. The old key is no longer
+        /// used to verify new audits.
+        ///
+        /// # Parameters
+        ///
+        /// * `old_kid`: Id of the key to replace.
+        /// * `kid`: Id of the new key.
+        /// * `key`: The new public key.
+        #[pallet::weight(T::WeightInfo::rotate_key())]
+        pub fn rotate_key(
+            origin: OriginFor<T>,
+            old_kid: KeyId,
+            kid: KeyId,
+            key: VerificationKey,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let old_kid: KeyIdOf<T> = Self::bounded(old_kid, <Error<T>>::UnknownKeyId)?;
+            let kid = Self::key_id(kid)?;
+            Self::mutate_authority(&who, |info| {
+                let old = info
+                    .keys
+                    .iter_mut()
+                    .find(|k| k.kid == old_kid)
+                    .ok_or(<Error<T>>::UnknownKeyId)?;
+                ensure!(old.status == KeyStatus::Active, <Error<T>>::KeyNotActive);
+                old.status = KeyStatus::Rotated;
+                Self::push_key(info, kid.clone(), key)
+            })?;
+            Self::deposit_event(<Event<T>>::KeyRotated(who, old_kid, kid));
+            Ok(())
+        }
+
+        /// Add an audit to a component. If the audit JWT references a schema in its `schema`
+        /// claim, the claims must conform to the schema.
         ///
         /// # Parameters
         ///
         /// * `component_id`: Id of the component to add an audit for.
         /// * `audit_data`: JWT data containing the audit.
-        #[pallet::weight(1_000_000 + T::DbWeight::get().reads(2) + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::audit(audit_data.len() as u32))]
         pub fn audit(
             origin: OriginFor<T>,
             audit_data: String,
@@ -204,25 +846,564 @@ pub mod pallet {
             Self::checked_add_audit(who, audit_data, None, component_id)
         }
 
+        /// Add an audit to a component, together with its content as typed fields. The fields
+        /// are stored next to the JWT, such that e.g. the components certified under a
+        /// standard can be queried.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component to add an audit for.
+        /// * `audit_data`: JWT data containing the audit.
+        /// * `payload`: The audit's type, validity period, location and certification.
+        #[pallet::weight(
+            T::WeightInfo::audit(audit_data.len() as u32)
+                .saturating_add(T::DbWeight::get().reads_writes(2, 2))
+        )]
+        #[transactional]
+        pub fn audit_with_payload(
+            origin: OriginFor<T>,
+            audit_data: String,
+            payload: AuditPayload<MomentOf<T>, String>,
+            component_id: ComponentId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let payload =
+                payload.try_map_ids(|id| Self::bounded(id, <Error<T>>::PayloadIdTooLong))?;
+            ensure!(payload.has_valid_period(), <Error<T>>::InvalidValidityPeriod);
+            ensure!(
+                payload.location.as_ref().map_or(true, Location::is_valid),
+                <Error<T>>::InvalidLocation
+            );
+            // Audits that don't reference a schema follow the schema named by their type, if
+            // there is one.
+            if Self::schema_version(&payload.audit_type) > 0 {
+                let jwt = Jwt::decode(&audit_data).map_err(Error::<T>::from)?;
+                if !jwt.claims.contains_key(SCHEMA_CLAIM) {
+                    Self::ensure_conforms(&jwt.claims, &payload.audit_type, None)?;
+                }
+            }
+
+            Self::checked_add_audit(who, audit_data, None, component_id.clone())?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            let index = Self::audit_count(&component_id) - 1;
+            if let Some(certification) = &payload.certification {
+                <Certifications<T>>::insert(&certification.standard, &component_id, index);
+            }
+            <AuditPayloads<T>>::insert(&component_id, index, payload);
+            Ok(())
+        }
+
+        /// Add an audit to a component, together with confidential data that only the listed
+        /// recipients can decrypt, e.g. prices or lab values. The audit JWT must contain the
+        /// commitment to the plaintext as hex string in its `commitment` claim, such that the
+        /// auditor's signature covers it.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component to add an audit for.
+        /// * `audit_data`: JWT data containing the public part of the audit.
+        /// * `cipher`: The cipher the content is encrypted with.
+        /// * `ciphertext`: The nonce, the encrypted content and its authentication tag.
+        /// * `recipients`: The content key, wrapped for each recipient with one of the
+        ///   recipient's active keys.
+        /// * `commitment`: Commitment to the plaintext, see [`ConfidentialData::commitment_of`].
+        #[pallet::weight(
+            T::WeightInfo::audit(audit_data.len() as u32)
+                .saturating_add(T::DbWeight::get().reads(recipients.len() as Weight))
+                .saturating_add(T::DbWeight::get().writes(1))
+        )]
+        #[transactional]
+        pub fn audit_confidential(
+            origin: OriginFor<T>,
+            audit_data: String,
+            cipher: ContentCipher,
+            ciphertext: Vec<u8>,
+            recipients: Vec<WrappedKey<T::AccountId, KeyId>>,
+            commitment: [u8; 32],
+            component_id: ComponentId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!ciphertext.is_empty(), <Error<T>>::InvalidCiphertext);
+            let ciphertext = Self::bounded(ciphertext, <Error<T>>::InvalidCiphertext)?;
+            let recipients = Self::wrapped_keys(recipients)?;
+            let jwt = Jwt::decode(&audit_data).map_err(Error::<T>::from)?;
+            let expected = format!("0x{}", HexDisplay::from(&commitment));
+            ensure!(
+                jwt.claims.get(COMMITMENT_CLAIM).and_then(Value::as_str) == Some(&expected[..]),
+                <Error<T>>::CommitmentMismatch
+            );
+
+            Self::checked_add_audit(who, audit_data, None, component_id.clone())?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            let index = Self::audit_count(&component_id) - 1;
+            <ConfidentialAudits<T>>::insert(
+                &component_id,
+                index,
+                ConfidentialData { cipher, ciphertext, recipients, commitment },
+            );
+            Ok(())
+        }
+
         /// Add an audit to a component. This is called when a new component is crafted.
-        /// Any components that are integrated into the new component must be listed.
+        /// Any components that are integrated into the new component must be listed. The
+        /// listed components must already exist and must not include the new component.
         ///
         /// # Parameters
         ///
         /// * `component_id`: Id of the component to add an audit for.
-        /// * `components`: A list of components that are part of the new component.
+        /// * `components`: A list of components that are part of the new component, each with
+        ///   how much of it was consumed, if that is known. The consumed quantity must not
+        ///   exceed what remains of the part's recorded produced quantity.
         /// * `audit_data`: JWT data containing the audit.
-        #[pallet::weight(1_000_000 + T::DbWeight::get().reads(2) + T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::WeightInfo::audit_assembly(
+            components.len() as u32,
+            audit_data.len() as u32,
+        ))]
         #[transactional]
         pub fn audit_assembly(
             origin: OriginFor<T>,
             audit_data: String,
-            components: Vec<ComponentId>,
+            components: Vec<(ComponentId, Option<PartUsage>)>,
+            component_id: ComponentId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            // Ensure this component does not exist, otherwise: If it exists, why is it created now?
+            Self::add_audit(who, audit_data, Some(components), component_id, None)
+        }
+
+        /// Revoke an audit. The audit remains part of the component's history, but is no
+        /// longer valid. Only the auditor that added the audit can revoke it.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component the audit was added to.
+        /// * `index`: Index of the audit within the component's audits.
+        /// * `reason`: Why the audit is revoked.
+        #[pallet::weight(T::WeightInfo::revoke_audit())]
+        pub fn revoke_audit(
+            origin: OriginFor<T>,
+            component_id: ComponentId,
+            index: u32,
+            reason: AuditReason,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::AuditNotFound)?;
+
+            let mut audit = Self::issued_audit(&component_id, &who, index)?;
+            audit.status = AuditStatus::Revoked(reason);
+            <Audits<T>>::insert(&component_id, index, audit);
+
+            Self::deposit_event(<Event<T>>::AuditRevoked(component_id, index, reason));
+            Ok(())
+        }
+
+        /// Replace an audit with a corrected one. The new audit is added to the component and
+        /// the old audit is marked as superseded by it. Only the auditor that added the audit
+        /// can amend it.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component the audit was added to.
+        /// * `index`: Index of the audit within the component's audits.
+        /// * `audit_data`: JWT data containing the corrected audit.
+        /// * `reason`: Why the audit is amended.
+        #[pallet::weight(T::WeightInfo::amend_audit(audit_data.len() as u32))]
+        pub fn amend_audit(
+            origin: OriginFor<T>,
+            component_id: ComponentId,
+            index: u32,
+            audit_data: String,
+            reason: AuditReason,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::add_audit(who, audit_data, None, component_id, Some((index, reason)))
+        }
+
+        /// Record how much of a component was produced. Only the auditor that created the
+        /// component can record its quantity. A recorded quantity can be corrected, but not
+        /// below what assemblies already consumed of the component.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component.
+        /// * `quantity`: The produced quantity of the component.
+        #[pallet::weight(T::WeightInfo::record_quantity())]
+        pub fn record_quantity(
+            origin: OriginFor<T>,
             component_id: ComponentId,
+            quantity: Quantity,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            // Ensure thisd component does not exist, otherwise: If it exists, why is it created now?
-            Self::checked_add_audit(who, audit_data, Some(components), component_id.clone())?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            let creator = Self::audit(&component_id, 0).ok_or(<Error<T>>::AuditNotFound)?;
+            ensure!(creator.issuer == who, <Error<T>>::NotAuditIssuer);
+
+            <Components<T>>::try_mutate(&component_id, |component| -> DispatchResult {
+                if let Some(recorded) = component.quantity {
+                    ensure!(recorded.is_compatible(&quantity), <Error<T>>::UnitMismatch);
+                }
+                ensure!(
+                    component.consumed <= quantity.base_amount(),
+                    <Error<T>>::InsufficientQuantity
+                );
+                component.quantity = Some(quantity);
+                Ok(())
+            })?;
+
+            Self::deposit_event(<Event<T>>::QuantityRecorded(component_id, quantity));
+            Ok(())
+        }
+
+        /// Split a component into lots. Each lot becomes a new component that consists of its
+        /// quantity of the split component. The audit is added to every lot.
+        ///
+        /// # Parameters
+        ///
+        /// * `audit_data`: JWT data containing the audit.
+        /// * `component_id`: Id of the component to split. Its produced quantity must be
+        ///   recorded and the lots must not exceed what remains of it.
+        /// * `lots`: Ids of the new components, each with its quantity.
+        #[pallet::weight(T::WeightInfo::split_component(lots.len() as u32))]
+        #[transactional]
+        pub fn split_component(
+            origin: OriginFor<T>,
+            audit_data: String,
+            component_id: ComponentId,
+            lots: Vec<(ComponentId, Quantity)>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!lots.is_empty(), <Error<T>>::EmptyDataProvided);
+
+            let mut lot_ids = Vec::with_capacity(lots.len());
+            for (lot_id, quantity) in lots {
+                let usage = PartUsage { quantity, yield_factor: None };
+                lot_ids.push(Self::create_component(
+                    who.clone(),
+                    audit_data.clone(),
+                    vec![(component_id.clone(), Some(usage))],
+                    lot_id,
+                    quantity,
+                )?);
+            }
+
+            let component_id = Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            Self::deposit_event(<Event<T>>::ComponentSplit(component_id, lot_ids));
+            Ok(())
+        }
+
+        /// Merge components of the same kind into a new component, e.g. lots that are
+        /// repacked together. The quantity of the new component is the sum of the merged
+        /// quantities.
+        ///
+        /// # Parameters
+        ///
+        /// * `audit_data`: JWT data containing the audit.
+        /// * `components`: Ids of the merged components, each with the merged quantity. All
+        ///   quantities must have the same unit.
+        /// * `component_id`: Id of the new component.
+        #[pallet::weight(T::WeightInfo::merge_components(
+            components.len() as u32,
+            audit_data.len() as u32,
+        ))]
+        #[transactional]
+        pub fn merge_components(
+            origin: OriginFor<T>,
+            audit_data: String,
+            components: Vec<(ComponentId, Quantity)>,
+            component_id: ComponentId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let unit = components.first().ok_or(<Error<T>>::EmptyDataProvided)?.1.unit;
+            let mut amount: u64 = 0;
+            let mut merged_ids = Vec::with_capacity(components.len());
+            for (id, quantity) in components.iter() {
+                ensure!(quantity.unit == unit, <Error<T>>::UnitMismatch);
+                amount = amount.checked_add(quantity.amount).ok_or(<Error<T>>::QuantityOverflow)?;
+                let id: ComponentIdOf<T> =
+                    Self::bounded(id.clone(), <Error<T>>::ComponentIdTooLong)?;
+                ensure!(!merged_ids.contains(&id), <Error<T>>::DuplicatePart);
+                merged_ids.push(id);
+            }
+
+            let parts = components
+                .into_iter()
+                .map(|(id, quantity)| (id, Some(PartUsage { quantity, yield_factor: None })))
+                .collect();
+            let component_id = Self::create_component(
+                who,
+                audit_data,
+                parts,
+                component_id,
+                Quantity { amount, unit },
+            )?;
+            Self::deposit_event(<Event<T>>::ComponentsMerged(merged_ids, component_id));
+            Ok(())
+        }
+
+        /// Transform a quantity of a component into a new kind of component, e.g. green coffee
+        /// into roasted coffee. If both quantities measure the same, the transformation can't
+        /// produce more than it consumes, and the ratio of produced to consumed quantity is
+        /// recorded as the yield of the transformation.
+        ///
+        /// # Parameters
+        ///
+        /// * `audit_data`: JWT data containing the audit.
+        /// * `component_id`: Id of the transformed component. Its produced quantity must be
+        ///   recorded and `consumed` must not exceed what remains of it.
+        /// * `consumed`: The transformed quantity of the component.
+        /// * `new_component_id`: Id of the new component.
+        /// * `produced`: The quantity of the new component.
+        #[pallet::weight(T::WeightInfo::transform_component(audit_data.len() as u32))]
+        #[transactional]
+        pub fn transform_component(
+            origin: OriginFor<T>,
+            audit_data: String,
+            component_id: ComponentId,
+            consumed: Quantity,
+            new_component_id: ComponentId,
+            produced: Quantity,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                !consumed.is_compatible(&produced)
+                    || produced.base_amount() <= consumed.base_amount(),
+                <Error<T>>::ProducedExceedsConsumed
+            );
+            let yield_factor = consumed.is_compatible(&produced).then(|| {
+                Perbill::from_rational(produced.base_amount(), consumed.base_amount())
+            });
+            let usage = PartUsage { quantity: consumed, yield_factor };
+
+            let new_component_id = Self::create_component(
+                who,
+                audit_data,
+                vec![(component_id.clone(), Some(usage))],
+                new_component_id,
+                produced,
+            )?;
+            let component_id = Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            Self::deposit_event(<Event<T>>::ComponentTransformed(component_id, new_component_id));
+            Ok(())
+        }
+
+        /// Start to hand over a component to another organization. The component is in transit
+        /// until the organization accepts it. A component in transit can be redirected to
+        /// another organization by calling this again.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component, held by the caller.
+        /// * `to`: Account of the organization that takes over the component.
+        #[pallet::weight(T::WeightInfo::transfer_custody())]
+        pub fn transfer_custody(
+            origin: OriginFor<T>,
+            component_id: ComponentId,
+            to: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            ensure!(<Authorities<T>>::contains_key(&to), <Error<T>>::AuthorityNotFound);
+
+            <ComponentCustody<T>>::try_mutate(&component_id, |custody| -> DispatchResult {
+                let custody = custody.as_mut().ok_or(<Error<T>>::NotCustodian)?;
+                ensure!(custody.custodian == who, <Error<T>>::NotCustodian);
+                ensure!(
+                    custody.status.is_available() || custody.status == ComponentStatus::InTransit,
+                    <Error<T>>::ComponentUnavailable
+                );
+                custody.pending = Some(to.clone());
+                custody.status = ComponentStatus::InTransit;
+                Ok(())
+            })?;
+
+            Self::deposit_event(<Event<T>>::CustodyOffered(component_id, who, to));
+            Ok(())
+        }
+
+        /// Take over a component that is handed over to the caller.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component.
+        #[pallet::weight(T::WeightInfo::accept_custody())]
+        pub fn accept_custody(origin: OriginFor<T>, component_id: ComponentId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+
+            let from = <ComponentCustody<T>>::try_mutate(
+                &component_id,
+                |custody| -> Result<T::AccountId, DispatchError> {
+                    let custody = custody.as_mut().ok_or(<Error<T>>::NoPendingTransfer)?;
+                    ensure!(custody.pending.as_ref() == Some(&who), <Error<T>>::NoPendingTransfer);
+                    custody.pending = None;
+                    custody.status = ComponentStatus::Received;
+                    Ok(core::mem::replace(&mut custody.custodian, who.clone()))
+                },
+            )?;
+
+            Self::deposit_event(<Event<T>>::CustodyAccepted(component_id, from, who));
+            Ok(())
+        }
+
+        /// Record that a component was destroyed. Only the custodian can destroy a component.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component, held by the caller.
+        #[pallet::weight(T::WeightInfo::destroy_component())]
+        pub fn destroy_component(origin: OriginFor<T>, component_id: ComponentId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+
+            <ComponentCustody<T>>::try_mutate(&component_id, |custody| -> DispatchResult {
+                let custody = custody.as_mut().ok_or(<Error<T>>::NotCustodian)?;
+                ensure!(custody.custodian == who, <Error<T>>::NotCustodian);
+                ensure!(custody.status.is_available(), <Error<T>>::ComponentUnavailable);
+                custody.status = ComponentStatus::Destroyed;
+                Ok(())
+            })?;
+
+            Self::deposit_event(<Event<T>>::ComponentStatusChanged(
+                component_id,
+                ComponentStatus::Destroyed,
+            ));
+            Ok(())
+        }
+
+        /// Recall a component, e.g. because it was found contaminated. Every component that
+        /// contains the recalled component, directly or through other components, is recalled
+        /// as well. Recalled components can't go into assemblies anymore.
+        ///
+        /// Only certifiers and the admin origin can recall components. If the recall reaches
+        /// more than `MaxRecallPropagation` components, the offchain worker continues it in
+        /// later blocks.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the recalled component.
+        /// * `reason`: Why the component is recalled.
+        #[pallet::weight(T::WeightInfo::recall_component(T::MaxRecallPropagation::get()))]
+        pub fn recall_component(
+            origin: OriginFor<T>,
+            component_id: ComponentId,
+            reason: RecallReason,
+        ) -> DispatchResult {
+            if let Err(origin) = T::AdminOrigin::try_origin(origin) {
+                let who = ensure_signed(origin)?;
+                let info = Self::authorities(&who).ok_or(<Error<T>>::Unauthorized)?;
+                ensure!(info.status == AuthorityStatus::Active, <Error<T>>::AuthoritySuspended);
+                ensure!(info.roles.contains(Role::Certifier), <Error<T>>::MissingRole);
+            }
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
+            ensure!(Self::exists(&component_id), <Error<T>>::ComponentNotFound);
+            ensure!(!<Recalls<T>>::contains_key(&component_id), <Error<T>>::AlreadyRecalled);
+
+            Self::mark_recalled(&component_id, Recall { source: component_id.clone(), reason });
+            Self::propagate_recalls(T::MaxRecallPropagation::get());
+            Ok(())
+        }
+
+        /// Continue recalls that reached more components than one transaction may mark.
+        /// Submitted as unsigned transaction by the offchain worker.
+        #[pallet::weight(T::WeightInfo::propagate_recall(T::MaxRecallPropagation::get()))]
+        pub fn propagate_recall(origin: OriginFor<T>) -> DispatchResult {
+            ensure_none(origin)?;
+            Self::propagate_recalls(T::MaxRecallPropagation::get());
+            Ok(())
+        }
+
+        /// Attach a document that is stored off-chain to an audit, e.g. a lab report that is
+        /// too big for the audit JWT. The document is referenced by the CID of its content, such
+        /// that anyone who obtains it can check that it is the attached document. Only the
+        /// auditor that added the audit can attach documents, and only while it is valid.
+        ///
+        /// # Parameters
+        ///
+        /// * `component_id`: Id of the component the audit was added to.
+        /// * `index`: Index of the audit within the component's audits.
+        /// * `document`: CID, size and media type of the document.
+        #[pallet::weight(T::WeightInfo::attach_document())]
+        pub fn attach_document(
+            origin: OriginFor<T>,
+            component_id: ComponentId,
+            index: u32,
+            document: DocumentRef<String>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::AuditNotFound)?;
+            ensure!(cid::digest(&document.cid).is_some(), <Error<T>>::InvalidCid);
+            let document: DocumentRefOf<T> =
+                document.try_map_text(|text| Self::bounded(text, <Error<T>>::DocumentRefTooLong))?;
+            Self::issued_audit(&component_id, &who, index)?;
+
+            <AuditDocuments<T>>::try_mutate(&component_id, index, |documents| {
+                ensure!(
+                    documents.iter().all(|attached| attached.cid != document.cid),
+                    <Error<T>>::DocumentAlreadyAttached
+                );
+                documents
+                    .try_push(document.clone())
+                    .map_err(|_| <Error<T>>::MaxAuditDocumentsReached)
+            })?;
+            Self::deposit_event(<Event<T>>::DocumentAttached(component_id, index, document.cid));
+            Ok(())
+        }
+
+        /// The admin origin can register a new version of an audit schema. Audits whose JWT
+        /// references the schema in its `schema` claim must contain the required fields of
+        /// the schema, and the fields they contain must have the listed types. The version
+        /// can be selected with the `schemaVersion` claim and defaults to the latest one.
+        ///
+        /// # Parameters
+        ///
+        /// * `schema_id`: Id of the schema, e.g. `harvest`.
+        /// * `fields`: The claims the schema lists, each with a distinct name.
+        #[pallet::weight(T::WeightInfo::register_schema(fields.len() as u32))]
+        pub fn register_schema(
+            origin: OriginFor<T>,
+            schema_id: String,
+            fields: Vec<SchemaField<String>>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let schema_id: PayloadIdOf<T> =
+                Self::bounded(schema_id, <Error<T>>::PayloadIdTooLong)?;
+            ensure!(
+                fields.len() <= T::MaxSchemaFields::get() as usize,
+                <Error<T>>::MaxSchemaFieldsReached
+            );
+            let mut names = BTreeSet::new();
+            let fields = fields
+                .into_iter()
+                .map(|field| {
+                    ensure!(names.insert(field.name.clone()), <Error<T>>::DuplicateSchemaField);
+                    Ok(SchemaField {
+                        name: Self::bounded(field.name, <Error<T>>::PayloadIdTooLong)?,
+                        field_type: field.field_type,
+                        required: field.required,
+                        concealable: field.concealable,
+                    })
+                })
+                .collect::<Result<Vec<_>, Error<T>>>()?;
+
+            let version = Self::schema_version(&schema_id) + 1;
+            <Schemas<T>>::insert(
+                &schema_id,
+                version,
+                AuditSchema {
+                    fields: fields.try_into().map_err(|_| <Error<T>>::MaxSchemaFieldsReached)?,
+                },
+            );
+            <SchemaVersions<T>>::insert(&schema_id, version);
+            Self::deposit_event(<Event<T>>::SchemaRegistered(schema_id, version));
             Ok(())
         }
     }
@@ -241,66 +1422,648 @@ pub mod pallet {
             components: Option<Vec<ComponentId>>,
             component_id: ComponentId,
         ) -> DispatchResult {
-            ensure!(
-                component_id.len() <= T::MaxComponentIdLength::get().saturated_into(),
-                <Error<T>>::ComponentIdTooLong
-            );
+            let components =
+                components.map(|comps| comps.into_iter().map(|id| (id, None)).collect());
+            Self::add_audit(auditor, audit_data, components, component_id, None)
+        }
+
+        /// The id of the audit at `index` of a component, derived from the component, the
+        /// auditor and the block and extrinsic in which the audit is added.
+        pub(crate) fn audit_id(
+            component_id: &ComponentIdOf<T>,
+            auditor: &T::AccountId,
+            extrinsic_index: Option<u32>,
+            index: u32,
+        ) -> T::Hash {
+            T::Hashing::hash_of(&(
+                component_id,
+                auditor,
+                <frame_system::Pallet<T>>::block_number(),
+                extrinsic_index,
+                index,
+            ))
+        }
+
+        /// The audit with the given id, the component it was added to and its index within
+        /// the component's audits.
+        pub fn audit_by_id(id: &T::Hash) -> Option<(ComponentIdOf<T>, u32, AuditOf<T>)> {
+            let (component_id, index) = Self::audit_location(id)?;
+            let audit = Self::audit(&component_id, index)?;
+            Some((component_id, index, audit))
+        }
+
+        /// All audits of a component, ordered by their index.
+        pub fn audits(component_id: &ComponentIdOf<T>) -> Vec<AuditOf<T>> {
+            (0..Self::audit_count(component_id))
+                .filter_map(|index| Self::audit(component_id, index))
+                .collect()
+        }
+
+        /// At most `limit` audits of a component, starting at index `offset`.
+        pub fn audits_page(
+            component_id: &ComponentIdOf<T>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AuditOf<T>> {
+            let end = Self::audit_count(component_id).min(offset.saturating_add(limit));
+            (offset..end)
+                .filter_map(|index| Self::audit(component_id, index))
+                .collect()
+        }
+
+        /// Whether a component was created or audited.
+        pub fn exists(component_id: &ComponentIdOf<T>) -> bool {
+            <Components<T>>::contains_key(component_id) || Self::audit_count(component_id) > 0
+        }
+
+        /// The component with the given id, if it exists.
+        pub fn component(component_id: &ComponentIdOf<T>) -> Option<ComponentOf<T>> {
+            Self::exists(component_id).then(|| Self::components(component_id))
+        }
+
+        /// The components that were assembled into a component.
+        pub fn parts_of(component_id: &ComponentIdOf<T>) -> Vec<ComponentIdOf<T>> {
+            Self::components(component_id).components.keys().cloned().collect()
+        }
+
+        /// The components a component was assembled into.
+        pub fn used_in(component_id: &ComponentIdOf<T>) -> Vec<ComponentIdOf<T>> {
+            Self::components(component_id).component_of.iter().cloned().collect()
+        }
+
+        /// Walk the assembly graph from a component in the given direction, breadth first,
+        /// and return at most `limit` of the reached components, skipping the first `offset`.
+        /// Each node carries at most `audit_limit` audits of its component, starting with the
+        /// first, and the number of all of them, such that the size of the result is bounded
+        /// by `limit` and `audit_limit`.
+        ///
+        /// The component itself is returned first, at depth 0. Components at `max_depth` are
+        /// not expanded any further and each component is returned only once, even if it is
+        /// reachable on several paths or the graph contains a cycle.
+        pub fn provenance(
+            component_id: &ComponentIdOf<T>,
+            direction: Direction,
+            max_depth: u32,
+            offset: u32,
+            limit: u32,
+            audit_limit: u32,
+        ) -> Vec<ProvenanceNode<ComponentIdOf<T>, AuditOf<T>>> {
+            let mut nodes = Vec::new();
+            if !Self::exists(component_id) {
+                return nodes;
+            }
+
+            let mut visited = BTreeSet::from([component_id.clone()]);
+            let mut queue = VecDeque::from([(component_id.clone(), 0, None)]);
+            let mut skip = offset;
+            while let Some((id, depth, parent)) = queue.pop_front() {
+                if nodes.len() as u32 >= limit {
+                    break;
+                }
+
+                if depth < max_depth {
+                    let component = Self::components(&id);
+                    let next: Vec<_> = match direction {
+                        Direction::Parts => component.components.keys().cloned().collect(),
+                        Direction::UsedIn => component.component_of.iter().cloned().collect(),
+                    };
+                    for next_id in next {
+                        if visited.insert(next_id.clone()) {
+                            queue.push_back((next_id, depth + 1, Some(id.clone())));
+                        }
+                    }
+                }
+
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                let audits = Self::audits_page(&id, 0, audit_limit);
+                let audit_count = Self::audit_count(&id);
+                nodes.push(ProvenanceNode { id, depth, parent, audits, audit_count });
+            }
+            nodes
+        }
+
+        /// The components certified under a standard at the given time, with the index of the
+        /// certifying audit. A component counts if its latest audit for the standard passed,
+        /// is still valid and covers the time. Of these components, the first `offset` are
+        /// skipped and at most `limit` are returned.
+        pub fn certified_components(
+            standard: &PayloadIdOf<T>,
+            at: MomentOf<T>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ComponentIdOf<T>, u32)> {
+            <Certifications<T>>::iter_prefix(standard)
+                .filter(|(component_id, index)| {
+                    let passed = Self::audit_payload(component_id, index).map_or(false, |payload| {
+                        payload.is_valid_at(&at)
+                            && payload.certification.map(|certification| certification.result)
+                                == Some(CertificationResult::Passed)
+                    });
+                    passed
+                        && Self::audit(component_id, index).map_or(false, |audit| audit.is_valid())
+                })
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect()
+        }
+
+        /// The documents attached to an audit, with their CIDs and media types as bytes.
+        pub fn documents(component_id: &ComponentIdOf<T>, index: u32) -> Vec<DocumentRef<Vec<u8>>> {
+            Self::audit_documents(component_id, index)
+                .into_iter()
+                .map(|document| DocumentRef {
+                    cid: document.cid.into_inner(),
+                    size: document.size,
+                    media_type: document.media_type.into_inner(),
+                })
+                .collect()
+        }
+
+        /// All audits of a component that were neither revoked nor superseded.
+        pub fn valid_audits(component_id: &ComponentIdOf<T>) -> Vec<AuditOf<T>> {
+            Self::audits(component_id)
+                .into_iter()
+                .filter(Audit::is_valid)
+                .collect()
+        }
+
+        /// Add an audit to a component. If `amends` is set, the audit at that index is marked
+        /// as superseded by the new audit. Amending an audit requires no role, since the auditor
+        /// already added the original audit.
+        fn add_audit(
+            auditor: T::AccountId,
+            audit_data: String,
+            components: Option<Vec<(ComponentId, Option<PartUsage>)>>,
+            component_id: ComponentId,
+            amends: Option<(u32, AuditReason)>,
+        ) -> DispatchResult {
+            let component_id: ComponentIdOf<T> =
+                Self::bounded(component_id, <Error<T>>::ComponentIdTooLong)?;
             ensure!(audit_data.len() != 0, <Error<T>>::EmptyDataProvided);
             ensure!(
-                audit_data.len() <= T::MaxAuditSize::get().saturated_into(),
+                audit_data.len() <= T::MaxAuditSize::get() as usize,
                 <Error<T>>::AuditTooBig
             );
-            let name = Self::authorities(auditor);
-            ensure!(name != String::default(), <Error<T>>::Unauthorized);
+            let info = Self::authorities(&auditor).ok_or(<Error<T>>::Unauthorized)?;
+            ensure!(info.status == AuthorityStatus::Active, <Error<T>>::AuthoritySuspended);
+            let (key_id, claims) = Self::verify_audit_jwt(&auditor, &info, &audit_data)?;
+            disclosure::digests(&claims).map_err(|_| <Error<T>>::InvalidDisclosureDigests)?;
+            if let Some(schema) = claims.get(SCHEMA_CLAIM) {
+                let schema_id = schema.as_str().ok_or(<Error<T>>::InvalidSchemaReference)?;
+                let schema_id = Self::bounded(schema_id.into(), <Error<T>>::UnknownSchema)?;
+                let version = match claims.get(SCHEMA_VERSION_CLAIM) {
+                    Some(version) => Some(
+                        version
+                            .as_u64()
+                            .and_then(|version| u32::try_from(version).ok())
+                            .ok_or(<Error<T>>::InvalidSchemaReference)?,
+                    ),
+                    None => None,
+                };
+                Self::ensure_conforms(&claims, &schema_id, version)?;
+            }
+            let audit_data = Self::bounded(audit_data, <Error<T>>::AuditTooBig)?;
+            let (name, roles) = (info.name, info.roles);
+
+            let new_index = Self::audit_count(&component_id);
+            ensure!(new_index < T::MaxAudits::get(), <Error<T>>::MaxAuditsReached);
+            let emit_component_added =
+                new_index == 0 && !<Components<T>>::contains_key(&component_id);
 
-            <Components<T>>::try_mutate(component_id.clone(), |val| -> DispatchResult {
+            if let Some((index, _)) = amends {
+                Self::issued_audit(&component_id, &auditor, index)?;
+            } else {
                 ensure!(
-                    val.audits.len() < T::MaxAudits::get().saturated_into(),
-                    <Error<T>>::MaxAuditsReached
+                    roles.contains_any(Self::required_roles(
+                        components.is_some(),
+                        emit_component_added
+                    )),
+                    <Error<T>>::MissingRole
                 );
-
-                let emit_component_added = *val == Default::default();
-
-                if let Some(comps) = components {
-                    // We should only create a component if it does not already exist.
-                    ensure!(
-                        val.audits.len() == 0
-                            && val.components.len() == 0
-                            && val.component_of.len() == 0,
-                        <Error<T>>::ComponentAlreadyExists
-                    );
+                if let Some(custody) = Self::custody(&component_id) {
                     ensure!(
-                        comps.len() <= T::MaxComponents::get().saturated_into(),
-                        <Error<T>>::MaxComponentsReached
+                        custody.custodian == auditor || roles.contains(Role::Certifier),
+                        <Error<T>>::NotCustodian
                     );
+                }
+            }
 
-                    for component in comps.iter() {
-                        <Components<T>>::try_mutate(component, |other| -> DispatchResult {
-                            ensure!(
-                                other.component_of.len() < T::MaxComponents::get().saturated_into(),
-                                <Error<T>>::MaxComponentOfReached
-                            );
-                            other.component_of.insert(component_id.clone());
-                            Ok(())
-                        })?;
-                    }
+            if let Some(comps) = components {
+                // We should only create a component if it does not already exist.
+                ensure!(emit_component_added, <Error<T>>::ComponentAlreadyExists);
+                ensure!(
+                    comps.len() <= T::MaxComponents::get() as usize,
+                    <Error<T>>::MaxComponentsReached
+                );
+                let comps = comps
+                    .into_iter()
+                    .map(|(id, usage)| {
+                        Ok((Self::bounded(id, <Error<T>>::ComponentIdTooLong)?, usage))
+                    })
+                    .collect::<Result<BTreeMap<ComponentIdOf<T>, _>, Error<T>>>()?;
+                Self::ensure_parts(&component_id, &comps)?;
 
-                    val.components = comps.into_iter().collect();
+                for (component, usage) in comps.iter() {
+                    Self::ensure_holds(component, &auditor)?;
+                    let used_up = <Components<T>>::try_mutate(
+                        component,
+                        |other| -> Result<bool, DispatchError> {
+                            other
+                                .component_of
+                                .try_insert(component_id.clone())
+                                .map_err(|_| <Error<T>>::MaxComponentOfReached)?;
+                            match usage {
+                                Some(usage) => Self::consume(other, &usage.quantity),
+                                // Parts without quantity go into the assembly entirely.
+                                None => Ok(true),
+                            }
+                        },
+                    )?;
+                    if used_up {
+                        Self::set_status(component, ComponentStatus::Consumed);
+                    }
                 }
 
-                if emit_component_added {
-                    Self::deposit_event(<Event<T>>::ComponentCreated(component_id.clone()));
-                }
+                <Components<T>>::insert(
+                    &component_id,
+                    Component {
+                        components: comps
+                            .try_into()
+                            .map_err(|_| <Error<T>>::MaxComponentsReached)?,
+                        ..Default::default()
+                    },
+                );
+            }
 
-                val.audits.push(Audit {
+            if emit_component_added {
+                <ComponentCustody<T>>::insert(
+                    &component_id,
+                    Custody {
+                        custodian: auditor.clone(),
+                        pending: None,
+                        status: ComponentStatus::Created,
+                    },
+                );
+                Self::deposit_event(<Event<T>>::ComponentCreated(component_id.clone()));
+            }
+
+            if let Some((index, reason)) = amends {
+                <Audits<T>>::mutate(&component_id, index, |audit| {
+                    if let Some(audit) = audit {
+                        audit.status = AuditStatus::Superseded(new_index, reason);
+                    }
+                });
+            }
+
+            let audit_id = Self::audit_id(
+                &component_id,
+                &auditor,
+                <frame_system::Pallet<T>>::extrinsic_index(),
+                new_index,
+            );
+            <AuditsById<T>>::insert(audit_id, (component_id.clone(), new_index));
+            <Audits<T>>::insert(
+                &component_id,
+                new_index,
+                Audit {
+                    issuer: auditor,
                     auditor: name.clone(),
                     timestamp: T::Timestamp::now(),
                     audit_data,
-                });
+                    key_id,
+                    status: AuditStatus::Valid,
+                },
+            );
+            <AuditCount<T>>::insert(&component_id, new_index + 1);
 
-                Self::deposit_event(<Event<T>>::AuditAdded(component_id, name));
-                Ok(())
+            if let Some((index, reason)) = amends {
+                Self::deposit_event(<Event<T>>::AuditAmended(
+                    component_id.clone(),
+                    index,
+                    new_index,
+                    reason,
+                ));
+            }
+            Self::deposit_event(<Event<T>>::AuditAdded(component_id, name, audit_id));
+            Ok(())
+        }
+
+        /// Create a component from parts with an audit and record its produced quantity.
+        fn create_component(
+            auditor: T::AccountId,
+            audit_data: String,
+            parts: Vec<(ComponentId, Option<PartUsage>)>,
+            component_id: ComponentId,
+            quantity: Quantity,
+        ) -> Result<ComponentIdOf<T>, DispatchError> {
+            let id: ComponentIdOf<T> =
+                Self::bounded(component_id.clone(), <Error<T>>::ComponentIdTooLong)?;
+            Self::add_audit(auditor, audit_data, Some(parts), component_id, None)?;
+            <Components<T>>::mutate(&id, |component| component.quantity = Some(quantity));
+            Ok(id)
+        }
+
+        /// Ensure that a new assembly keeps the assembly graph acyclic.
+        ///
+        /// All parts must already exist and the assembly must not be one of them. Since the
+        /// assembly itself doesn't exist yet, no existing component can reach it and the new
+        /// edges can't close a longer cycle.
+        fn ensure_parts(
+            assembly: &ComponentIdOf<T>,
+            parts: &BTreeMap<ComponentIdOf<T>, Option<PartUsage>>,
+        ) -> DispatchResult {
+            ensure!(!parts.contains_key(assembly), <Error<T>>::CycleDetected);
+            ensure!(parts.keys().all(Self::exists), <Error<T>>::UnknownPart);
+            Ok(())
+        }
+
+        /// Account for `quantity` of a part being consumed by an assembly. The part's consumed
+        /// quantity must not exceed its recorded produced quantity. Returns whether nothing is
+        /// left of the part.
+        fn consume(part: &mut ComponentOf<T>, quantity: &Quantity) -> Result<bool, DispatchError> {
+            let produced = part.quantity.ok_or(<Error<T>>::UnknownQuantity)?;
+            ensure!(produced.is_compatible(quantity), <Error<T>>::UnitMismatch);
+            let consumed = part.consumed.saturating_add(quantity.base_amount());
+            ensure!(consumed <= produced.base_amount(), <Error<T>>::InsufficientQuantity);
+            part.consumed = consumed;
+            Ok(consumed == produced.base_amount())
+        }
+
+        /// Ensure that the auditor holds a part that goes into an assembly and that the part
+        /// is available. Parts without custody can be used by anyone, unless they were recalled.
+        fn ensure_holds(part: &ComponentIdOf<T>, auditor: &T::AccountId) -> DispatchResult {
+            ensure!(!<Recalls<T>>::contains_key(part), <Error<T>>::ComponentUnavailable);
+            if let Some(custody) = Self::custody(part) {
+                ensure!(custody.custodian == *auditor, <Error<T>>::NotCustodian);
+                ensure!(custody.status.is_available(), <Error<T>>::ComponentUnavailable);
+            }
+            Ok(())
+        }
+
+        /// Whether recalls are waiting to be propagated to further components.
+        pub(super) fn recall_pending() -> bool {
+            <PendingRecalls<T>>::iter_keys().next().is_some()
+        }
+
+        /// Recall a component and queue the components containing it to be recalled.
+        fn mark_recalled(component_id: &ComponentIdOf<T>, recall: RecallOf<T>) {
+            Self::set_status(component_id, ComponentStatus::Recalled);
+            Self::deposit_event(<Event<T>>::ComponentRecalled(
+                component_id.clone(),
+                recall.source.clone(),
+                recall.reason,
+            ));
+            <Recalls<T>>::insert(component_id, recall.clone());
+            <PendingRecalls<T>>::insert(component_id, (recall, None::<ComponentIdOf<T>>));
+        }
+
+        /// Recall the components that contain recalled components, visiting at most `limit`
+        /// components. A component whose containing components were not all visited stays
+        /// pending with the last visited one, such that the next call continues after it.
+        pub(super) fn propagate_recalls(limit: u32) {
+            let mut budget = limit;
+            while budget > 0 {
+                let (component_id, (recall, mut last)) = match <PendingRecalls<T>>::iter().next() {
+                    Some(pending) => pending,
+                    None => break,
+                };
+                budget -= 1;
+
+                let mut done = true;
+                let component_of = Self::components(&component_id).component_of;
+                let remaining = component_of
+                    .iter()
+                    .filter(|id| last.as_ref().map_or(true, |last| *id > last));
+                for containing in remaining {
+                    if budget == 0 {
+                        done = false;
+                        break;
+                    }
+                    budget -= 1;
+                    last = Some(containing.clone());
+                    if !<Recalls<T>>::contains_key(containing) {
+                        Self::mark_recalled(containing, recall.clone());
+                    }
+                }
+
+                if done {
+                    <PendingRecalls<T>>::remove(&component_id);
+                    Self::deposit_event(<Event<T>>::RecallPropagated(component_id));
+                } else {
+                    <PendingRecalls<T>>::insert(&component_id, (recall, last));
+                }
+            }
+        }
+
+        /// Change the lifecycle status of a component that has custody.
+        fn set_status(component_id: &ComponentIdOf<T>, status: ComponentStatus) {
+            <ComponentCustody<T>>::mutate(component_id, |custody| {
+                if let Some(custody) = custody {
+                    custody.status = status;
+                    Self::deposit_event(<Event<T>>::ComponentStatusChanged(
+                        component_id.clone(),
+                        status,
+                    ));
+                }
+            });
+        }
+
+        /// The valid audit at `index` of a component, if it was added by `issuer`.
+        fn issued_audit(
+            component_id: &ComponentIdOf<T>,
+            issuer: &T::AccountId,
+            index: u32,
+        ) -> Result<AuditOf<T>, DispatchError> {
+            let audit = Self::audit(component_id, index).ok_or(<Error<T>>::AuditNotFound)?;
+            ensure!(audit.issuer == *issuer, <Error<T>>::NotAuditIssuer);
+            ensure!(audit.is_valid(), <Error<T>>::AuditNotValid);
+            Ok(audit)
+        }
+
+        /// The roles of which an auditor needs at least one to add an audit.
+        ///
+        /// * `assembly`: Whether the audit creates a component from other components.
+        /// * `creates`: Whether the audit creates the component.
+        fn required_roles(assembly: bool, creates: bool) -> &'static [Role] {
+            match (assembly, creates) {
+                (true, _) => &[Role::Assembler],
+                (false, true) => &[Role::Producer, Role::Registrar],
+                (false, false) => &[Role::Producer, Role::Transporter, Role::Certifier],
+            }
+        }
+
+        /// Decode the JWT in `audit_data` and verify its signature against the active key of
+        /// the auditor that is referenced by its `kid` header. Returns the id of that key.
+        ///
+        /// A JWT without `kid` header is accepted if the auditor has exactly one active key.
+        /// The key may be referenced by its DID URL, as Verifiable Credentials do, and the
+        /// `iss` claim may be a DID. Both must then be the auditor's DID.
+        fn verify_audit_jwt(
+            auditor: &T::AccountId,
+            info: &AuthorityInfoOf<T>,
+            audit_data: &str,
+        ) -> Result<(KeyIdOf<T>, Map<String, Value>), DispatchError> {
+            let jwt = Jwt::decode(audit_data).map_err(Error::<T>::from)?;
+            if let Some(issuer) = jwt.claims.get(ISSUER_CLAIM).and_then(Value::as_str) {
+                if issuer.starts_with(did::DID_PREFIX) {
+                    let account = did::account(issuer).ok_or(<Error<T>>::DidMismatch)?;
+                    ensure!(Self::is_did_of(auditor, &account), <Error<T>>::DidMismatch);
+                }
+            }
+            let kid = match jwt.header.kid.as_deref() {
+                Some(kid) => match did::split_key_url(kid) {
+                    Some((account, kid)) => {
+                        ensure!(Self::is_did_of(auditor, &account), <Error<T>>::DidMismatch);
+                        Some(kid)
+                    }
+                    None => Some(kid),
+                },
+                None => None,
+            };
+
+            let key = match kid {
+                Some(kid) => {
+                    let key = info.key(kid.as_bytes()).ok_or(<Error<T>>::UnknownKeyId)?;
+                    ensure!(key.status == KeyStatus::Active, <Error<T>>::KeyNotActive);
+                    key
+                }
+                None => {
+                    let mut active = info.active_keys();
+                    let key = active.next().ok_or(<Error<T>>::VerificationKeyNotFound)?;
+                    ensure!(active.next().is_none(), <Error<T>>::MissingKeyId);
+                    key
+                }
+            };
+
+            jwt.verify(&key.key).map_err(Error::<T>::from)?;
+            Ok((key.kid.clone(), jwt.claims))
+        }
+
+        /// Whether the account of a DID is `account`.
+        fn is_did_of(account: &T::AccountId, did_account: &[u8; 32]) -> bool {
+            T::AccountId::decode(&mut &did_account[..]).map_or(false, |decoded| &decoded == account)
+        }
+
+        /// Ensure that confidential data has distinct recipients, each an organization whose
+        /// referenced key is active and fits the key wrapping, and bound the wrapped keys.
+        fn wrapped_keys(
+            recipients: Vec<WrappedKey<T::AccountId, KeyId>>,
+        ) -> Result<BoundedVec<WrappedKeyOf<T>, T::MaxRecipients>, DispatchError> {
+            ensure!(!recipients.is_empty(), <Error<T>>::InvalidRecipients);
+            let mut accounts = BTreeSet::new();
+            let recipients = recipients
+                .into_iter()
+                .map(|wrapped| {
+                    ensure!(
+                        accounts.insert(wrapped.recipient.clone()),
+                        <Error<T>>::InvalidRecipients
+                    );
+                    let info = Self::authorities(&wrapped.recipient)
+                        .ok_or(<Error<T>>::AuthorityNotFound)?;
+                    let key = info.key(wrapped.kid.as_bytes()).ok_or(<Error<T>>::UnknownKeyId)?;
+                    ensure!(key.status == KeyStatus::Active, <Error<T>>::KeyNotActive);
+                    ensure!(
+                        wrapped.wrapping.supports(&key.key)
+                            && wrapped.wrapped_key.len() == wrapped.wrapping.wrapped_key_length(),
+                        <Error<T>>::InvalidWrappedKey
+                    );
+                    Ok(WrappedKey {
+                        recipient: wrapped.recipient,
+                        kid: key.kid.clone(),
+                        wrapping: wrapped.wrapping,
+                        wrapped_key: wrapped.wrapped_key,
+                    })
+                })
+                .collect::<Result<Vec<_>, DispatchError>>()?;
+            recipients.try_into().map_err(|_| <Error<T>>::InvalidRecipients.into())
+        }
+
+        /// Ensure that the claims of an audit conform to a version of a schema, by default the
+        /// latest one. A required field may only be missing if the schema lets audits conceal
+        /// it, and the audit must contain at least one digest of a concealed claim for each
+        /// missing required field.
+        fn ensure_conforms(
+            claims: &Map<String, Value>,
+            schema_id: &PayloadIdOf<T>,
+            version: Option<u32>,
+        ) -> DispatchResult {
+            let version = version.unwrap_or_else(|| Self::schema_version(schema_id));
+            let schema = Self::schema(schema_id, version).ok_or(<Error<T>>::UnknownSchema)?;
+            let mut concealed = 0;
+            for field in schema.fields.iter() {
+                // Field names are stored from text, so they are valid UTF-8.
+                let name = core::str::from_utf8(&field.name).unwrap_or_default();
+                match claims.get(name) {
+                    None | Some(Value::Null) => {
+                        if field.required {
+                            ensure!(field.concealable, <Error<T>>::MissingAuditField);
+                            concealed += 1;
+                        }
+                    }
+                    Some(value) => {
+                        ensure!(field.field_type.matches(value), <Error<T>>::AuditFieldTypeMismatch)
+                    }
+                }
+            }
+            let digests =
+                disclosure::digests(claims).map_err(|_| <Error<T>>::InvalidDisclosureDigests)?;
+            ensure!(digests.len() >= concealed, <Error<T>>::MissingAuditField);
+            Ok(())
+        }
+
+        /// Apply `f` to the record of the authorized organization `account`.
+        fn mutate_authority(
+            account: &T::AccountId,
+            f: impl FnOnce(&mut AuthorityInfoOf<T>) -> DispatchResult,
+        ) -> DispatchResult {
+            <Authorities<T>>::try_mutate(account, |val| -> DispatchResult {
+                f(val.as_mut().ok_or(<Error<T>>::AuthorityNotFound)?)
             })
         }
+
+        /// Convert a key id that is passed to an extrinsic into the stored representation.
+        fn key_id(kid: KeyId) -> Result<KeyIdOf<T>, Error<T>> {
+            ensure!(!kid.is_empty(), <Error<T>>::EmptyDataProvided);
+            Self::bounded(kid, <Error<T>>::KeyIdTooLong)
+        }
+
+        /// Add a new active key to `info`.
+        fn push_key(
+            info: &mut AuthorityInfoOf<T>,
+            kid: KeyIdOf<T>,
+            key: VerificationKey,
+        ) -> DispatchResult {
+            ensure!(info.key(&kid).is_none(), <Error<T>>::DuplicateKeyId);
+            info.keys
+                .try_push(AuthorityKey {
+                    kid,
+                    key,
+                    status: KeyStatus::Active,
+                })
+                .map_err(|_| <Error<T>>::MaxAuthorityKeysReached)?;
+            Ok(())
+        }
+
+        /// Convert `value` into a bounded string, failing with `error` if it is too long.
+        pub(crate) fn bounded<S: Get<u32>>(
+            value: String,
+            error: Error<T>,
+        ) -> Result<BoundedString<S>, Error<T>> {
+            value.into_bytes().try_into().map_err(|_| error)
+        }
+    }
+
+    impl<T> From<JwtError> for Error<T> {
+        fn from(error: JwtError) -> Self {
+            match error {
+                JwtError::Malformed => <Error<T>>::InvalidJwt,
+                JwtError::UnsupportedAlgorithm => <Error<T>>::UnsupportedJwtAlgorithm,
+                JwtError::AlgorithmMismatch => <Error<T>>::JwtAlgorithmMismatch,
+                JwtError::InvalidSignature => <Error<T>>::InvalidJwtSignature,
+            }
+        }
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,238 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Storage migrations of the supply chain pallet.
+
+use super::{Config, Pallet};
+use frame_support::{
+    traits::{GetStorageVersion, StorageVersion},
+    weights::Weight,
+};
+
+/// Run every migration that has not been applied to the on-chain storage yet.
+pub fn migrate<T: Config>() -> Weight {
+    let mut weight: Weight = 0;
+
+    if Pallet::<T>::on_chain_storage_version() < 1 {
+        weight = weight.saturating_add(v1::migrate::<T>());
+        StorageVersion::new(1).put::<Pallet<T>>();
+    }
+
+    weight
+}
+
+/// Check that the storage can be migrated, before `migrate` runs.
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+    if Pallet::<T>::on_chain_storage_version() < 1 {
+        v1::pre_upgrade::<T>()?;
+    }
+    Ok(())
+}
+
+/// Check that the migrations that ran were applied, after `migrate` ran.
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+    frame_support::ensure!(
+        Pallet::<T>::on_chain_storage_version() == Pallet::<T>::current_storage_version(),
+        "the storage version was not updated"
+    );
+    v1::post_upgrade::<T>()
+}
+
+/// Organization names become `AuthorityInfo` records with verification keys and roles, and
+/// audits move out of the components into their own storage, recording their issuer, status
+/// and the id of the key they were verified against. Strings and collections become bounded.
+///
+/// Organizations that were authorized before roles existed could add every kind of audit, so
+/// they are granted every role. The issuer of an audit is looked up by the organization name;
+/// audits of organizations that are no longer authorized get the default account as issuer and
+/// can't be revoked. Every audit is indexed by its id, which is derived from the block of the
+/// migration.
+///
+/// The lengths of names, ids and audits were already limited by the same constants before. Only
+/// `MaxComponents` was lowered, from `u16::MAX` to a bound that keeps components encodable within
+/// a block. Parts beyond that limit can't be kept; they are dropped with a warning, and the
+/// try-runtime checks refuse the upgrade if any component has that many. The quantities of
+/// components and parts are unknown.
+pub mod v1 {
+    extern crate alloc;
+
+    use crate::{
+        pallet::{AuditCount, Audits, AuditsById, Authorities, Components, MomentOf},
+        types::{Audit, AuditStatus, AuthorityInfo, BoundedString, Component, Roles},
+        Config, Pallet,
+    };
+    use alloc::{
+        collections::{BTreeMap, BTreeSet},
+        string::String,
+        vec::Vec,
+    };
+    use codec::Decode;
+    use frame_support::{
+        log,
+        storage::bounded_btree_set::BoundedBTreeSet,
+        traits::Get,
+        weights::Weight,
+    };
+    #[cfg(feature = "try-runtime")]
+    use frame_support::{
+        ensure,
+        storage::migration::storage_iter,
+        traits::{OnRuntimeUpgradeHelpersExt, PalletInfoAccess},
+    };
+
+    #[derive(Decode)]
+    struct OldAudit<T> {
+        auditor: String,
+        timestamp: T,
+        audit_data: String,
+    }
+
+    #[derive(Decode)]
+    struct OldComponent<T> {
+        audits: Vec<OldAudit<T>>,
+        component_of: BTreeSet<String>,
+        components: BTreeSet<String>,
+    }
+
+    /// Convert `value` into a bounded string, dropping the bytes beyond the bound.
+    fn truncate<S: Get<u32>>(value: String) -> BoundedString<S> {
+        let mut bytes = value.into_bytes();
+        bytes.truncate(S::get() as usize);
+        bytes.try_into().expect("truncated to the bound; qed")
+    }
+
+    /// Convert `set` into a bounded set, dropping the elements beyond the bound with a warning
+    /// that names the `field` of the component.
+    fn truncate_set<L: Get<u32>, S: Get<u32>>(
+        component_id: &[u8],
+        field: &str,
+        set: BTreeSet<String>,
+    ) -> BoundedBTreeSet<BoundedString<L>, S> {
+        if set.len() > S::get() as usize {
+            log::warn!(
+                target: "runtime::supply-chain",
+                "Dropping {} of the {} entries of `{}` of component {}",
+                set.len() - S::get() as usize,
+                set.len(),
+                field,
+                String::from_utf8_lossy(component_id),
+            );
+        }
+        set.into_iter()
+            .take(S::get() as usize)
+            .map(truncate::<L>)
+            .collect::<BTreeSet<_>>()
+            .try_into()
+            .expect("truncated to the bound; qed")
+    }
+
+    pub fn migrate<T: Config>() -> Weight {
+        let mut translated: u64 = 0;
+
+        <Authorities<T>>::translate::<String, _>(|_, name| {
+            translated += 1;
+            Some(AuthorityInfo::new(truncate(name), Roles::all()))
+        });
+
+        let issuers: BTreeMap<Vec<u8>, T::AccountId> = <Authorities<T>>::iter()
+            .map(|(account, info)| (info.name.into_inner(), account))
+            .collect();
+        <Components<T>>::translate::<OldComponent<MomentOf<T>>, _>(|component_id, old| {
+            translated += 1;
+            let count = old.audits.len() as u32;
+            for (index, audit) in old.audits.into_iter().enumerate() {
+                let index = index as u32;
+                let auditor = truncate(audit.auditor);
+                let issuer = issuers.get(&auditor[..]).cloned().unwrap_or_default();
+                let id = <Pallet<T>>::audit_id(&component_id, &issuer, None, index);
+                <AuditsById<T>>::insert(id, (component_id.clone(), index));
+                <Audits<T>>::insert(
+                    &component_id,
+                    index,
+                    Audit {
+                        issuer,
+                        auditor,
+                        timestamp: audit.timestamp,
+                        audit_data: truncate(audit.audit_data),
+                        // Audits from before key registration were not verified against a key.
+                        key_id: Default::default(),
+                        status: AuditStatus::Valid,
+                    },
+                );
+                translated += 2;
+            }
+            if count > 0 {
+                <AuditCount<T>>::insert(&component_id, count);
+                translated += 1;
+            }
+            Some(Component {
+                component_of: truncate_set(&component_id, "component_of", old.component_of),
+                // Quantities were not recorded before.
+                components: truncate_set::<T::MaxComponentIdLength, T::MaxComponents>(
+                    &component_id,
+                    "components",
+                    old.components,
+                )
+                .into_iter()
+                .map(|id| (id, None))
+                .collect::<BTreeMap<_, _>>()
+                .try_into()
+                .expect("bounded by the same limit; qed"),
+                ..Default::default()
+            })
+        });
+
+        T::DbWeight::get().reads_writes(translated, translated)
+    }
+
+    /// Components in the storage of the previous version.
+    #[cfg(feature = "try-runtime")]
+    fn old_components<T: Config>() -> impl Iterator<Item = (Vec<u8>, OldComponent<MomentOf<T>>)> {
+        storage_iter(Pallet::<T>::name().as_bytes(), b"Components")
+    }
+
+    /// Refuse the upgrade if a component would lose parts, and remember how many components
+    /// and audits there are.
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+        let (mut components, mut audits) = (0u32, 0u32);
+        for (_, component) in old_components::<T>() {
+            ensure!(
+                component.components.len() <= T::MaxComponents::get() as usize
+                    && component.component_of.len() <= T::MaxComponents::get() as usize,
+                "a component has more parts than MaxComponents allows"
+            );
+            components += 1;
+            audits += component.audits.len() as u32;
+        }
+        Pallet::<T>::set_temp_storage((components, audits), "v1_counts");
+        Ok(())
+    }
+
+    /// Check that every component and audit was migrated.
+    #[cfg(feature = "try-runtime")]
+    pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+        let (components, audits) = match Pallet::<T>::get_temp_storage::<(u32, u32)>("v1_counts") {
+            Some(counts) => counts,
+            // The migration didn't run.
+            None => return Ok(()),
+        };
+        ensure!(
+            <Components<T>>::iter_keys().count() as u32 == components,
+            "components were lost"
+        );
+        ensure!(<Audits<T>>::iter_keys().count() as u32 == audits, "audits were lost");
+        ensure!(<AuditsById<T>>::iter_keys().count() as u32 == audits, "audits were not indexed");
+        ensure!(
+            <Authorities<T>>::iter_values().all(|info| info.roles == Roles::all()),
+            "authorities were not granted every role"
+        );
+        Ok(())
+    }
+}
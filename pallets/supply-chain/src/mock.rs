@@ -6,16 +6,20 @@
 // merge, publish, distribute, sublicense, and/or sell copies of the Software.
 
 use super::types::{
-    MaxAuditSize, MaxAuditorNameLength, MaxAudits, MaxComponentIdLength, MaxComponents,
+    MaxAuditDocuments, MaxAuditSize, MaxAuditorNameLength, MaxAudits, MaxAuthorityKeys,
+    MaxCiphertextSize, MaxComponentIdLength, MaxComponents, MaxDocumentRefLength, MaxKeyIdLength,
+    MaxPayloadIdLength, MaxRecipients, MaxSchemaFields, MaxWebsiteLength,
 };
 use crate as pallet_supply_chain;
-use frame_support::parameter_types;
+use frame_support::{ord_parameter_types, parameter_types};
 use frame_system as system;
 use sp_core::H256;
+use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
 };
+use std::sync::Arc;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -29,7 +33,7 @@ frame_support::construct_runtime!(
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         Timestamp: pallet_timestamp,
-        SupplyChain: pallet_supply_chain::{Pallet, Call, Storage, Event<T>},
+        SupplyChain: pallet_supply_chain::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
     }
 );
 
@@ -75,20 +79,52 @@ impl pallet_timestamp::Config for Test {
     type WeightInfo = ();
 }
 
+ord_parameter_types! {
+    pub const Admin: u64 = 100;
+}
+
+parameter_types! {
+    // Small enough for the tests to exceed it with a few assemblies.
+    pub const MaxRecallPropagation: u32 = 4;
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type Extrinsic = UncheckedExtrinsic;
+    type OverarchingCall = Call;
+}
+
 impl pallet_supply_chain::Config for Test {
     type Event = Event;
+    type AdminOrigin = system::EnsureSignedBy<Admin, u64>;
     type MaxAuditorNameLength = MaxAuditorNameLength;
     type MaxAudits = MaxAudits;
     type MaxAuditSize = MaxAuditSize;
+    type MaxAuthorityKeys = MaxAuthorityKeys;
     type MaxComponents = MaxComponents;
     type MaxComponentIdLength = MaxComponentIdLength;
+    type MaxKeyIdLength = MaxKeyIdLength;
+    type MaxPayloadIdLength = MaxPayloadIdLength;
+    type MaxRecallPropagation = MaxRecallPropagation;
+    type MaxSchemaFields = MaxSchemaFields;
+    type MaxAuditDocuments = MaxAuditDocuments;
+    type MaxDocumentRefLength = MaxDocumentRefLength;
+    type MaxRecipients = MaxRecipients;
+    type MaxCiphertextSize = MaxCiphertextSize;
+    type MaxWebsiteLength = MaxWebsiteLength;
     type Timestamp = Timestamp;
+    type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    system::GenesisConfig::default()
+    let mut ext: sp_io::TestExternalities = system::GenesisConfig::default()
         .build_storage::<Test>()
         .unwrap()
-        .into()
+        .into();
+    // The benchmarks sign their audit JWTs with keys from the keystore.
+    ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+    ext
 }
@@ -5,23 +5,95 @@
 // the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
 // merge, publish, distribute, sublicense, and/or sell copies of the Software.
 
-use crate::{mock::*, types::ComponentId, Components, Config, Error};
-use frame_support::{assert_err, assert_ok};
+use crate::{
+    mock::*,
+    types::{
+        AuditPayload, AuditReason, AuditStatus, AuthorityStatus, Certification,
+        CertificationResult, ComponentId, ComponentStatus, ContentCipher, Custody, Direction,
+        DocumentRef, FieldType, KeyStatus, KeyWrapping, Location, PartUsage, Quantity, Recall,
+        RecallReason, Role, Roles, SchemaField, Unit, VerificationKey, WrappedKey,
+    },
+    Authorities, ComponentIdOf, Components, ConfidentialDataOf, Config, Error,
+};
+use codec::Encode;
+use std::{collections::BTreeSet, convert::TryInto};
+use frame_support::{
+    assert_err, assert_ok,
+    traits::{GetStorageVersion, StorageVersion},
+};
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+use sp_runtime::{
+    traits::ValidateUnsigned,
+    transaction_validity::{InvalidTransaction, TransactionSource},
+    DispatchError, Perbill,
+};
+
+const ADMIN: u64 = 100;
 
 fn setup_authorities(num: u8) -> Vec<u8> {
     let range: Vec<u8> = (0..num).collect();
 
     for auth_id in range.iter() {
         assert_ok!(SupplyChain::authorize(
-            Origin::root(),
+            Origin::signed(ADMIN),
             u64::from(*auth_id),
-            format!("AUTHORITY_{}", auth_id)
+            format!("AUTHORITY_{}", auth_id),
+            Role::ALL.to_vec()
+        ));
+        assert_ok!(SupplyChain::add_key(
+            Origin::signed(u64::from(*auth_id)),
+            "key-0".to_string(),
+            VerificationKey::Ed25519(signing_pair(*auth_id).public().0)
         ));
     }
 
     range
 }
 
+/// The stored representation of a component id.
+fn component(id: &str) -> ComponentIdOf<Test> {
+    id.as_bytes().to_vec().try_into().unwrap()
+}
+
+fn signing_pair(auth_id: u8) -> ed25519::Pair {
+    ed25519::Pair::from_seed(&[auth_id; 32])
+}
+
+fn encode_segment(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn signing_input(alg: &str, claims: &str) -> String {
+    format!(
+        "{}.{}",
+        encode_segment(format!(r#"{{"alg":"{}","typ":"JWT"}}"#, alg).as_bytes()),
+        encode_segment(claims.as_bytes())
+    )
+}
+
+/// Create an EdDSA JWT containing `claims` signed by the key of authority `auth_id`.
+fn signed_jwt(auth_id: u8, claims: &str) -> String {
+    signed_jwt_with_kid(auth_id, None, claims)
+}
+
+fn signed_jwt_with_kid(auth_id: u8, kid: Option<&str>, claims: &str) -> String {
+    let header = match kid {
+        Some(kid) => format!(r#"{{"alg":"EdDSA","kid":"{}","typ":"JWT"}}"#, kid),
+        None => r#"{"alg":"EdDSA","typ":"JWT"}"#.to_string(),
+    };
+    let input = format!(
+        "{}.{}",
+        encode_segment(header.as_bytes()),
+        encode_segment(claims.as_bytes())
+    );
+    let signature = signing_pair(auth_id).sign(input.as_bytes());
+    format!("{}.{}", input, encode_segment(&signature.0))
+}
+
+fn audit_jwt() -> String {
+    signed_jwt(0, r#"{"iss":"AUTHORITY_0","result":"passed"}"#)
+}
+
 // Test: authorize()
 #[test]
 fn add_authority_adds_authority() {
@@ -30,11 +102,14 @@ fn add_authority_adds_authority() {
         let org = "ExampleOrg".to_string();
 
         assert_ok!(SupplyChain::authorize(
-            Origin::root(),
+            Origin::signed(ADMIN),
             authority,
-            org.clone()
+            org.clone(),
+            vec![Role::Producer]
         ));
-        assert_eq!(SupplyChain::authorities(authority), org);
+        let info = SupplyChain::authorities(authority).unwrap();
+        assert_eq!(&info.name[..], org.as_bytes());
+        assert_eq!(info.roles, Roles::from(&[Role::Producer][..]));
     });
 }
 
@@ -42,12 +117,13 @@ fn add_authority_adds_authority() {
 fn add_authority_adds_authority_twice() {
     new_test_ext().execute_with(|| {
         assert_ok!(SupplyChain::authorize(
-            Origin::root(),
+            Origin::signed(ADMIN),
             0,
-            "ExampleOrg".to_string()
+            "ExampleOrg".to_string(),
+            vec![Role::Producer]
         ));
         assert_err!(
-            SupplyChain::authorize(Origin::root(), 0, "ExampleOrg".to_string()),
+            SupplyChain::authorize(Origin::signed(ADMIN), 0, "ExampleOrg".to_string(), vec![Role::Producer]),
             <Error<Test>>::AlreadyAuthorized
         );
     });
@@ -57,7 +133,7 @@ fn add_authority_adds_authority_twice() {
 fn add_authority_tries_to_add_empty_name() {
     new_test_ext().execute_with(|| {
         assert_err!(
-            SupplyChain::authorize(Origin::root(), 0, "".to_string()),
+            SupplyChain::authorize(Origin::signed(ADMIN), 0, "".to_string(), vec![]),
             <Error<Test>>::EmptyDataProvided
         );
     });
@@ -67,15 +143,41 @@ fn add_authority_tries_to_add_empty_name() {
 fn add_authority_tries_to_add_too_long_name() {
     new_test_ext().execute_with(|| {
         let long_name =
-            "s".repeat((<Test as Config>::MaxAuditorNameLength::get() + 1).into());
+            "s".repeat((<Test as Config>::MaxAuditorNameLength::get() + 1) as usize);
 
         assert_err!(
-            SupplyChain::authorize(Origin::root(), 0, long_name),
+            SupplyChain::authorize(Origin::signed(ADMIN), 0, long_name, vec![]),
             <Error<Test>>::AuditorNameTooLong
         );
     });
 }
 
+#[test]
+fn admin_calls_require_admin_origin() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+
+        for origin in [Origin::root(), Origin::signed(authority)] {
+            assert_err!(
+                SupplyChain::authorize(origin.clone(), 1, "ExampleOrg".to_string(), vec![]),
+                DispatchError::BadOrigin
+            );
+            assert_err!(
+                SupplyChain::deauthorize(origin.clone(), authority),
+                DispatchError::BadOrigin
+            );
+            assert_err!(
+                SupplyChain::grant_role(origin.clone(), authority, Role::Producer),
+                DispatchError::BadOrigin
+            );
+            assert_err!(
+                SupplyChain::revoke_key(origin, authority, "key-0".to_string()),
+                DispatchError::BadOrigin
+            );
+        }
+    });
+}
+
 // Test: deauthorize()
 #[test]
 fn remove_authority_removes_authority() {
@@ -84,11 +186,12 @@ fn remove_authority_removes_authority() {
         let org = "ExampleOrg".to_string();
 
         assert_ok!(SupplyChain::authorize(
-            Origin::root(),
+            Origin::signed(ADMIN),
             authority,
-            org.clone()
+            org.clone(),
+            vec![Role::Producer]
         ));
-        assert_ok!(SupplyChain::deauthorize(Origin::root(), authority));
+        assert_ok!(SupplyChain::deauthorize(Origin::signed(ADMIN), authority));
     });
 }
 
@@ -98,7 +201,7 @@ fn remove_authority_fails_removing_non_existing_authority() {
         let authority = 0;
 
         assert_err!(
-            SupplyChain::deauthorize(Origin::root(), authority),
+            SupplyChain::deauthorize(Origin::signed(ADMIN), authority),
             <Error<Test>>::AuthorityNotFound
         );
     });
@@ -112,7 +215,7 @@ fn add_audit_component_id_tool_long() {
         let audit_data = "1234".to_string();
         let components = None;
         let component_id: ComponentId =
-            "0".repeat((<Test as Config>::MaxComponentIdLength::get() + 1).into());
+            "0".repeat((<Test as Config>::MaxComponentIdLength::get() + 1) as usize);
 
         assert_err!(
             SupplyChain::checked_add_audit(authority, audit_data, components, component_id),
@@ -140,7 +243,7 @@ fn add_audit_no_data() {
 fn add_audit_too_much_data() {
     new_test_ext().execute_with(|| {
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".repeat((<Test as Config>::MaxAuditSize::get() + 1).into()).to_string();
+        let audit_data = "1".repeat((<Test as Config>::MaxAuditSize::get() + 1) as usize).to_string();
         let components = None;
         let component_id: ComponentId = "386a00b808e37a15".to_string();
 
@@ -170,7 +273,7 @@ fn add_audit_auditor_not_authorized() {
 fn add_audit_too_many_audits() {
     new_test_ext().execute_with(|| {
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".to_string();
+        let audit_data = audit_jwt();
         let components = None;
         let component_id: ComponentId = "386a00b808e37a15".to_string();
 
@@ -194,7 +297,7 @@ fn add_audit_too_many_audits() {
 fn add_assembly_audit_component_exists() {
     new_test_ext().execute_with(|| {
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".to_string();
+        let audit_data = audit_jwt();
         let mut components = None;
         let component_id: ComponentId = "386a00b808e37a15".to_string();
 
@@ -217,7 +320,7 @@ fn add_assembly_audit_component_exists() {
 fn add_assembly_audit_too_many_components() {
     new_test_ext().execute_with(|| {
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".to_string();
+        let audit_data = audit_jwt();
         let components = Some(vec![
             "1".to_string();
             (<Test as Config>::MaxComponents::get() as u32 + 1)
@@ -235,14 +338,14 @@ fn add_assembly_audit_too_many_components() {
 #[test]
 fn add_assembly_audit_too_many_component_of() {
     new_test_ext().execute_with(|| {
-        let components = (0..<Test as Config>::MaxComponents::get()).into_iter().map(|v| v.to_string()).collect();
-
-        <Components<Test>>::mutate("0", |comp| {
-            comp.component_of = components;
+        <Components<Test>>::mutate(component("0"), |comp| {
+            for v in 0..<Test as Config>::MaxComponents::get() {
+                comp.component_of.try_insert(component(&v.to_string())).unwrap();
+            }
         });
 
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".to_string();
+        let audit_data = audit_jwt();
         let components = Some(vec!["0".to_string()]);
         let component_id: ComponentId = "386a00b808e37a15".to_string();
 
@@ -257,7 +360,7 @@ fn add_assembly_audit_too_many_component_of() {
 fn add_audit_adds_audits() {
     new_test_ext().execute_with(|| {
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".to_string();
+        let audit_data = audit_jwt();
         let components = None;
         let component_id: ComponentId = "386a00b808e37a15".to_string();
 
@@ -269,11 +372,33 @@ fn add_audit_adds_audits() {
         ));
 
         let now = <Test as Config>::Timestamp::now();
-        let authority_name = SupplyChain::authorities(authority);
-        let audit = &SupplyChain::components(component_id).audits[0];
+        let authority_name = SupplyChain::authorities(authority).unwrap().name;
+        let audit = SupplyChain::audit(&component(&component_id), 0).unwrap();
         assert_eq!(audit.timestamp, now);
         assert_eq!(audit.auditor, authority_name);
-        assert_eq!(audit.audit_data, audit_data);
+        assert_eq!(&audit.audit_data[..], audit_data.as_bytes());
+    });
+}
+
+#[test]
+fn add_audit_leaves_component_untouched() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+
+        for _ in 0..2 {
+            assert_ok!(SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                None,
+                component_id.clone()
+            ));
+        }
+
+        assert!(!<Components<Test>>::contains_key(&component_id));
+        assert_eq!(SupplyChain::audit_count(&component(&component_id)), 2);
+        assert_eq!(SupplyChain::audits(&component(&component_id)).len(), 2);
+        assert_eq!(SupplyChain::audit(&component(&component_id), 2), None);
     });
 }
 
@@ -281,11 +406,14 @@ fn add_audit_adds_audits() {
 fn add_assembly_audit_adds_components_and_audit() {
     new_test_ext().execute_with(|| {
         let authority = setup_authorities(1).pop().unwrap().into();
-        let audit_data = "1".to_string();
+        let audit_data = audit_jwt();
         let comp_one = "0".to_string();
         let comp_two = "1".to_string();
         let components = Some(vec![comp_one.clone(), comp_two.clone()]);
         let component_id: ComponentId = "386a00b808e37a15".to_string();
+        for part in [&comp_one, &comp_two] {
+            assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, part.clone()));
+        }
 
         assert_ok!(SupplyChain::checked_add_audit(
             authority,
@@ -295,14 +423,2016 @@ fn add_assembly_audit_adds_components_and_audit() {
         ));
 
         let now = <Test as Config>::Timestamp::now();
-        let authority_name = SupplyChain::authorities(authority);
-        let created_component = &SupplyChain::components(component_id.clone());
-        let audit = &created_component.audits[0];
+        let authority_name = SupplyChain::authorities(authority).unwrap().name;
+        let created_component = SupplyChain::components(&component(&component_id));
+        let audit = SupplyChain::audit(&component(&component_id), 0).unwrap();
         assert_eq!(audit.timestamp, now);
         assert_eq!(audit.auditor, authority_name);
-        assert_eq!(audit.audit_data, audit_data);
-        assert_eq!(created_component.components, components.unwrap().into_iter().collect());
-        assert!(SupplyChain::components(comp_one).component_of.contains(&component_id));
-        assert!(SupplyChain::components(comp_two).component_of.contains(&component_id));
+        assert_eq!(&audit.audit_data[..], audit_data.as_bytes());
+        assert_eq!(created_component.components.len(), 2);
+        assert!(created_component.components.contains_key(&component(&comp_one)));
+        assert!(created_component.components.contains_key(&component(&comp_two)));
+        let component_id = component(&component_id);
+        assert!(SupplyChain::components(component(&comp_one)).component_of.contains(&component_id));
+        assert!(SupplyChain::components(component(&comp_two)).component_of.contains(&component_id));
+    });
+}
+#[test]
+fn add_assembly_audit_unknown_part() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let part: ComponentId = "0".to_string();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let components = Some(vec![part.clone(), "1".to_string()]);
+        assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, part));
+
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                components,
+                component_id.clone()
+            ),
+            <Error<Test>>::UnknownPart
+        );
+        assert!(!SupplyChain::exists(&component(&component_id)));
+        assert!(!SupplyChain::exists(&component("1")));
+        assert!(SupplyChain::used_in(&component("0")).is_empty());
+    });
+}
+
+#[test]
+fn add_assembly_audit_rejects_cycles() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let part: ComponentId = "0".to_string();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, part.clone()));
+
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                Some(vec![part.clone(), component_id.clone()]),
+                component_id.clone()
+            ),
+            <Error<Test>>::CycleDetected
+        );
+
+        // Once assembled, a part can't be reassembled from the assembly.
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            Some(vec![part.clone()]),
+            component_id.clone()
+        ));
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_jwt(), Some(vec![component_id]), part),
+            <Error<Test>>::ComponentAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn add_assembly_audit_consumes_quantities() {
+    new_test_ext().execute_with(|| {
+        let authority: u64 = setup_authorities(1).pop().unwrap().into();
+        let beans: ComponentId = "beans".to_string();
+        let usage = |quantity| PartUsage {
+            quantity,
+            yield_factor: Some(Perbill::from_percent(82)),
+        };
+        let roast = |part_usage, id: &str| {
+            SupplyChain::audit_assembly(
+                Origin::signed(authority),
+                audit_jwt(),
+                vec![(beans.clone(), part_usage)],
+                id.to_string(),
+            )
+        };
+        assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, beans.clone()));
+
+        // The produced quantity of a part must be known to consume it.
+        assert_err!(roast(Some(usage(kilograms(60))), "roast-0"), <Error<Test>>::UnknownQuantity);
+        assert_ok!(SupplyChain::record_quantity(
+            Origin::signed(authority),
+            beans.clone(),
+            kilograms(100)
+        ));
+
+        assert_ok!(roast(Some(usage(kilograms(60))), "roast-0"));
+        let roasted = SupplyChain::components(component("roast-0"));
+        assert_eq!(
+            roasted.components.get(&component(&beans)),
+            Some(&Some(usage(kilograms(60))))
+        );
+        assert_eq!(SupplyChain::components(component(&beans)).consumed, 60_000_000);
+
+        let grams = Quantity { amount: 40_001, unit: Unit::Gram };
+        assert_err!(roast(Some(usage(grams)), "roast-1"), <Error<Test>>::InsufficientQuantity);
+        let litres = Quantity { amount: 1, unit: Unit::Litre };
+        assert_err!(roast(Some(usage(litres)), "roast-1"), <Error<Test>>::UnitMismatch);
+        assert_ok!(roast(Some(usage(kilograms(40))), "roast-1"));
+        assert_eq!(SupplyChain::components(component(&beans)).consumed, 100_000_000);
+
+        // Nothing is left of the part.
+        let status = SupplyChain::custody(component(&beans)).unwrap().status;
+        assert_eq!(status, ComponentStatus::Consumed);
+        assert_err!(roast(None, "roast-2"), <Error<Test>>::ComponentUnavailable);
+    });
+}
+
+#[test]
+fn record_quantity_fails() {
+    new_test_ext().execute_with(|| {
+        let authorities = setup_authorities(2);
+        let (creator, other) = (authorities[0].into(), authorities[1].into());
+        let beans: ComponentId = "beans".to_string();
+
+        assert_err!(
+            SupplyChain::record_quantity(Origin::signed(creator), beans.clone(), kilograms(1)),
+            <Error<Test>>::AuditNotFound
+        );
+        assert_ok!(SupplyChain::checked_add_audit(creator, audit_jwt(), None, beans.clone()));
+        assert_err!(
+            SupplyChain::record_quantity(Origin::signed(other), beans.clone(), kilograms(1)),
+            <Error<Test>>::NotAuditIssuer
+        );
+        assert_ok!(SupplyChain::record_quantity(
+            Origin::signed(creator),
+            beans.clone(),
+            kilograms(10)
+        ));
+        assert_err!(
+            SupplyChain::record_quantity(
+                Origin::signed(creator),
+                beans.clone(),
+                Quantity { amount: 10, unit: Unit::Piece }
+            ),
+            <Error<Test>>::UnitMismatch
+        );
+
+        assert_ok!(SupplyChain::audit_assembly(
+            Origin::signed(creator),
+            audit_jwt(),
+            vec![(
+                beans.clone(),
+                Some(PartUsage { quantity: kilograms(8), yield_factor: None })
+            )],
+            "roast".to_string(),
+        ));
+        assert_err!(
+            SupplyChain::record_quantity(Origin::signed(creator), beans.clone(), kilograms(7)),
+            <Error<Test>>::InsufficientQuantity
+        );
+        assert_ok!(SupplyChain::record_quantity(
+            Origin::signed(creator),
+            beans.clone(),
+            Quantity { amount: 8_000, unit: Unit::Gram }
+        ));
+        assert_eq!(
+            SupplyChain::components(component(&beans)).quantity,
+            Some(Quantity { amount: 8_000, unit: Unit::Gram })
+        );
+    });
+}
+
+/// Create a component with the given produced quantity.
+fn setup_component(authority: u64, id: &str, quantity: Quantity) {
+    assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, id.to_string()));
+    assert_ok!(SupplyChain::record_quantity(Origin::signed(authority), id.to_string(), quantity));
+}
+
+fn kilograms(amount: u64) -> Quantity {
+    Quantity { amount, unit: Unit::Kilogram }
+}
+
+// Test: split_component(), merge_components() and transform_component()
+#[test]
+fn split_component_creates_lots() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authority = setup_authorities(1).pop().unwrap().into();
+        setup_component(authority, "batch", kilograms(10));
+
+        assert_ok!(SupplyChain::split_component(
+            Origin::signed(authority),
+            audit_jwt(),
+            "batch".to_string(),
+            vec![("lot-0".to_string(), kilograms(4)), ("lot-1".to_string(), kilograms(6))],
+        ));
+
+        for (lot, amount) in [("lot-0", 4), ("lot-1", 6)] {
+            let lot = SupplyChain::components(component(lot));
+            assert_eq!(lot.quantity, Some(kilograms(amount)));
+            assert_eq!(
+                lot.components.get(&component("batch")),
+                Some(&Some(PartUsage { quantity: kilograms(amount), yield_factor: None }))
+            );
+        }
+        assert_eq!(
+            SupplyChain::used_in(&component("batch")),
+            vec![component("lot-0"), component("lot-1")]
+        );
+        assert_eq!(SupplyChain::components(component("batch")).consumed, 10_000_000);
+        assert_eq!(SupplyChain::audit_count(component("lot-1")), 1);
+        System::assert_last_event(Event::SupplyChain(crate::Event::ComponentSplit(
+            component("batch"),
+            vec![component("lot-0"), component("lot-1")],
+        )));
+
+        assert_err!(
+            SupplyChain::split_component(
+                Origin::signed(authority),
+                audit_jwt(),
+                "batch".to_string(),
+                vec![("lot-2".to_string(), kilograms(1))],
+            ),
+            <Error<Test>>::InsufficientQuantity
+        );
+        assert!(!SupplyChain::exists(&component("lot-2")));
+        assert_err!(
+            SupplyChain::split_component(
+                Origin::signed(authority),
+                audit_jwt(),
+                "batch".to_string(),
+                vec![]
+            ),
+            <Error<Test>>::EmptyDataProvided
+        );
     });
-}
\ No newline at end of file
+}
+
+#[test]
+fn merge_components_sums_quantities() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authority = setup_authorities(1).pop().unwrap().into();
+        setup_component(authority, "lot-0", kilograms(4));
+        setup_component(authority, "lot-1", kilograms(6));
+        let merge = |components: Vec<(&str, Quantity)>| {
+            SupplyChain::merge_components(
+                Origin::signed(authority),
+                audit_jwt(),
+                components.into_iter().map(|(id, quantity)| (id.to_string(), quantity)).collect(),
+                "pallet".to_string(),
+            )
+        };
+
+        assert_err!(merge(vec![]), <Error<Test>>::EmptyDataProvided);
+        assert_err!(
+            merge(vec![("lot-0", kilograms(4)), ("lot-0", kilograms(4))]),
+            <Error<Test>>::DuplicatePart
+        );
+        assert_err!(
+            merge(vec![
+                ("lot-0", kilograms(4)),
+                ("lot-1", Quantity { amount: 1, unit: Unit::Gram })
+            ]),
+            <Error<Test>>::UnitMismatch
+        );
+        assert_err!(
+            merge(vec![("lot-0", kilograms(u64::MAX)), ("lot-1", kilograms(1))]),
+            <Error<Test>>::QuantityOverflow
+        );
+
+        assert_ok!(merge(vec![("lot-0", kilograms(4)), ("lot-1", kilograms(3))]));
+        let merged = SupplyChain::components(component("pallet"));
+        assert_eq!(merged.quantity, Some(kilograms(7)));
+        assert_eq!(merged.components.len(), 2);
+        assert_eq!(SupplyChain::components(component("lot-1")).consumed, 3_000_000);
+        System::assert_last_event(Event::SupplyChain(crate::Event::ComponentsMerged(
+            vec![component("lot-0"), component("lot-1")],
+            component("pallet"),
+        )));
+    });
+}
+
+#[test]
+fn transform_component_records_yield() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authority = setup_authorities(1).pop().unwrap().into();
+        setup_component(authority, "green", kilograms(100));
+
+        assert_ok!(SupplyChain::transform_component(
+            Origin::signed(authority),
+            audit_jwt(),
+            "green".to_string(),
+            kilograms(50),
+            "roasted".to_string(),
+            kilograms(41),
+        ));
+        let roasted = SupplyChain::components(component("roasted"));
+        assert_eq!(roasted.quantity, Some(kilograms(41)));
+        assert_eq!(
+            roasted.components.get(&component("green")),
+            Some(&Some(PartUsage {
+                quantity: kilograms(50),
+                yield_factor: Some(Perbill::from_percent(82))
+            }))
+        );
+        System::assert_last_event(Event::SupplyChain(crate::Event::ComponentTransformed(
+            component("green"),
+            component("roasted"),
+        )));
+
+        // The yield can't exceed 100%.
+        assert_err!(
+            SupplyChain::transform_component(
+                Origin::signed(authority),
+                audit_jwt(),
+                "green".to_string(),
+                kilograms(10),
+                "watered".to_string(),
+                kilograms(11),
+            ),
+            <Error<Test>>::ProducedExceedsConsumed
+        );
+
+        // Quantities that measure different things have no yield.
+        assert_ok!(SupplyChain::transform_component(
+            Origin::signed(authority),
+            audit_jwt(),
+            "green".to_string(),
+            kilograms(50),
+            "bags".to_string(),
+            Quantity { amount: 10, unit: Unit::Piece },
+        ));
+        assert_eq!(
+            SupplyChain::components(component("bags")).components.get(&component("green")),
+            Some(&Some(PartUsage { quantity: kilograms(50), yield_factor: None }))
+        );
+        assert_err!(
+            SupplyChain::transform_component(
+                Origin::signed(authority),
+                audit_jwt(),
+                "green".to_string(),
+                kilograms(1),
+                "ground".to_string(),
+                kilograms(1),
+            ),
+            <Error<Test>>::InsufficientQuantity
+        );
+    });
+}
+
+// Test: transfer_custody(), accept_custody() and destroy_component()
+#[test]
+fn custody_is_handed_over() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authorities = setup_authorities(3);
+        let (producer, carrier, other) =
+            (authorities[0].into(), authorities[1].into(), authorities[2].into());
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(
+            producer,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+        assert_eq!(
+            SupplyChain::custody(component(&component_id)),
+            Some(Custody { custodian: producer, pending: None, status: ComponentStatus::Created })
+        );
+
+        assert_err!(
+            SupplyChain::transfer_custody(Origin::signed(carrier), component_id.clone(), other),
+            <Error<Test>>::NotCustodian
+        );
+        assert_err!(
+            SupplyChain::transfer_custody(Origin::signed(producer), component_id.clone(), ADMIN),
+            <Error<Test>>::AuthorityNotFound
+        );
+        assert_ok!(SupplyChain::transfer_custody(
+            Origin::signed(producer),
+            component_id.clone(),
+            other
+        ));
+        // The component can be redirected while it is in transit.
+        assert_ok!(SupplyChain::transfer_custody(
+            Origin::signed(producer),
+            component_id.clone(),
+            carrier
+        ));
+        System::assert_last_event(Event::SupplyChain(crate::Event::CustodyOffered(
+            component(&component_id),
+            producer,
+            carrier,
+        )));
+        assert_eq!(
+            SupplyChain::custody(component(&component_id)),
+            Some(Custody {
+                custodian: producer,
+                pending: Some(carrier),
+                status: ComponentStatus::InTransit
+            })
+        );
+
+        assert_err!(
+            SupplyChain::accept_custody(Origin::signed(other), component_id.clone()),
+            <Error<Test>>::NoPendingTransfer
+        );
+        assert_ok!(SupplyChain::accept_custody(Origin::signed(carrier), component_id.clone()));
+        System::assert_last_event(Event::SupplyChain(crate::Event::CustodyAccepted(
+            component(&component_id),
+            producer,
+            carrier,
+        )));
+        assert_eq!(
+            SupplyChain::custody(component(&component_id)),
+            Some(Custody { custodian: carrier, pending: None, status: ComponentStatus::Received })
+        );
+        assert_err!(
+            SupplyChain::accept_custody(Origin::signed(carrier), component_id.clone()),
+            <Error<Test>>::NoPendingTransfer
+        );
+
+        assert_err!(
+            SupplyChain::destroy_component(Origin::signed(producer), component_id.clone()),
+            <Error<Test>>::NotCustodian
+        );
+        assert_ok!(SupplyChain::destroy_component(Origin::signed(carrier), component_id.clone()));
+        assert_eq!(
+            SupplyChain::custody(component(&component_id)).unwrap().status,
+            ComponentStatus::Destroyed
+        );
+        assert_err!(
+            SupplyChain::transfer_custody(Origin::signed(carrier), component_id, producer),
+            <Error<Test>>::ComponentUnavailable
+        );
+    });
+}
+
+#[test]
+fn custody_restricts_audits_and_assemblies() {
+    new_test_ext().execute_with(|| {
+        let authorities = setup_authorities(2);
+        let (producer, transporter) = (authorities[0].into(), authorities[1].into());
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(
+            producer,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+        assert_ok!(SupplyChain::revoke_role(Origin::signed(ADMIN), transporter, Role::Certifier));
+
+        // Only the custodian and certifiers can audit the component.
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                transporter,
+                signed_jwt(1, "{}"),
+                None,
+                component_id.clone()
+            ),
+            <Error<Test>>::NotCustodian
+        );
+        assert_ok!(SupplyChain::grant_role(Origin::signed(ADMIN), transporter, Role::Certifier));
+        assert_ok!(SupplyChain::checked_add_audit(
+            transporter,
+            signed_jwt(1, "{}"),
+            None,
+            component_id.clone()
+        ));
+
+        // Only the custodian can assemble the component, and not while it is in transit.
+        let assemble = |auditor: u64, jwt: String| {
+            let parts = Some(vec![component_id.clone()]);
+            SupplyChain::checked_add_audit(auditor, jwt, parts, "assembly".to_string())
+        };
+        assert_err!(assemble(transporter, signed_jwt(1, "{}")), <Error<Test>>::NotCustodian);
+        assert_ok!(SupplyChain::transfer_custody(
+            Origin::signed(producer),
+            component_id.clone(),
+            transporter
+        ));
+        assert_err!(assemble(producer, audit_jwt()), <Error<Test>>::ComponentUnavailable);
+        assert_ok!(SupplyChain::accept_custody(Origin::signed(transporter), component_id.clone()));
+        assert_ok!(assemble(transporter, signed_jwt(1, "{}")));
+        assert_eq!(
+            SupplyChain::custody(component(&component_id)).unwrap().status,
+            ComponentStatus::Consumed
+        );
+    });
+}
+
+// Test: recall_component() and propagate_recall()
+#[test]
+fn recall_component_fails() {
+    new_test_ext().execute_with(|| {
+        let authorities = setup_authorities(2);
+        let (producer, certifier) = (authorities[0].into(), authorities[1].into());
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(
+            producer,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+        assert_ok!(SupplyChain::revoke_role(Origin::signed(ADMIN), producer, Role::Certifier));
+
+        assert_err!(
+            SupplyChain::recall_component(
+                Origin::signed(producer),
+                component_id.clone(),
+                RecallReason::Defect
+            ),
+            <Error<Test>>::MissingRole
+        );
+        assert_err!(
+            SupplyChain::recall_component(
+                Origin::signed(ADMIN + 1),
+                component_id.clone(),
+                RecallReason::Defect
+            ),
+            <Error<Test>>::Unauthorized
+        );
+        assert_err!(
+            SupplyChain::recall_component(
+                Origin::signed(certifier),
+                "unknown".to_string(),
+                RecallReason::Defect
+            ),
+            <Error<Test>>::ComponentNotFound
+        );
+        assert_ok!(SupplyChain::recall_component(
+            Origin::signed(certifier),
+            component_id.clone(),
+            RecallReason::Contamination
+        ));
+        assert_err!(
+            SupplyChain::recall_component(Origin::signed(ADMIN), component_id, RecallReason::Fraud),
+            <Error<Test>>::AlreadyRecalled
+        );
+        assert_err!(
+            SupplyChain::propagate_recall(Origin::signed(certifier)),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn recall_propagates_to_assemblies() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let assemble = |parts: Vec<(&str, Option<PartUsage>)>, id: &str| {
+            let parts = parts.into_iter().map(|(part, usage)| (part.to_string(), usage)).collect();
+            assert_ok!(SupplyChain::audit_assembly(
+                Origin::signed(authority),
+                audit_jwt(),
+                parts,
+                id.to_string()
+            ));
+        };
+        setup_component(authority, "cotton", kilograms(10));
+        setup_component(authority, "dye", kilograms(1));
+        for yarn in ["yarn-1", "yarn-2", "yarn-3", "yarn-4", "yarn-5"] {
+            let usage = PartUsage { quantity: kilograms(1), yield_factor: None };
+            assemble(vec![("cotton", Some(usage))], yarn);
+        }
+        assemble(vec![("yarn-1", None), ("dye", None)], "shirt");
+        assemble(vec![("shirt", None)], "package");
+        let call = crate::Call::propagate_recall {};
+        assert_eq!(
+            SupplyChain::validate_unsigned(TransactionSource::Local, &call),
+            InvalidTransaction::Call.into()
+        );
+
+        assert_ok!(SupplyChain::recall_component(
+            Origin::signed(ADMIN),
+            "cotton".to_string(),
+            RecallReason::Contamination
+        ));
+        System::assert_has_event(Event::SupplyChain(crate::Event::ComponentRecalled(
+            component("yarn-1"),
+            component("cotton"),
+            RecallReason::Contamination,
+        )));
+        // MaxRecallPropagation is 4, so only the cotton and three yarns are visited.
+        assert!(SupplyChain::recall(component("yarn-3")).is_some());
+        assert!(SupplyChain::recall(component("yarn-4")).is_none());
+        assert!(SupplyChain::validate_unsigned(TransactionSource::Local, &call).is_ok());
+        assert_eq!(
+            SupplyChain::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Call.into()
+        );
+
+        let mut continuations = 0;
+        while SupplyChain::recall_pending() {
+            assert_ok!(SupplyChain::propagate_recall(Origin::none()));
+            continuations += 1;
+            assert!(continuations < 10);
+        }
+        let propagated = crate::Event::RecallPropagated(component("cotton"));
+        System::assert_has_event(Event::SupplyChain(propagated));
+        for id in ["cotton", "yarn-1", "yarn-4", "yarn-5", "shirt", "package"] {
+            assert_eq!(
+                SupplyChain::recall(component(id)),
+                Some(Recall { source: component("cotton"), reason: RecallReason::Contamination })
+            );
+            assert_eq!(
+                SupplyChain::custody(component(id)).unwrap().status,
+                ComponentStatus::Recalled
+            );
+        }
+        assert_eq!(SupplyChain::recall(component("dye")), None);
+
+        // Recalled components can't go into assemblies.
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                Some(vec!["yarn-2".to_string()]),
+                "sweater".to_string()
+            ),
+            <Error<Test>>::ComponentUnavailable
+        );
+    });
+}
+
+fn certificate(
+    standard: &str,
+    result: CertificationResult,
+    valid_until: Option<u64>,
+) -> AuditPayload<u64, String> {
+    AuditPayload {
+        audit_type: "organic-inspection".to_string(),
+        issued_at: 100,
+        valid_until,
+        location: Some(Location::Gln(4012345000009)),
+        certification: Some(Certification { standard: standard.to_string(), result }),
+    }
+}
+
+// Test: audit_with_payload() and certified_components()
+#[test]
+fn audit_with_payload_stores_payload() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let payload = certificate("EU-2018/848", CertificationResult::Passed, Some(200));
+
+        assert_ok!(SupplyChain::audit_with_payload(
+            Origin::signed(authority),
+            audit_jwt(),
+            payload.clone(),
+            component_id.clone()
+        ));
+        let stored = SupplyChain::audit_payload(component(&component_id), 0).unwrap();
+        let standard = stored.certification.clone().unwrap().standard;
+        assert_eq!(stored.try_map_ids(|id| String::from_utf8(id.into_inner())), Ok(payload));
+        assert_eq!(SupplyChain::certification(standard, component(&component_id)), Some(0));
+        // Audits without payload have no entry.
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+        assert_eq!(SupplyChain::audit_payload(component(&component_id), 1), None);
+    });
+}
+
+#[test]
+fn audit_with_payload_fails() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let audit = |payload| {
+            SupplyChain::audit_with_payload(
+                Origin::signed(authority),
+                audit_jwt(),
+                payload,
+                component_id.clone(),
+            )
+        };
+
+        let standard = "x".repeat((<Test as Config>::MaxPayloadIdLength::get() + 1) as usize);
+        assert_err!(
+            audit(certificate(&standard, CertificationResult::Passed, None)),
+            <Error<Test>>::PayloadIdTooLong
+        );
+        assert_err!(
+            audit(certificate("EU-2018/848", CertificationResult::Passed, Some(99))),
+            <Error<Test>>::InvalidValidityPeriod
+        );
+        for location in [
+            Location::Gps { latitude: 90_000_001, longitude: 0 },
+            Location::Gps { latitude: 0, longitude: -180_000_001 },
+            Location::Gln(4012345000008),
+            Location::Gln(40123450000090),
+        ] {
+            let payload = AuditPayload {
+                location: Some(location),
+                ..certificate("EU-2018/848", CertificationResult::Passed, None)
+            };
+            assert_err!(audit(payload), <Error<Test>>::InvalidLocation);
+        }
+        assert_eq!(SupplyChain::audit_count(component(&component_id)), 0);
+    });
+}
+
+#[test]
+fn certified_components_are_valid_and_passed() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let audit = |id: &str, payload| {
+            assert_ok!(SupplyChain::audit_with_payload(
+                Origin::signed(authority),
+                audit_jwt(),
+                payload,
+                id.to_string()
+            ));
+        };
+        let organic = "EU-2018/848";
+        audit("certified", certificate(organic, CertificationResult::Passed, Some(200)));
+        audit("unlimited", certificate(organic, CertificationResult::Passed, None));
+        audit("failed", certificate(organic, CertificationResult::Failed, Some(200)));
+        audit("expired", certificate(organic, CertificationResult::Passed, Some(120)));
+        audit("other", certificate("ISO-22000", CertificationResult::Passed, Some(200)));
+        audit("revoked", certificate(organic, CertificationResult::Passed, Some(200)));
+        assert_ok!(SupplyChain::revoke_audit(
+            Origin::signed(authority),
+            "revoked".to_string(),
+            0,
+            AuditReason::Other
+        ));
+        // Only the latest certification under a standard counts.
+        audit("recertified", certificate(organic, CertificationResult::Passed, Some(200)));
+        audit("recertified", certificate(organic, CertificationResult::Failed, Some(200)));
+
+        let standard = organic.as_bytes().to_vec().try_into().unwrap();
+        let mut certified = SupplyChain::certified_components(&standard, 150, 0, 10);
+        certified.sort();
+        assert_eq!(certified, vec![(component("certified"), 0), (component("unlimited"), 0)]);
+        assert_eq!(
+            SupplyChain::certified_components(&standard, 300, 0, 10),
+            vec![(component("unlimited"), 0)]
+        );
+        assert_eq!(SupplyChain::certified_components(&standard, 99, 0, 10), vec![]);
+        assert_eq!(SupplyChain::certified_components(&standard, 150, 1, 10).len(), 1);
+        assert_eq!(SupplyChain::certified_components(&standard, 150, 0, 1).len(), 1);
+    });
+}
+
+fn field(name: &str, field_type: FieldType, required: bool) -> SchemaField<String> {
+    SchemaField { name: name.to_string(), field_type, required, concealable: false }
+}
+
+fn harvest_fields() -> Vec<SchemaField<String>> {
+    vec![
+        field("harvestDate", FieldType::String, true),
+        field("weight", FieldType::Number, true),
+        field("organic", FieldType::Boolean, false),
+    ]
+}
+
+// Test: cid
+#[test]
+fn cid_references_content_digest() {
+    use crate::cid::{cid, digest};
+
+    let hello = cid(b"hello world");
+    assert_eq!(hello, "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e");
+    assert_eq!(digest(&hello), Some(sp_io::hashing::sha2_256(b"hello world")));
+    assert_eq!(digest(&hello.to_uppercase()), None);
+    assert_eq!(digest(&hello[..hello.len() - 1]), None);
+    // A CIDv1 of dag-pb content is not a raw document.
+    assert_eq!(digest("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"), None);
+}
+
+// Test: disclosure
+#[test]
+fn disclosures_reveal_concealed_claims() {
+    use crate::disclosure::{conceal, digest, disclose, split_presentation, DisclosureError};
+
+    // Example of the SD-JWT specification.
+    let family_name = "WyI2cU1RdlJMNWhhaiIsICJmYW1pbHlfbmFtZSIsICJNw7ZiaXVzIl0";
+    assert_eq!(digest(family_name), "uutlBuYeMDyjLLTpf6Jxi7yNkEF35jdyWMn9U7b_RYY");
+
+    let mut claims: serde_json::Map<_, _> = serde_json::from_str(
+        r#"{"iss":"AUTHORITY_0","deforestationFree":true,"supplier":"Farm 7","price":12}"#,
+    )
+    .unwrap();
+    let deforestation_free =
+        conceal(&mut claims, "deforestationFree", b"saltsaltsaltsalt").unwrap();
+    assert_eq!(
+        deforestation_free,
+        "WyJjMkZzZEhOaGJIUnpZV3gwYzJGc2RBIiwiZGVmb3Jlc3RhdGlvbkZyZWUiLHRydWVd"
+    );
+    let supplier = conceal(&mut claims, "supplier", &[1; 16]).unwrap();
+    assert_eq!(conceal(&mut claims, "unknown", &[2; 16]), None);
+    assert_eq!(conceal(&mut claims, "_sd", &[2; 16]), None);
+    assert!(!claims.contains_key("deforestationFree"));
+    assert_eq!(claims["_sd"].as_array().unwrap().len(), 2);
+    assert_eq!(claims["_sd_alg"], "sha-256");
+
+    let disclosed = disclose(&claims, &[&deforestation_free]).unwrap();
+    assert_eq!(disclosed.len(), 1);
+    assert_eq!(disclosed["deforestationFree"], true);
+    assert_eq!(disclose(&claims, &[]).unwrap().len(), 0);
+    assert_eq!(disclose(&claims, &[&supplier, &deforestation_free]).unwrap().len(), 2);
+    assert_eq!(disclose(&claims, &[&supplier, &supplier]), Err(DisclosureError::DuplicateClaim));
+    assert_eq!(disclose(&claims, &[family_name]), Err(DisclosureError::UnknownDigest));
+    claims.insert("_sd_alg".into(), "sha-512".into());
+    assert_eq!(disclose(&claims, &[&supplier]), Err(DisclosureError::UnsupportedAlgorithm));
+
+    let presentation = format!("header.claims.signature~{}~{}~", supplier, deforestation_free);
+    assert_eq!(
+        split_presentation(&presentation),
+        ("header.claims.signature", vec![&supplier[..], &deforestation_free[..]])
+    );
+}
+
+#[test]
+fn audits_conceal_claims() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let audit = |claims: &str| {
+            let audit_data = signed_jwt(0, claims);
+            SupplyChain::audit(Origin::signed(authority), audit_data, component_id.clone())
+        };
+        let digest = crate::disclosure::digest("WyJzYWx0IiwibG90IiwiTDQyIl0");
+
+        assert_err!(audit(r#"{"_sd":"digest"}"#), <Error<Test>>::InvalidDisclosureDigests);
+        assert_err!(audit(r#"{"_sd":["digest"]}"#), <Error<Test>>::InvalidDisclosureDigests);
+        assert_err!(
+            audit(&format!(r#"{{"_sd":["{0}","{0}"]}}"#, digest)),
+            <Error<Test>>::InvalidDisclosureDigests
+        );
+        assert_err!(
+            audit(&format!(r#"{{"_sd":["{}"],"_sd_alg":"sha-512"}}"#, digest)),
+            <Error<Test>>::InvalidDisclosureDigests
+        );
+        assert_ok!(audit(&format!(r#"{{"_sd":["{}"],"_sd_alg":"sha-256"}}"#, digest)));
+
+        // Required fields of the schema may only be among the concealed claims if the schema
+        // lets audits conceal them, and the audit has a digest for each missing one.
+        assert_ok!(SupplyChain::register_schema(
+            Origin::signed(ADMIN),
+            "harvest".to_string(),
+            harvest_fields()
+        ));
+        let concealable = harvest_fields()
+            .into_iter()
+            .map(|field| SchemaField { concealable: field.required, ..field })
+            .collect();
+        assert_ok!(SupplyChain::register_schema(
+            Origin::signed(ADMIN),
+            "concealed".to_string(),
+            concealable
+        ));
+        let other = crate::disclosure::digest("WyJzYWx0Iiwid2VpZ2h0IiwxMi41XQ");
+        assert_err!(audit(r#"{"schema":"harvest"}"#), <Error<Test>>::MissingAuditField);
+        assert_err!(
+            audit(&format!(r#"{{"schema":"harvest","_sd":["{}","{}"]}}"#, digest, other)),
+            <Error<Test>>::MissingAuditField
+        );
+        assert_err!(audit(r#"{"schema":"concealed","_sd":[]}"#), <Error<Test>>::MissingAuditField);
+        assert_err!(
+            audit(&format!(r#"{{"schema":"concealed","_sd":["{}"]}}"#, digest)),
+            <Error<Test>>::MissingAuditField
+        );
+        let partial = format!(r#"{{"schema":"concealed","weight":12.5,"_sd":["{}"]}}"#, digest);
+        assert_ok!(audit(&partial));
+        assert_ok!(audit(&format!(r#"{{"schema":"concealed","_sd":["{}","{}"]}}"#, digest, other)));
+        assert_eq!(SupplyChain::audit_count(component(&component_id)), 3);
+    });
+}
+
+// Test: did
+#[test]
+fn dids_identify_accounts() {
+    use crate::did::{account, decode_base58, did, encode_base58, split_key_url};
+
+    let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public().0;
+    let alice_did = did(&alice, 42);
+    assert_eq!(alice_did, "did:greenaureus:5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+    assert_eq!(account(&alice_did), Some(alice));
+    assert_eq!(account(&did(&alice, 1000)), Some(alice));
+    assert_eq!(account("did:greenaureus:5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"), None);
+    assert_eq!(account("did:web:example.com"), None);
+    assert_eq!(split_key_url(&format!("{}#key-0", alice_did)), Some((alice, "key-0")));
+    assert_eq!(split_key_url(&alice_did), None);
+
+    assert_eq!(encode_base58(b"hello world"), "StV1DL6CwTryKyV");
+    assert_eq!(encode_base58(&[0, 0, 1]), "112");
+    assert_eq!(decode_base58("112"), Some(vec![0, 0, 1]));
+    assert_eq!(decode_base58("0"), None);
+}
+
+#[test]
+fn audits_reference_auditor_did() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(2)[0].into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        // The mock's accounts are decoded from the start of a DID's account.
+        let did_of = |account: u64| {
+            let mut bytes = [0; 32];
+            bytes[..8].copy_from_slice(&account.to_le_bytes());
+            crate::did::did(&bytes, 42)
+        };
+        let audit = |kid: &str, issuer: &str| {
+            let claims = format!(r#"{{"iss":"{}","vc":{{"credentialSubject":{{}}}}}}"#, issuer);
+            let audit_data = signed_jwt_with_kid(0, Some(kid), &claims);
+            SupplyChain::audit(Origin::signed(authority), audit_data, component_id.clone())
+        };
+
+        let key_url = format!("{}#key-0", did_of(0));
+        assert_ok!(audit(&key_url, &did_of(0)));
+        assert_ok!(audit("key-0", &did_of(0)));
+        assert_ok!(audit(&key_url, "AUTHORITY_0"));
+        let other_key_url = format!("{}#key-0", did_of(1));
+        assert_err!(audit(&other_key_url, "AUTHORITY_0"), <Error<Test>>::DidMismatch);
+        assert_err!(audit(&key_url, &did_of(1)), <Error<Test>>::DidMismatch);
+        assert_err!(audit(&key_url, "did:greenaureus:unknown"), <Error<Test>>::DidMismatch);
+        let unknown_key_url = format!("{}#key-1", did_of(0));
+        assert_err!(audit(&unknown_key_url, "AUTHORITY_0"), <Error<Test>>::UnknownKeyId);
+        assert_eq!(SupplyChain::audit_count(component(&component_id)), 3);
+    });
+}
+
+// Test: attach_document()
+#[test]
+fn attach_document_references_documents() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authorities = setup_authorities(2);
+        let (auditor, other) = (authorities[0].into(), authorities[1].into());
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(
+            auditor,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+        let document = |content: &[u8]| DocumentRef {
+            cid: crate::cid::cid(content),
+            size: content.len() as u64,
+            media_type: "application/pdf".to_string(),
+        };
+        let attach = |who: u64, index: u32, document: DocumentRef<String>| {
+            SupplyChain::attach_document(Origin::signed(who), component_id.clone(), index, document)
+        };
+
+        assert_ok!(attach(auditor, 0, document(b"lab report")));
+        System::assert_last_event(Event::SupplyChain(crate::Event::DocumentAttached(
+            component(&component_id),
+            0,
+            crate::cid::cid(b"lab report").into_bytes().try_into().unwrap(),
+        )));
+        assert_err!(
+            attach(auditor, 0, document(b"lab report")),
+            <Error<Test>>::DocumentAlreadyAttached
+        );
+        assert_err!(attach(other, 0, document(b"photo")), <Error<Test>>::NotAuditIssuer);
+        assert_err!(attach(auditor, 1, document(b"photo")), <Error<Test>>::AuditNotFound);
+        let invalid = DocumentRef { cid: "not a cid".to_string(), ..document(b"photo") };
+        assert_err!(attach(auditor, 0, invalid), <Error<Test>>::InvalidCid);
+        let media_type = "x".repeat((<Test as Config>::MaxDocumentRefLength::get() + 1) as usize);
+        let too_long = DocumentRef { media_type, ..document(b"photo") };
+        assert_err!(attach(auditor, 0, too_long), <Error<Test>>::DocumentRefTooLong);
+        for i in 1..<Test as Config>::MaxAuditDocuments::get() {
+            assert_ok!(attach(auditor, 0, document(&i.to_be_bytes())));
+        }
+        assert_err!(
+            attach(auditor, 0, document(b"photo")),
+            <Error<Test>>::MaxAuditDocumentsReached
+        );
+
+        let documents = SupplyChain::documents(&component(&component_id), 0);
+        assert_eq!(documents.len() as u32, <Test as Config>::MaxAuditDocuments::get());
+        assert_eq!(documents[0].cid, crate::cid::cid(b"lab report").into_bytes());
+        assert_eq!(documents[0].size, 10);
+        assert_eq!(&documents[0].media_type[..], b"application/pdf");
+
+        assert_ok!(SupplyChain::revoke_audit(
+            Origin::signed(auditor),
+            component_id.clone(),
+            0,
+            AuditReason::Other
+        ));
+        assert_err!(attach(auditor, 0, document(b"photo")), <Error<Test>>::AuditNotValid);
+    });
+}
+
+// Test: register_schema()
+#[test]
+fn register_schema_adds_versions() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let schema_id = "harvest".to_string();
+
+        assert_err!(
+            SupplyChain::register_schema(Origin::signed(0), schema_id.clone(), harvest_fields()),
+            DispatchError::BadOrigin
+        );
+        let mut fields = harvest_fields();
+        fields.push(field("weight", FieldType::Integer, false));
+        assert_err!(
+            SupplyChain::register_schema(Origin::signed(ADMIN), schema_id.clone(), fields),
+            <Error<Test>>::DuplicateSchemaField
+        );
+        let fields = (0..=<Test as Config>::MaxSchemaFields::get())
+            .map(|i| field(&format!("field{}", i), FieldType::String, false))
+            .collect();
+        assert_err!(
+            SupplyChain::register_schema(Origin::signed(ADMIN), schema_id.clone(), fields),
+            <Error<Test>>::MaxSchemaFieldsReached
+        );
+
+        assert_ok!(SupplyChain::register_schema(
+            Origin::signed(ADMIN),
+            schema_id.clone(),
+            harvest_fields()
+        ));
+        assert_ok!(SupplyChain::register_schema(Origin::signed(ADMIN), schema_id, vec![]));
+        System::assert_last_event(Event::SupplyChain(crate::Event::SchemaRegistered(
+            b"harvest".to_vec().try_into().unwrap(),
+            2,
+        )));
+        let schema_id = b"harvest".to_vec().try_into().unwrap();
+        assert_eq!(SupplyChain::schema_version(&schema_id), 2);
+        assert_eq!(SupplyChain::schema(&schema_id, 1).unwrap().fields.len(), 3);
+        assert!(SupplyChain::schema(&schema_id, 2).unwrap().fields.is_empty());
+    });
+}
+
+#[test]
+fn audits_conform_to_schema() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        assert_ok!(SupplyChain::register_schema(
+            Origin::signed(ADMIN),
+            "harvest".to_string(),
+            harvest_fields()
+        ));
+        let audit = |claims: &str| {
+            SupplyChain::checked_add_audit(
+                authority,
+                signed_jwt(0, claims),
+                None,
+                "386a00b808e37a15".to_string(),
+            )
+        };
+
+        assert_ok!(audit(r#"{"schema":"harvest","harvestDate":"2022-01-01","weight":12.5}"#));
+        assert_ok!(audit(
+            r#"{"schema":"harvest","harvestDate":"2022-01-01","weight":12,"organic":null}"#
+        ));
+        assert_err!(
+            audit(r#"{"schema":"harvest","harvestDate":"2022-01-01"}"#),
+            <Error<Test>>::MissingAuditField
+        );
+        assert_err!(
+            audit(r#"{"schema":"harvest","harvestDate":"2022-01-01","weight":"12"}"#),
+            <Error<Test>>::AuditFieldTypeMismatch
+        );
+        assert_err!(audit(r#"{"schema":"transport"}"#), <Error<Test>>::UnknownSchema);
+        assert_err!(audit(r#"{"schema":5}"#), <Error<Test>>::InvalidSchemaReference);
+        assert_err!(
+            audit(r#"{"schema":"harvest","schemaVersion":-1}"#),
+            <Error<Test>>::InvalidSchemaReference
+        );
+        assert_err!(
+            audit(r#"{"schema":"harvest","schemaVersion":2}"#),
+            <Error<Test>>::UnknownSchema
+        );
+
+        // New audits follow the latest version, unless they select another one.
+        let mut fields = harvest_fields();
+        fields.push(field("lot", FieldType::Integer, true));
+        assert_ok!(SupplyChain::register_schema(
+            Origin::signed(ADMIN),
+            "harvest".to_string(),
+            fields
+        ));
+        let claims = r#""schema":"harvest","harvestDate":"2022-01-01","weight":12"#;
+        assert_err!(audit(&format!("{{{}}}", claims)), <Error<Test>>::MissingAuditField);
+        assert_err!(
+            audit(&format!(r#"{{{},"lot":1.5}}"#, claims)),
+            <Error<Test>>::AuditFieldTypeMismatch
+        );
+        assert_ok!(audit(&format!(r#"{{{},"lot":7}}"#, claims)));
+        assert_ok!(audit(&format!(r#"{{{},"schemaVersion":1}}"#, claims)));
+        // Audits without a schema are not checked.
+        assert_ok!(audit("{}"));
+
+        // Audits with a payload follow the schema named by their type.
+        let payload = AuditPayload {
+            audit_type: "harvest".to_string(),
+            issued_at: 0,
+            valid_until: None,
+            location: None,
+            certification: None,
+        };
+        assert_err!(
+            SupplyChain::audit_with_payload(
+                Origin::signed(authority),
+                signed_jwt(0, "{}"),
+                payload.clone(),
+                "386a00b808e37a15".to_string()
+            ),
+            <Error<Test>>::MissingAuditField
+        );
+        assert_ok!(SupplyChain::audit_with_payload(
+            Origin::signed(authority),
+            signed_jwt(0, &format!(r#"{{{},"lot":7}}"#, claims)),
+            payload,
+            "386a00b808e37a15".to_string()
+        ));
+    });
+}
+
+// Test: grant_role() and revoke_role()
+#[test]
+fn grant_and_revoke_role() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SupplyChain::authorize(Origin::signed(ADMIN), 0, "ExampleOrg".to_string(), vec![]));
+
+        assert_ok!(SupplyChain::grant_role(Origin::signed(ADMIN), 0, Role::Certifier));
+        assert!(SupplyChain::authorities(0).unwrap().roles.contains(Role::Certifier));
+        assert_err!(
+            SupplyChain::grant_role(Origin::signed(ADMIN), 0, Role::Certifier),
+            <Error<Test>>::RoleAlreadyGranted
+        );
+
+        assert_ok!(SupplyChain::revoke_role(Origin::signed(ADMIN), 0, Role::Certifier));
+        assert!(!SupplyChain::authorities(0).unwrap().roles.contains(Role::Certifier));
+        assert_err!(
+            SupplyChain::revoke_role(Origin::signed(ADMIN), 0, Role::Certifier),
+            <Error<Test>>::MissingRole
+        );
+        assert_err!(
+            SupplyChain::grant_role(Origin::signed(ADMIN), 1, Role::Certifier),
+            <Error<Test>>::AuthorityNotFound
+        );
+    });
+}
+
+#[test]
+fn audits_require_roles() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let assembly_id: ComponentId = "8f8915a158f4e5d7".to_string();
+        for role in Role::ALL {
+            assert_ok!(SupplyChain::revoke_role(Origin::signed(ADMIN), authority, role));
+        }
+
+        // Creating a component requires a producer or registrar.
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_jwt(), None, component_id.clone()),
+            <Error<Test>>::MissingRole
+        );
+        assert_ok!(SupplyChain::grant_role(Origin::signed(ADMIN), authority, Role::Registrar));
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+
+        // Auditing an existing component requires a producer, transporter or certifier.
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_jwt(), None, component_id.clone()),
+            <Error<Test>>::MissingRole
+        );
+        assert_ok!(SupplyChain::grant_role(Origin::signed(ADMIN), authority, Role::Transporter));
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+
+        // Assembling requires an assembler.
+        let components = Some(vec![component_id]);
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_jwt(), components.clone(), assembly_id.clone()),
+            <Error<Test>>::MissingRole
+        );
+        assert_ok!(SupplyChain::grant_role(Origin::signed(ADMIN), authority, Role::Assembler));
+        assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), components, assembly_id));
+    });
+}
+
+// Test: set_authority_status()
+#[test]
+fn set_authority_status_suspends_authority() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+
+        assert_ok!(SupplyChain::set_authority_status(
+            Origin::signed(ADMIN),
+            authority,
+            AuthorityStatus::Suspended
+        ));
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_jwt(), None, "386a00b808e37a15".to_string()),
+            <Error<Test>>::AuthoritySuspended
+        );
+
+        assert_ok!(SupplyChain::set_authority_status(
+            Origin::signed(ADMIN),
+            authority,
+            AuthorityStatus::Active
+        ));
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            None,
+            "386a00b808e37a15".to_string()
+        ));
+    });
+}
+
+// Test: update_authority()
+#[test]
+fn update_authority_updates_details() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let website = Some("https://greenaureus.de".to_string());
+        let lei = Some("5299000J2N45DDNE4Y28".to_string());
+
+        assert_ok!(SupplyChain::update_authority(
+            Origin::signed(authority),
+            website.clone(),
+            lei.clone()
+        ));
+        let info = SupplyChain::authorities(authority).unwrap();
+        assert_eq!(info.website.unwrap()[..], *website.unwrap().as_bytes());
+        assert_eq!(info.lei.unwrap()[..], *lei.unwrap().as_bytes());
+    });
+}
+
+#[test]
+fn update_authority_rejects_invalid_details() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let long_website =
+            "s".repeat((<Test as Config>::MaxWebsiteLength::get() + 1) as usize);
+
+        assert_err!(
+            SupplyChain::update_authority(Origin::signed(authority), Some(long_website), None),
+            <Error<Test>>::WebsiteTooLong
+        );
+        assert_err!(
+            SupplyChain::update_authority(Origin::signed(authority), None, Some("5299-00".to_string())),
+            <Error<Test>>::InvalidLei
+        );
+        assert_err!(
+            SupplyChain::update_authority(Origin::signed(9), None, None),
+            <Error<Test>>::AuthorityNotFound
+        );
+    });
+}
+
+// Test: add_key()
+#[test]
+fn add_key_adds_key() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let key = VerificationKey::Sr25519([1; 32]);
+
+        assert_ok!(SupplyChain::add_key(Origin::signed(authority), "key-1".to_string(), key));
+        let info = SupplyChain::authorities(authority).unwrap();
+        assert_eq!(info.key(b"key-1").unwrap().key, key);
+        assert_eq!(info.active_keys().count(), 2);
+    });
+}
+
+#[test]
+fn add_key_fails() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let key = VerificationKey::Ed25519([0; 32]);
+        let long_kid = "k".repeat((<Test as Config>::MaxKeyIdLength::get() + 1) as usize);
+
+        assert_err!(
+            SupplyChain::add_key(Origin::signed(9), "key-1".to_string(), key),
+            <Error<Test>>::AuthorityNotFound
+        );
+        assert_err!(
+            SupplyChain::add_key(Origin::signed(authority), "key-0".to_string(), key),
+            <Error<Test>>::DuplicateKeyId
+        );
+        assert_err!(
+            SupplyChain::add_key(Origin::signed(authority), "".to_string(), key),
+            <Error<Test>>::EmptyDataProvided
+        );
+        assert_err!(
+            SupplyChain::add_key(Origin::signed(authority), long_kid, key),
+            <Error<Test>>::KeyIdTooLong
+        );
+
+        for i in 1..<Test as Config>::MaxAuthorityKeys::get() {
+            assert_ok!(SupplyChain::add_key(Origin::signed(authority), format!("key-{}", i), key));
+        }
+        assert_err!(
+            SupplyChain::add_key(Origin::signed(authority), "one-too-many".to_string(), key),
+            <Error<Test>>::MaxAuthorityKeysReached
+        );
+    });
+}
+
+// Test: rotate_key()
+#[test]
+fn rotate_key_replaces_key() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(2).pop().unwrap().into();
+        let new_key = VerificationKey::Ed25519(signing_pair(0).public().0);
+
+        assert_ok!(SupplyChain::rotate_key(
+            Origin::signed(authority),
+            "key-0".to_string(),
+            "key-1".to_string(),
+            new_key
+        ));
+        let info = SupplyChain::authorities(authority).unwrap();
+        assert_eq!(info.key(b"key-0").unwrap().status, KeyStatus::Rotated);
+        assert_eq!(info.key(b"key-1").unwrap().status, KeyStatus::Active);
+
+        assert_err!(
+            SupplyChain::rotate_key(
+                Origin::signed(authority),
+                "key-0".to_string(),
+                "key-2".to_string(),
+                new_key
+            ),
+            <Error<Test>>::KeyNotActive
+        );
+        assert_err!(
+            SupplyChain::rotate_key(
+                Origin::signed(authority),
+                "key-9".to_string(),
+                "key-2".to_string(),
+                new_key
+            ),
+            <Error<Test>>::UnknownKeyId
+        );
+    });
+}
+
+// Test: revoke_key()
+#[test]
+fn revoke_key_revokes_key() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+
+        assert_ok!(SupplyChain::revoke_key(Origin::signed(ADMIN), authority, "key-0".to_string()));
+        assert_eq!(
+            SupplyChain::authorities(authority).unwrap().key(b"key-0").unwrap().status,
+            KeyStatus::Revoked
+        );
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                authority,
+                signed_jwt_with_kid(0, Some("key-0"), "{}"),
+                None,
+                "386a00b808e37a15".to_string()
+            ),
+            <Error<Test>>::KeyNotActive
+        );
+        assert_err!(
+            SupplyChain::revoke_key(Origin::signed(ADMIN), authority, "key-9".to_string()),
+            <Error<Test>>::UnknownKeyId
+        );
+    });
+}
+
+// Test: JWT verification in checked_add_audit()
+#[test]
+fn add_audit_no_verification_key() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(SupplyChain::authorize(Origin::signed(ADMIN), 0, "ExampleOrg".to_string(), vec![Role::Producer]));
+
+        assert_err!(
+            SupplyChain::checked_add_audit(0, audit_jwt(), None, "386a00b808e37a15".to_string()),
+            <Error<Test>>::VerificationKeyNotFound
+        )
+    });
+}
+
+#[test]
+fn add_audit_malformed_jwt() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let jwt = audit_jwt();
+        let (input, _) = jwt.rsplit_once('.').unwrap();
+
+        for audit_data in [
+            "1".to_string(),
+            input.to_string(),
+            format!("{}.!!!", input),
+            format!("{}.{}", signing_input("EdDSA", "[]"), encode_segment(&[0; 64])),
+        ] {
+            assert_err!(
+                SupplyChain::checked_add_audit(authority, audit_data, None, component_id.clone()),
+                <Error<Test>>::InvalidJwt
+            );
+        }
+    });
+}
+
+#[test]
+fn add_audit_unsupported_algorithm() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let audit_data = format!("{}.{}", signing_input("HS256", "{}"), encode_segment(&[0; 32]));
+
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_data, None, "386a00b808e37a15".to_string()),
+            <Error<Test>>::UnsupportedJwtAlgorithm
+        )
+    });
+}
+
+#[test]
+fn add_audit_algorithm_mismatch() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        assert_ok!(SupplyChain::rotate_key(
+            Origin::signed(authority),
+            "key-0".to_string(),
+            "key-1".to_string(),
+            VerificationKey::Sr25519([0; 32])
+        ));
+
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, audit_jwt(), None, "386a00b808e37a15".to_string()),
+            <Error<Test>>::JwtAlgorithmMismatch
+        )
+    });
+}
+
+#[test]
+fn add_audit_forged_signature() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(2).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        // Signed by authority 0, submitted by authority 1.
+        let forged = audit_jwt();
+        // Claims altered after signing.
+        let (_, signature) = forged.rsplit_once('.').unwrap();
+        let tampered = format!(
+            "{}.{}",
+            signing_input("EdDSA", r#"{"iss":"AUTHORITY_0","result":"failed"}"#),
+            signature
+        );
+
+        for audit_data in [forged.clone(), tampered] {
+            assert_err!(
+                SupplyChain::checked_add_audit(authority, audit_data, None, component_id.clone()),
+                <Error<Test>>::InvalidJwtSignature
+            );
+        }
+    });
+}
+
+#[test]
+fn add_audit_accepts_sr25519_jwt() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let pair = sr25519::Pair::from_seed(&[7; 32]);
+        assert_ok!(SupplyChain::rotate_key(
+            Origin::signed(authority),
+            "key-0".to_string(),
+            "key-1".to_string(),
+            VerificationKey::Sr25519(pair.public().0)
+        ));
+
+        let input = signing_input("Sr25519", r#"{"result":"passed"}"#);
+        let audit_data = format!("{}.{}", input, encode_segment(&pair.sign(input.as_bytes()).0));
+
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_data,
+            None,
+            "386a00b808e37a15".to_string()
+        ));
+    });
+}
+
+#[test]
+fn add_audit_accepts_es256k_jwt() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let pair = ecdsa::Pair::from_seed(&[7; 32]);
+        assert_ok!(SupplyChain::rotate_key(
+            Origin::signed(authority),
+            "key-0".to_string(),
+            "key-1".to_string(),
+            VerificationKey::Secp256k1(pair.public().0)
+        ));
+
+        let input = signing_input("ES256K", r#"{"result":"passed"}"#);
+        let signature = pair.sign_prehashed(&sp_io::hashing::sha2_256(input.as_bytes()));
+        let audit_data = format!("{}.{}", input, encode_segment(&signature.0[..64]));
+
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_data,
+            None,
+            "386a00b808e37a15".to_string()
+        ));
+    });
+}
+
+#[test]
+fn add_audit_selects_key_by_kid() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(2).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        // Authority 1 additionally registers the key of authority 0 as "key-1".
+        assert_ok!(SupplyChain::add_key(
+            Origin::signed(authority),
+            "key-1".to_string(),
+            VerificationKey::Ed25519(signing_pair(0).public().0)
+        ));
+
+        assert_err!(
+            SupplyChain::checked_add_audit(authority, signed_jwt(1, "{}"), None, component_id.clone()),
+            <Error<Test>>::MissingKeyId
+        );
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                authority,
+                signed_jwt_with_kid(1, Some("key-2"), "{}"),
+                None,
+                component_id.clone()
+            ),
+            <Error<Test>>::UnknownKeyId
+        );
+        assert_err!(
+            SupplyChain::checked_add_audit(
+                authority,
+                signed_jwt_with_kid(1, Some("key-1"), "{}"),
+                None,
+                component_id.clone()
+            ),
+            <Error<Test>>::InvalidJwtSignature
+        );
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            signed_jwt_with_kid(0, Some("key-1"), "{}"),
+            None,
+            component_id.clone()
+        ));
+        assert_eq!(SupplyChain::audit(&component(&component_id), 0).unwrap().key_id[..], b"key-1");
+    });
+}
+
+// Test: audit ids
+#[test]
+fn add_audit_indexes_audit_by_id() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        for _ in 0..2 {
+            assert_ok!(SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                None,
+                component_id.clone()
+            ));
+        }
+
+        let id = SupplyChain::audit_id(&component(&component_id), &authority, None, 1);
+        System::assert_last_event(Event::SupplyChain(crate::Event::AuditAdded(
+            component(&component_id),
+            SupplyChain::authorities(authority).unwrap().name,
+            id,
+        )));
+        assert_ne!(id, SupplyChain::audit_id(&component(&component_id), &authority, None, 0));
+
+        let (found_component, index, audit) = SupplyChain::audit_by_id(&id).unwrap();
+        assert_eq!(found_component, component(&component_id));
+        assert_eq!(index, 1);
+        assert_eq!(audit, SupplyChain::audit(&component(&component_id), 1).unwrap());
+        assert_eq!(SupplyChain::audit_by_id(&Default::default()), None);
+    });
+}
+
+// Test: queries
+#[test]
+fn queries_return_components_and_audits() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let part: ComponentId = "0".to_string();
+        let assembly: ComponentId = "386a00b808e37a15".to_string();
+        assert!(!SupplyChain::exists(&component(&part)));
+        assert_eq!(SupplyChain::component(&component(&part)), None);
+
+        for _ in 0..3 {
+            assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, part.clone()));
+        }
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            Some(vec![part.clone()]),
+            assembly.clone()
+        ));
+
+        assert!(SupplyChain::exists(&component(&part)));
+        assert!(SupplyChain::component(&component(&assembly)).is_some());
+        assert_eq!(SupplyChain::parts_of(&component(&assembly)), vec![component(&part)]);
+        assert_eq!(SupplyChain::used_in(&component(&part)), vec![component(&assembly)]);
+        assert!(SupplyChain::used_in(&component(&assembly)).is_empty());
+
+        let audits = SupplyChain::audits(&component(&part));
+        assert_eq!(SupplyChain::audits_page(&component(&part), 1, 10), audits[1..].to_vec());
+        assert_eq!(SupplyChain::audits_page(&component(&part), 0, 2), audits[..2].to_vec());
+        assert!(SupplyChain::audits_page(&component(&part), 3, 10).is_empty());
+        assert!(SupplyChain::audits_page(&component(&part), u32::MAX, u32::MAX).is_empty());
+    });
+}
+
+#[test]
+fn provenance_walks_assembly_graph() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        for id in ["raw", "b"] {
+            assert_ok!(SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                None,
+                id.to_string()
+            ));
+        }
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            Some(vec!["raw".to_string()]),
+            "a".to_string()
+        ));
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            Some(vec!["a".to_string(), "b".to_string()]),
+            "product".to_string()
+        ));
+        let walk = |id: &str, direction, max_depth, offset, limit| {
+            SupplyChain::provenance(&component(id), direction, max_depth, offset, limit, 1)
+                .into_iter()
+                .map(|node| {
+                    assert_eq!(node.audits, SupplyChain::audits(&node.id));
+                    assert_eq!(node.audit_count, 1);
+                    (node.id.into_inner(), node.depth, node.parent.map(|id| id.into_inner()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let bill_of_materials = vec![
+            (b"product".to_vec(), 0, None),
+            (b"a".to_vec(), 1, Some(b"product".to_vec())),
+            (b"b".to_vec(), 1, Some(b"product".to_vec())),
+            (b"raw".to_vec(), 2, Some(b"a".to_vec())),
+        ];
+        assert_eq!(walk("product", Direction::Parts, u32::MAX, 0, u32::MAX), bill_of_materials);
+        assert_eq!(
+            walk("product", Direction::Parts, 1, 0, u32::MAX),
+            bill_of_materials[..3].to_vec()
+        );
+        assert_eq!(
+            walk("product", Direction::Parts, 0, 0, u32::MAX),
+            bill_of_materials[..1].to_vec()
+        );
+        assert_eq!(
+            walk("product", Direction::Parts, u32::MAX, 1, 2),
+            bill_of_materials[1..3].to_vec()
+        );
+        assert!(walk("product", Direction::Parts, u32::MAX, 4, u32::MAX).is_empty());
+        assert!(walk("product", Direction::Parts, u32::MAX, 0, 0).is_empty());
+
+        assert_eq!(
+            walk("raw", Direction::UsedIn, u32::MAX, 0, u32::MAX),
+            vec![
+                (b"raw".to_vec(), 0, None),
+                (b"a".to_vec(), 1, Some(b"raw".to_vec())),
+                (b"product".to_vec(), 2, Some(b"a".to_vec())),
+            ]
+        );
+        assert!(walk("unknown", Direction::Parts, u32::MAX, 0, u32::MAX).is_empty());
+
+        // Each component is returned once, even if the graph contains a cycle.
+        Components::<Test>::mutate(component("raw"), |raw| {
+            raw.components.try_insert(component("product"), None).unwrap();
+        });
+        assert_eq!(walk("product", Direction::Parts, u32::MAX, 0, u32::MAX), bill_of_materials);
+
+        // Nodes carry at most the requested number of audits, but count all of them.
+        assert_ok!(SupplyChain::checked_add_audit(authority, audit_jwt(), None, "b".to_string()));
+        let nodes = SupplyChain::provenance(&component("b"), Direction::Parts, 0, 0, 1, 1);
+        assert_eq!(nodes[0].audits, SupplyChain::audits_page(&component("b"), 0, 1));
+        assert_eq!(nodes[0].audit_count, 2);
+        let nodes = SupplyChain::provenance(&component("b"), Direction::Parts, 0, 0, 1, 0);
+        assert!(nodes[0].audits.is_empty());
+    });
+}
+
+// Test: revoke_audit()
+#[test]
+fn revoke_audit_revokes_audit() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        for _ in 0..2 {
+            assert_ok!(SupplyChain::checked_add_audit(
+                authority,
+                audit_jwt(),
+                None,
+                component_id.clone()
+            ));
+        }
+
+        assert_ok!(SupplyChain::revoke_audit(
+            Origin::signed(authority),
+            component_id.clone(),
+            0,
+            AuditReason::DataError
+        ));
+
+        let audits = SupplyChain::audits(&component(&component_id));
+        assert_eq!(audits.len(), 2);
+        assert_eq!(audits[0].status, AuditStatus::Revoked(AuditReason::DataError));
+        assert_eq!(SupplyChain::valid_audits(&component(&component_id)), vec![audits[1].clone()]);
+    });
+}
+
+#[test]
+fn revoke_audit_fails() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(2).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            signed_jwt(1, "{}"),
+            None,
+            component_id.clone()
+        ));
+
+        assert_err!(
+            SupplyChain::revoke_audit(Origin::signed(authority), component_id.clone(), 1, AuditReason::Other),
+            <Error<Test>>::AuditNotFound
+        );
+        assert_err!(
+            SupplyChain::revoke_audit(Origin::signed(0), component_id.clone(), 0, AuditReason::Other),
+            <Error<Test>>::NotAuditIssuer
+        );
+        assert_ok!(SupplyChain::revoke_audit(
+            Origin::signed(authority),
+            component_id.clone(),
+            0,
+            AuditReason::Other
+        ));
+        assert_err!(
+            SupplyChain::revoke_audit(Origin::signed(authority), component_id, 0, AuditReason::Other),
+            <Error<Test>>::AuditNotValid
+        );
+    });
+}
+
+// Test: amend_audit()
+#[test]
+fn amend_audit_supersedes_audit() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(1).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let corrected = signed_jwt(0, r#"{"iss":"AUTHORITY_0","result":"failed"}"#);
+        assert_ok!(SupplyChain::checked_add_audit(
+            authority,
+            audit_jwt(),
+            None,
+            component_id.clone()
+        ));
+        // Amending requires no role.
+        assert_ok!(SupplyChain::revoke_role(Origin::signed(ADMIN), authority, Role::Producer));
+
+        assert_ok!(SupplyChain::amend_audit(
+            Origin::signed(authority),
+            component_id.clone(),
+            0,
+            corrected.clone(),
+            AuditReason::DataError
+        ));
+
+        let audits = SupplyChain::audits(&component(&component_id));
+        assert_eq!(audits[0].status, AuditStatus::Superseded(1, AuditReason::DataError));
+        assert_eq!(&audits[1].audit_data[..], corrected.as_bytes());
+        assert!(audits[1].is_valid());
+        assert_err!(
+            SupplyChain::amend_audit(
+                Origin::signed(authority),
+                component_id.clone(),
+                0,
+                audit_jwt(),
+                AuditReason::DataError
+            ),
+            <Error<Test>>::AuditNotValid
+        );
+        assert_eq!(SupplyChain::valid_audits(&component(&component_id)).len(), 1);
+    });
+}
+
+#[test]
+fn amend_audit_requires_valid_jwt_and_issuer() {
+    new_test_ext().execute_with(|| {
+        let authority = setup_authorities(2).pop().unwrap().into();
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        assert_ok!(SupplyChain::checked_add_audit(0, audit_jwt(), None, component_id.clone()));
+
+        assert_err!(
+            SupplyChain::amend_audit(
+                Origin::signed(authority),
+                component_id.clone(),
+                0,
+                signed_jwt(1, "{}"),
+                AuditReason::DataError
+            ),
+            <Error<Test>>::NotAuditIssuer
+        );
+        assert_err!(
+            SupplyChain::amend_audit(
+                Origin::signed(0),
+                component_id.clone(),
+                0,
+                signed_jwt(1, "{}"),
+                AuditReason::DataError
+            ),
+            <Error<Test>>::InvalidJwtSignature
+        );
+        assert!(SupplyChain::audit(&component(&component_id), 0).unwrap().is_valid());
+    });
+}
+
+// Test: migrations
+#[test]
+fn migrate_v1_converts_authority_names() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<SupplyChain>();
+        sp_io::storage::set(
+            &<Authorities<Test>>::hashed_key_for(0),
+            &"ExampleOrg".to_string().encode(),
+        );
+
+        crate::migrations::migrate::<Test>();
+
+        let info = SupplyChain::authorities(0).unwrap();
+        assert_eq!(&info.name[..], b"ExampleOrg");
+        assert_eq!(info.status, AuthorityStatus::Active);
+        assert!(info.keys.is_empty());
+        assert_eq!(info.roles, Roles::all());
+        assert_eq!(SupplyChain::on_chain_storage_version(), 1);
+    });
+}
+
+#[test]
+fn migrate_v1_moves_audits_out_of_components() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<SupplyChain>();
+        sp_io::storage::set(
+            &<Authorities<Test>>::hashed_key_for(0),
+            &"ExampleOrg".to_string().encode(),
+        );
+        let component_id = component("386a00b808e37a15");
+        let parts: BTreeSet<ComponentId> = vec!["0".to_string()].into_iter().collect();
+        // The layout of a component before the migration.
+        let old_audits = vec![
+            ("ExampleOrg".to_string(), 1u64, "first".to_string()),
+            ("UnknownOrg".to_string(), 2u64, "second".to_string()),
+        ];
+        sp_io::storage::set(
+            &<Components<Test>>::hashed_key_for(&component_id),
+            &(old_audits, BTreeSet::<ComponentId>::new(), parts).encode(),
+        );
+
+        crate::migrations::migrate::<Test>();
+
+        let parts = SupplyChain::components(&component_id).components;
+        assert_eq!(parts.len(), 1);
+        assert!(parts.contains(&component("0")));
+        assert_eq!(SupplyChain::audit_count(&component_id), 2);
+        let audits = SupplyChain::audits(&component_id);
+        assert_eq!(audits[0].issuer, 0);
+        assert_eq!(&audits[0].audit_data[..], b"first");
+        assert_eq!(&audits[1].auditor[..], b"UnknownOrg");
+        assert_eq!(audits[1].timestamp, 2);
+        let id = SupplyChain::audit_id(&component_id, &0, None, 0);
+        assert_eq!(SupplyChain::audit_by_id(&id), Some((component_id, 0, audits[0].clone())));
+    });
+}
+
+#[test]
+fn migrate_v1_drops_parts_beyond_max_components() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<SupplyChain>();
+        let component_id = component("386a00b808e37a15");
+        let max = <Test as Config>::MaxComponents::get();
+        let parts: BTreeSet<ComponentId> = (0..=max).map(|i| i.to_string()).collect();
+        sp_io::storage::set(
+            &<Components<Test>>::hashed_key_for(&component_id),
+            &(Vec::<(String, u64, String)>::new(), BTreeSet::<ComponentId>::new(), parts).encode(),
+        );
+
+        crate::migrations::migrate::<Test>();
+
+        assert_eq!(SupplyChain::components(&component_id).components.len() as u32, max);
+    });
+}
+
+// Test: audit_confidential()
+#[test]
+fn audit_confidential_stores_confidential_data() {
+    new_test_ext().execute_with(|| {
+        let authorities = setup_authorities(3);
+        let (auditor, buyer, other) =
+            (authorities[0].into(), authorities[1].into(), authorities[2].into());
+        let component_id: ComponentId = "386a00b808e37a15".to_string();
+        let (salt, plaintext) = ([7; 32], br#"{"price":"12.50 EUR/kg"}"#);
+        let commitment = ConfidentialDataOf::<Test>::commitment_of(&salt, plaintext);
+        let jwt = |commitment: &[u8]| {
+            let claims = format!(
+                r#"{{"iss":"AUTHORITY_0","commitment":"0x{}"}}"#,
+                sp_core::hexdisplay::HexDisplay::from(&commitment)
+            );
+            signed_jwt(0, &claims)
+        };
+        let wrapped = |recipient: u64, kid: &str, wrapping: KeyWrapping, length: usize| {
+            WrappedKey {
+                recipient,
+                kid: kid.to_string(),
+                wrapping,
+                wrapped_key: vec![1; length].try_into().unwrap(),
+            }
+        };
+        let audit = |audit_data: String, recipients: Vec<WrappedKey<u64, String>>| {
+            SupplyChain::audit_confidential(
+                Origin::signed(auditor),
+                audit_data,
+                ContentCipher::XChaCha20Poly1305,
+                vec![2; 64],
+                recipients,
+                commitment,
+                component_id.clone(),
+            )
+        };
+        let length = KeyWrapping::X25519.wrapped_key_length();
+
+        assert_err!(audit(jwt(&commitment), vec![]), <Error<Test>>::InvalidRecipients);
+        assert_err!(
+            audit(
+                jwt(&commitment),
+                vec![
+                    wrapped(buyer, "key-0", KeyWrapping::X25519, length),
+                    wrapped(buyer, "key-0", KeyWrapping::X25519, length),
+                ]
+            ),
+            <Error<Test>>::InvalidRecipients
+        );
+        assert_err!(
+            audit(jwt(&commitment), vec![wrapped(42, "key-0", KeyWrapping::X25519, length)]),
+            <Error<Test>>::AuthorityNotFound
+        );
+        assert_err!(
+            audit(jwt(&commitment), vec![wrapped(buyer, "key-1", KeyWrapping::X25519, length)]),
+            <Error<Test>>::UnknownKeyId
+        );
+        assert_err!(
+            audit(
+                jwt(&commitment),
+                vec![wrapped(buyer, "key-0", KeyWrapping::Ristretto255, length)]
+            ),
+            <Error<Test>>::InvalidWrappedKey
+        );
+        assert_err!(
+            audit(jwt(&commitment), vec![wrapped(buyer, "key-0", KeyWrapping::X25519, 32)]),
+            <Error<Test>>::InvalidWrappedKey
+        );
+        let recipients = vec![
+            wrapped(auditor, "key-0", KeyWrapping::X25519, length),
+            wrapped(buyer, "key-0", KeyWrapping::X25519, length),
+        ];
+        assert_err!(audit(jwt(&[0; 32]), recipients.clone()), <Error<Test>>::CommitmentMismatch);
+        assert_err!(audit(audit_jwt(), recipients.clone()), <Error<Test>>::CommitmentMismatch);
+        assert_eq!(SupplyChain::audit_count(component(&component_id)), 0);
+
+        assert_ok!(audit(jwt(&commitment), recipients));
+        let data = SupplyChain::confidential_data(component(&component_id), 0).unwrap();
+        assert_eq!(data.cipher, ContentCipher::XChaCha20Poly1305);
+        assert_eq!(&data.ciphertext[..], &[2; 64][..]);
+        let recipients: Vec<_> = data.recipients.iter().map(|wrapped| wrapped.recipient).collect();
+        assert_eq!(recipients, vec![auditor, buyer]);
+        assert!(!recipients.contains(&other));
+        assert_eq!(&data.recipients[1].kid[..], b"key-0");
+        assert!(data.is_disclosed_by(&salt, plaintext));
+        assert!(!data.is_disclosed_by(&[0; 32], plaintext));
+        assert!(!data.is_disclosed_by(&salt, br#"{"price":"9.90 EUR/kg"}"#));
+    });
+}
@@ -7,51 +7,1107 @@
 
 extern crate alloc;
 
-use alloc::{collections::BTreeSet, string::String, vec::Vec};
-use frame_support::{parameter_types, Parameter};
+use alloc::{string::String, vec::Vec};
+use codec::MaxEncodedLen;
+use frame_support::{
+    parameter_types,
+    storage::{
+        bounded_btree_map::BoundedBTreeMap, bounded_btree_set::BoundedBTreeSet,
+        bounded_vec::BoundedVec,
+    },
+    traits::Get,
+    CloneNoBound, DefaultNoBound, EqNoBound, Parameter, PartialEqNoBound, RuntimeDebugNoBound,
+};
 use scale_info::TypeInfo;
-use sp_runtime::traits::AtLeast32Bit;
+use sp_runtime::{traits::AtLeast32Bit, Perbill};
 
+/// The id of a component, as it is passed to the extrinsics.
 pub type ComponentId = String;
 
+/// UTF-8 encoded text of at most `S` bytes.
+pub type BoundedString<S> = BoundedVec<u8, S>;
+
 /// The overarching type for a product within the supply chain.
-/// Contains all the information about the parts and audits.
+/// Contains the information about the parts. The audits of a component are stored separately.
 #[derive(
-    Clone, Eq, Default, PartialEq, codec::Decode, codec::Encode, sp_runtime::RuntimeDebug, TypeInfo,
+    CloneNoBound,
+    DefaultNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
 )]
-pub struct Component<T>
-where
-    T: AtLeast32Bit + Parameter + Default + Copy,
-{
-    /// List of audits.
-    pub audits: Vec<Audit<T>>,
+#[codec(mel_bound())]
+#[scale_info(skip_type_params(IdLength, MaxParts))]
+pub struct Component<IdLength: Get<u32>, MaxParts: Get<u32>> {
     /// List of components the component is a part of.
-    pub component_of: BTreeSet<ComponentId>,
-    /// List of components that are part of this component.
-    pub components: BTreeSet<ComponentId>,
+    pub component_of: BoundedBTreeSet<BoundedString<IdLength>, MaxParts>,
+    /// List of components that are part of this component, with how much of each part went
+    /// into the component, if that was recorded.
+    pub components: BoundedBTreeMap<BoundedString<IdLength>, Option<PartUsage>, MaxParts>,
+    /// How much of the component was produced, if that was recorded.
+    pub quantity: Option<Quantity>,
+    /// How much of the component went into assemblies, in the base unit of its quantity.
+    pub consumed: u128,
+}
+
+/// Who holds a component and where it is in its lifecycle.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Custody<AccountId> {
+    /// The organization that currently holds the component.
+    pub custodian: AccountId,
+    /// The organization the component is handed over to, until it accepts the component.
+    pub pending: Option<AccountId>,
+    /// The lifecycle status of the component.
+    pub status: ComponentStatus,
+}
+
+/// Lifecycle status of a component.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum ComponentStatus {
+    /// The component was created and is held by its creator.
+    Created,
+    /// The component is being handed over to another organization.
+    InTransit,
+    /// The component was accepted by the organization it was handed over to.
+    Received,
+    /// The component went into assemblies entirely.
+    Consumed,
+    /// The component was destroyed by its custodian.
+    Destroyed,
+    /// The component was recalled.
+    Recalled,
+}
+
+impl Default for ComponentStatus {
+    fn default() -> Self {
+        ComponentStatus::Created
+    }
+}
+
+impl ComponentStatus {
+    /// Whether the component can still be handed over or go into assemblies.
+    pub fn is_available(&self) -> bool {
+        matches!(self, ComponentStatus::Created | ComponentStatus::Received)
+    }
+}
+
+/// Why a component was recalled, and the recalled component the recall started at.
+#[derive(
+    CloneNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
+)]
+#[codec(mel_bound())]
+#[scale_info(skip_type_params(IdLength))]
+pub struct Recall<IdLength: Get<u32>> {
+    /// The component the recall started at. Components that contain it are recalled as well.
+    pub source: BoundedString<IdLength>,
+    /// Why the component was recalled.
+    pub reason: RecallReason,
+}
+
+/// Why a component was recalled.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum RecallReason {
+    /// The component is contaminated.
+    Contamination,
+    /// The component doesn't comply with regulations or certification requirements.
+    NonCompliance,
+    /// The component is defective.
+    Defect,
+    /// The component's origin or audits were found to be fraudulent.
+    Fraud,
+    /// Any other reason.
+    Other,
+}
+
+/// An amount of a component.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct Quantity {
+    /// The amount, measured in `unit`.
+    pub amount: u64,
+    /// The unit of measure of `amount`.
+    pub unit: Unit,
+}
+
+impl Quantity {
+    /// The amount in the base unit of the quantity's dimension.
+    pub fn base_amount(&self) -> u128 {
+        u128::from(self.amount) * self.unit.base_factor()
+    }
+
+    /// Whether the amounts of both quantities can be compared.
+    pub fn is_compatible(&self, other: &Quantity) -> bool {
+        self.unit.dimension() == other.unit.dimension()
+    }
+}
+
+/// A unit of measure.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum Unit {
+    /// Number of items.
+    Piece,
+    /// Milligram, the base unit of mass.
+    Milligram,
+    /// Gram, 1000 mg.
+    Gram,
+    /// Kilogram, 1000 g.
+    Kilogram,
+    /// Metric ton, 1000 kg.
+    Tonne,
+    /// Millilitre, the base unit of volume.
+    Millilitre,
+    /// Litre, 1000 ml.
+    Litre,
+    /// Cubic metre, 1000 l.
+    CubicMetre,
+}
+
+/// What a unit of measure measures.
+#[derive(Clone, Copy, Eq, PartialEq, sp_runtime::RuntimeDebug)]
+pub enum Dimension {
+    /// Counted in pieces.
+    Count,
+    /// Weighed in milligrams.
+    Mass,
+    /// Measured in millilitres.
+    Volume,
+}
+
+impl Unit {
+    /// What the unit measures.
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Piece => Dimension::Count,
+            Unit::Milligram | Unit::Gram | Unit::Kilogram | Unit::Tonne => Dimension::Mass,
+            Unit::Millilitre | Unit::Litre | Unit::CubicMetre => Dimension::Volume,
+        }
+    }
+
+    /// The number of base units of the unit's dimension in one unit. The base units are
+    /// pieces, milligrams and millilitres.
+    pub fn base_factor(&self) -> u128 {
+        match self {
+            Unit::Piece | Unit::Milligram | Unit::Millilitre => 1,
+            Unit::Gram | Unit::Litre => 1_000,
+            Unit::Kilogram | Unit::CubicMetre => 1_000_000,
+            Unit::Tonne => 1_000_000_000,
+        }
+    }
+}
+
+/// How much of a part went into an assembly.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct PartUsage {
+    /// The consumed amount of the part.
+    pub quantity: Quantity,
+    /// Share of the consumed amount that ends up in the assembly, e.g. about 82% for green
+    /// coffee that loses weight while it is roasted.
+    pub yield_factor: Option<Perbill>,
 }
 
 /// A single audit.
 #[derive(
-    Clone, Eq, Default, PartialEq, codec::Decode, codec::Encode, sp_runtime::RuntimeDebug, TypeInfo,
+    CloneNoBound,
+    DefaultNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
 )]
-pub struct Audit<T>
+#[codec(mel_bound(AccountId: MaxEncodedLen, T: MaxEncodedLen))]
+#[scale_info(skip_type_params(NameLength, KeyIdLength, AuditSize))]
+pub struct Audit<AccountId, T, NameLength, KeyIdLength, AuditSize>
 where
+    AccountId: Clone + Default + Eq + core::fmt::Debug,
     T: AtLeast32Bit + Parameter + Default + Copy,
+    NameLength: Get<u32>,
+    KeyIdLength: Get<u32>,
+    AuditSize: Get<u32>,
 {
+    /// Account of the auditor organization.
+    pub issuer: AccountId,
     /// Auditor organization.
-    pub auditor: String,
+    pub auditor: BoundedString<NameLength>,
     /// Timestamp of the transaction.
     pub timestamp: T,
     /// Audit data in form of a JWT.
-    pub audit_data: String,
+    pub audit_data: BoundedString<AuditSize>,
+    /// Id of the auditor's key the JWT was verified against.
+    pub key_id: BoundedString<KeyIdLength>,
+    /// Whether the audit is still valid. Audits are never removed, only marked.
+    pub status: AuditStatus,
+}
+
+impl<AccountId, T, NameLength, KeyIdLength, AuditSize>
+    Audit<AccountId, T, NameLength, KeyIdLength, AuditSize>
+where
+    AccountId: Clone + Default + Eq + core::fmt::Debug,
+    T: AtLeast32Bit + Parameter + Default + Copy,
+    NameLength: Get<u32>,
+    KeyIdLength: Get<u32>,
+    AuditSize: Get<u32>,
+{
+    /// Whether the audit was neither revoked nor superseded.
+    pub fn is_valid(&self) -> bool {
+        self.status == AuditStatus::Valid
+    }
+}
+
+/// Structured content of an audit, stored next to the audit's JWT such that the chain can
+/// check and index it. `Id` is text when the payload is submitted and a bounded string when
+/// it is stored.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct AuditPayload<Moment, Id> {
+    /// The type of the audit or the id of the schema its claims follow.
+    pub audit_type: Id,
+    /// When the audited statement was issued.
+    pub issued_at: Moment,
+    /// Until when the audited statement holds, unless it does not expire.
+    pub valid_until: Option<Moment>,
+    /// Where the audit took place.
+    pub location: Option<Location>,
+    /// The certification the audit attests.
+    pub certification: Option<Certification<Id>>,
+}
+
+impl<Moment: PartialOrd, Id> AuditPayload<Moment, Id> {
+    /// Whether the audited statement holds at the given time.
+    pub fn is_valid_at(&self, at: &Moment) -> bool {
+        self.issued_at <= *at && self.valid_until.as_ref().map_or(true, |until| at <= until)
+    }
+
+    /// Whether the validity period ends no earlier than it starts.
+    pub fn has_valid_period(&self) -> bool {
+        self.valid_until.as_ref().map_or(true, |until| self.issued_at <= *until)
+    }
+
+    /// Convert the ids of the payload, e.g. from text into bounded strings.
+    pub fn try_map_ids<NewId, E>(
+        self,
+        mut map: impl FnMut(Id) -> Result<NewId, E>,
+    ) -> Result<AuditPayload<Moment, NewId>, E> {
+        Ok(AuditPayload {
+            audit_type: map(self.audit_type)?,
+            issued_at: self.issued_at,
+            valid_until: self.valid_until,
+            location: self.location,
+            certification: match self.certification {
+                Some(Certification { standard, result }) => {
+                    Some(Certification { standard: map(standard)?, result })
+                }
+                None => None,
+            },
+        })
+    }
+}
+
+/// Where an audit took place.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum Location {
+    /// WGS 84 coordinates in millionths of a degree.
+    Gps { latitude: i32, longitude: i32 },
+    /// Global Location Number (GS1), the 13 digits including the check digit.
+    Gln(u64),
+}
+
+impl Location {
+    /// Whether the coordinates are in range or the GLN has 13 digits and a correct check digit.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Location::Gps { latitude, longitude } => {
+                (-90_000_000..=90_000_000).contains(&latitude)
+                    && (-180_000_000..=180_000_000).contains(&longitude)
+            }
+            Location::Gln(gln) => {
+                if gln >= 10_000_000_000_000 {
+                    return false;
+                }
+                // From the right, the digits before the check digit are weighted 3, 1, 3, ...
+                let (mut digits, mut sum, mut weight) = (gln / 10, 0, 3);
+                while digits > 0 {
+                    sum += digits % 10 * weight;
+                    weight = 4 - weight;
+                    digits /= 10;
+                }
+                gln % 10 == (10 - sum % 10) % 10
+            }
+        }
+    }
+}
+
+/// A certification attested by an audit.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct Certification<Id> {
+    /// The certified standard, e.g. `EU-2018/848` for organic production.
+    pub standard: Id,
+    /// Whether the audited component meets the standard.
+    pub result: CertificationResult,
+}
+
+/// Outcome of a certification audit.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum CertificationResult {
+    /// The component meets the standard.
+    Passed,
+    /// The component does not meet the standard.
+    Failed,
+}
+
+/// Reference to a document that is stored off-chain and attached to an audit, e.g. a lab
+/// report or a photo. `Text` is text when the reference is submitted and a bounded string
+/// when it is stored.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct DocumentRef<Text> {
+    /// Content identifier of the document, see [`crate::cid`].
+    pub cid: Text,
+    /// Size of the document in bytes.
+    pub size: u64,
+    /// Media type of the document, e.g. `application/pdf`.
+    pub media_type: Text,
+}
+
+impl<Text> DocumentRef<Text> {
+    /// Convert the text of the reference, e.g. into bounded strings.
+    pub fn try_map_text<NewText, E>(
+        self,
+        mut map: impl FnMut(Text) -> Result<NewText, E>,
+    ) -> Result<DocumentRef<NewText>, E> {
+        Ok(DocumentRef { cid: map(self.cid)?, size: self.size, media_type: map(self.media_type)? })
+    }
+}
+
+/// Confidential data of an audit, encrypted such that only the listed recipients can decrypt
+/// it. The content is encrypted with a random content key, which is wrapped for each recipient
+/// with one of the recipient's registered keys. The commitment binds the audit to the
+/// plaintext, such that anyone can check disclosed data against it, see
+/// [`ConfidentialData::commitment_of`].
+#[derive(
+    CloneNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
+)]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+#[scale_info(skip_type_params(KeyIdLength, MaxRecipients, CiphertextSize))]
+pub struct ConfidentialData<AccountId, KeyIdLength, MaxRecipients, CiphertextSize>
+where
+    AccountId: Clone + Eq + core::fmt::Debug,
+    KeyIdLength: Get<u32>,
+    MaxRecipients: Get<u32>,
+    CiphertextSize: Get<u32>,
+{
+    /// The cipher the content is encrypted with.
+    pub cipher: ContentCipher,
+    /// The nonce of the cipher followed by the encrypted content and its authentication tag.
+    pub ciphertext: BoundedVec<u8, CiphertextSize>,
+    /// The content key, wrapped for each recipient.
+    pub recipients: BoundedVec<WrappedKey<AccountId, BoundedString<KeyIdLength>>, MaxRecipients>,
+    /// Commitment to the plaintext.
+    pub commitment: [u8; 32],
+}
+
+impl<AccountId, KeyIdLength, MaxRecipients, CiphertextSize>
+    ConfidentialData<AccountId, KeyIdLength, MaxRecipients, CiphertextSize>
+where
+    AccountId: Clone + Eq + core::fmt::Debug,
+    KeyIdLength: Get<u32>,
+    MaxRecipients: Get<u32>,
+    CiphertextSize: Get<u32>,
+{
+    /// The commitment to a plaintext: the BLAKE2b-256 hash of a random 32 byte salt followed by
+    /// the plaintext. The salt keeps guessable plaintexts, e.g. prices, from being confirmed
+    /// by hashing candidates. It is disclosed together with the plaintext.
+    pub fn commitment_of(salt: &[u8], plaintext: &[u8]) -> [u8; 32] {
+        let mut preimage = Vec::with_capacity(salt.len() + plaintext.len());
+        preimage.extend_from_slice(salt);
+        preimage.extend_from_slice(plaintext);
+        sp_io::hashing::blake2_256(&preimage)
+    }
+
+    /// Whether disclosed data matches the commitment.
+    pub fn is_disclosed_by(&self, salt: &[u8], plaintext: &[u8]) -> bool {
+        Self::commitment_of(salt, plaintext) == self.commitment
+    }
+}
+
+/// The content key of confidential data, wrapped for one recipient. `KeyId` is text when the
+/// data is submitted and a bounded string when it is stored.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+pub struct WrappedKey<AccountId, KeyId> {
+    /// Account of the recipient organization.
+    pub recipient: AccountId,
+    /// Id of the recipient's key the content key is wrapped with.
+    pub kid: KeyId,
+    /// How the content key is wrapped.
+    pub wrapping: KeyWrapping,
+    /// The ephemeral public key of the key agreement, the nonce and the encrypted content key
+    /// with its authentication tag.
+    pub wrapped_key: BoundedVec<u8, MaxWrappedKeyLength>,
+}
+
+/// Cipher that encrypts the content of confidential data with a 256 bit content key.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum ContentCipher {
+    /// XChaCha20-Poly1305 with a 24 byte nonce.
+    XChaCha20Poly1305,
+    /// AES-256-GCM with a 12 byte nonce.
+    Aes256Gcm,
+}
+
+/// Key agreement with a recipient's registered key that wraps the content key of confidential
+/// data. Each derives a wrapping key from the shared secret of an ephemeral key pair and the
+/// recipient's key with HKDF-SHA-256 and encrypts the content key with XChaCha20-Poly1305.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum KeyWrapping {
+    /// X25519 with an Ed25519 key, converted to its Montgomery form.
+    X25519,
+    /// Diffie-Hellman on Ristretto255 with an Sr25519 key.
+    Ristretto255,
+    /// ECDH on secp256k1 with a secp256k1 key. The ephemeral key is compressed.
+    Secp256k1,
+}
+
+impl KeyWrapping {
+    /// Whether the content key can be wrapped with the key.
+    pub fn supports(&self, key: &VerificationKey) -> bool {
+        matches!(
+            (self, key),
+            (KeyWrapping::X25519, VerificationKey::Ed25519(_))
+                | (KeyWrapping::Ristretto255, VerificationKey::Sr25519(_))
+                | (KeyWrapping::Secp256k1, VerificationKey::Secp256k1(_))
+        )
+    }
+
+    /// Length of a wrapped content key: the ephemeral public key, the 24 byte nonce, the
+    /// 32 byte content key and the 16 byte authentication tag.
+    pub fn wrapped_key_length(&self) -> usize {
+        let ephemeral_key = match self {
+            KeyWrapping::X25519 | KeyWrapping::Ristretto255 => 32,
+            KeyWrapping::Secp256k1 => 33,
+        };
+        ephemeral_key + 24 + 32 + 16
+    }
+}
+
+/// A version of an audit schema: the claims an audit JWT of the schema must or may contain.
+/// Claims that the schema does not list are allowed.
+#[derive(
+    CloneNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
+)]
+#[codec(mel_bound())]
+#[scale_info(skip_type_params(NameLength, MaxFields))]
+pub struct AuditSchema<NameLength: Get<u32>, MaxFields: Get<u32>> {
+    /// The fields of the schema, each with a distinct name.
+    pub fields: BoundedVec<SchemaField<BoundedString<NameLength>>, MaxFields>,
+}
+
+/// A field of an audit schema. `Name` is text when the schema is registered and a bounded
+/// string when it is stored.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct SchemaField<Name> {
+    /// Name of the claim.
+    pub name: Name,
+    /// The JSON type of the claim's value.
+    pub field_type: FieldType,
+    /// Whether audits of the schema must contain the claim.
+    pub required: bool,
+    /// Whether audits may conceal the claim as a selectively disclosable claim. A required
+    /// claim that is concealable may be missing from an audit that contains a digest for it.
+    #[cfg_attr(feature = "std", serde(default))]
+    pub concealable: bool,
+}
+
+/// JSON type of a schema field.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum FieldType {
+    String,
+    /// Any number, including fractions.
+    Number,
+    /// A number without fraction.
+    Integer,
+    Boolean,
+    Object,
+    Array,
+}
+
+impl FieldType {
+    /// Whether a JSON value has the type.
+    pub fn matches(&self, value: &serde_json::Value) -> bool {
+        use serde_json::Value;
+        match (self, value) {
+            (FieldType::String, Value::String(_))
+            | (FieldType::Number, Value::Number(_))
+            | (FieldType::Boolean, Value::Bool(_))
+            | (FieldType::Object, Value::Object(_))
+            | (FieldType::Array, Value::Array(_)) => true,
+            (FieldType::Integer, Value::Number(number)) => !number.is_f64(),
+            _ => false,
+        }
+    }
+}
+
+/// Status of an audit.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum AuditStatus {
+    /// The audit is valid.
+    Valid,
+    /// The audit was withdrawn by its auditor.
+    Revoked(AuditReason),
+    /// The audit was replaced by the audit at the given index of the same component.
+    Superseded(u32, AuditReason),
+}
+
+impl Default for AuditStatus {
+    fn default() -> Self {
+        AuditStatus::Valid
+    }
+}
+
+/// Why an audit was revoked or superseded.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum AuditReason {
+    /// The audit contained wrong data.
+    DataError,
+    /// The audit was added to the wrong component.
+    WrongComponent,
+    /// The audited facts changed, e.g. a certificate was withdrawn.
+    Withdrawn,
+    /// The audit was found to be fraudulent.
+    Fraud,
+    /// Any other reason.
+    Other,
+}
+
+/// The direction in which the assembly graph of a component is traversed.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum Direction {
+    /// Towards the parts a component was assembled from, i.e. its bill of materials.
+    Parts,
+    /// Towards the components a component was assembled into, i.e. where it is used.
+    UsedIn,
+}
+
+/// A component reached while traversing the assembly graph, together with a page of its
+/// audits.
+#[derive(Clone, Eq, PartialEq, codec::Decode, codec::Encode, sp_runtime::RuntimeDebug, TypeInfo)]
+pub struct ProvenanceNode<ComponentId, Audit> {
+    /// Id of the component.
+    pub id: ComponentId,
+    /// Number of assembly steps between the component and the component the traversal
+    /// started at.
+    pub depth: u32,
+    /// The component through which the component was reached first. `None` for the
+    /// component the traversal started at.
+    pub parent: Option<ComponentId>,
+    /// The first audits of the component, ordered by their index, at most as many as the
+    /// traversal was asked for.
+    pub audits: Vec<Audit>,
+    /// Number of audits of the component. The audits beyond `audits` are queried page by
+    /// page.
+    pub audit_count: u32,
+}
+
+/// The id of a verification key, as it is passed to the extrinsics.
+pub type KeyId = String;
+
+/// An organization that is authorized to add audits.
+#[derive(
+    CloneNoBound,
+    DefaultNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
+)]
+#[codec(mel_bound())]
+#[scale_info(skip_type_params(NameLength, KeyIdLength, MaxKeys, WebsiteLength))]
+pub struct AuthorityInfo<NameLength, KeyIdLength, MaxKeys, WebsiteLength>
+where
+    NameLength: Get<u32>,
+    KeyIdLength: Get<u32>,
+    MaxKeys: Get<u32>,
+    WebsiteLength: Get<u32>,
+{
+    /// Display name of the organization.
+    pub name: BoundedString<NameLength>,
+    /// Keys the organization's audit JWTs are verified against.
+    pub keys: BoundedVec<AuthorityKey<KeyIdLength>, MaxKeys>,
+    /// Website of the organization.
+    pub website: Option<BoundedString<WebsiteLength>>,
+    /// Legal Entity Identifier (ISO 17442) of the organization.
+    pub lei: Option<[u8; 20]>,
+    /// Whether the organization is currently allowed to add audits.
+    pub status: AuthorityStatus,
+    /// The roles granted to the organization.
+    pub roles: Roles,
+}
+
+impl<NameLength, KeyIdLength, MaxKeys, WebsiteLength>
+    AuthorityInfo<NameLength, KeyIdLength, MaxKeys, WebsiteLength>
+where
+    NameLength: Get<u32>,
+    KeyIdLength: Get<u32>,
+    MaxKeys: Get<u32>,
+    WebsiteLength: Get<u32>,
+{
+    /// Create the record of a newly authorized organization without any keys.
+    pub fn new(name: BoundedString<NameLength>, roles: Roles) -> Self {
+        Self {
+            name,
+            roles,
+            ..Default::default()
+        }
+    }
+
+    /// Find a key by its id.
+    pub fn key(&self, kid: &[u8]) -> Option<&AuthorityKey<KeyIdLength>> {
+        self.keys.iter().find(|k| &k.kid[..] == kid)
+    }
+
+    /// Iterate over the keys whose status is active, i.e. neither rotated nor revoked.
+    pub fn active_keys(&self) -> impl Iterator<Item = &AuthorityKey<KeyIdLength>> + '_ {
+        self.keys.iter().filter(|k| k.status == KeyStatus::Active)
+    }
+}
+
+/// A role an organization can take in the supply chain. Each extrinsic that adds audits
+/// requires the auditor to have a specific role.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum Role {
+    /// Creates components by producing them, e.g. harvesting.
+    Producer,
+    /// Audits components while they are moved.
+    Transporter,
+    /// Certifies existing components.
+    Certifier,
+    /// Creates components by assembling other components.
+    Assembler,
+    /// Registers components that were produced outside of the supply chain.
+    Registrar,
+}
+
+impl Role {
+    /// All roles.
+    pub const ALL: [Role; 5] = [
+        Role::Producer,
+        Role::Transporter,
+        Role::Certifier,
+        Role::Assembler,
+        Role::Registrar,
+    ];
+
+    fn flag(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+/// A set of roles.
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+pub struct Roles(u8);
+
+impl Roles {
+    /// A set containing every role.
+    pub fn all() -> Self {
+        Self::from(&Role::ALL[..])
+    }
+
+    /// Whether `role` is in the set.
+    pub fn contains(&self, role: Role) -> bool {
+        self.0 & role.flag() != 0
+    }
+
+    /// Whether any of `roles` is in the set.
+    pub fn contains_any(&self, roles: &[Role]) -> bool {
+        roles.iter().any(|role| self.contains(*role))
+    }
+
+    /// Add `role` to the set. Returns whether it was not in the set before.
+    pub fn insert(&mut self, role: Role) -> bool {
+        let added = !self.contains(role);
+        self.0 |= role.flag();
+        added
+    }
+
+    /// Remove `role` from the set. Returns whether it was in the set before.
+    pub fn remove(&mut self, role: Role) -> bool {
+        let removed = self.contains(role);
+        self.0 &= !role.flag();
+        removed
+    }
+
+    /// Iterate over the roles in the set.
+    pub fn iter(&self) -> impl Iterator<Item = Role> + '_ {
+        Role::ALL.iter().copied().filter(move |role| self.contains(*role))
+    }
+}
+
+impl From<&[Role]> for Roles {
+    fn from(roles: &[Role]) -> Self {
+        let mut set = Self::default();
+        for role in roles {
+            set.insert(*role);
+        }
+        set
+    }
+}
+
+/// Status of an authorized organization.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum AuthorityStatus {
+    /// The organization can add audits.
+    Active,
+    /// The organization is temporarily not allowed to add audits.
+    Suspended,
+}
+
+impl Default for AuthorityStatus {
+    fn default() -> Self {
+        AuthorityStatus::Active
+    }
+}
+
+/// A verification key of an organization, identified by the `kid` used in JWT headers.
+#[derive(
+    CloneNoBound,
+    EqNoBound,
+    PartialEqNoBound,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    RuntimeDebugNoBound,
+    TypeInfo,
+)]
+#[codec(mel_bound())]
+#[scale_info(skip_type_params(KeyIdLength))]
+pub struct AuthorityKey<KeyIdLength: Get<u32>> {
+    /// Key id, referenced by the `kid` header of audit JWTs.
+    pub kid: BoundedString<KeyIdLength>,
+    /// The public key.
+    pub key: VerificationKey,
+    /// Whether the key may still be used to verify new audits.
+    pub status: KeyStatus,
+}
+
+/// Status of a verification key.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub enum KeyStatus {
+    /// The key is used to verify new audits.
+    Active,
+    /// The key was replaced by the organization and is no longer used.
+    Rotated,
+    /// The key was revoked by the admin origin and is no longer used.
+    Revoked,
+}
+
+/// A public key that audit JWTs of an authority are verified against.
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    codec::Decode,
+    codec::Encode,
+    codec::MaxEncodedLen,
+    sp_runtime::RuntimeDebug,
+    TypeInfo,
+)]
+pub enum VerificationKey {
+    /// Ed25519 public key, used for JWTs with `"alg": "EdDSA"`.
+    Ed25519([u8; 32]),
+    /// Sr25519 public key, used for JWTs with `"alg": "Sr25519"`.
+    Sr25519([u8; 32]),
+    /// Compressed secp256k1 public key, used for JWTs with `"alg": "ES256K"`.
+    Secp256k1([u8; 33]),
 }
 
 // Define some default values for the pallet configuration.
 parameter_types! {
-    pub const MaxAuditorNameLength: u16 = 64;
-    pub const MaxAudits: u16 = 32;
-    pub const MaxAuditSize: u16 = 4096;
-    pub const MaxComponents: u16 = u16::MAX;
-    pub const MaxComponentIdLength: u16 = 256;
+    pub const MaxAuditorNameLength: u32 = 64;
+    pub const MaxAudits: u32 = 32;
+    pub const MaxAuditSize: u32 = 4096;
+    pub const MaxAuditDocuments: u32 = 8;
+    pub const MaxAuthorityKeys: u32 = 8;
+    pub const MaxCiphertextSize: u32 = 4096;
+    pub const MaxComponents: u32 = 2048;
+    pub const MaxComponentIdLength: u32 = 256;
+    pub const MaxDocumentRefLength: u32 = 128;
+    pub const MaxKeyIdLength: u32 = 64;
+    pub const MaxPayloadIdLength: u32 = 64;
+    pub const MaxRecipients: u32 = 16;
+    pub const MaxRecallPropagation: u32 = 64;
+    pub const MaxSchemaFields: u32 = 32;
+    pub const MaxWebsiteLength: u32 = 256;
+    // Wrapped content keys have a fixed length per key wrapping, so it is not configurable.
+    pub const MaxWrappedKeyLength: u32 = 128;
 }
@@ -0,0 +1,524 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! Weights for pallet_supply_chain
+//!
+//! NOT GENERATED BY THE BENCHMARK CLI. These weights are estimates of the worst case of each
+//! call: its decoding, encoding and JWT work timed natively and multiplied by 3 for Wasm
+//! execution, plus its signature checks, which run natively, plus 15 µs per call. Database
+//! accesses are priced by `DbWeight`. Replace them with the output of the benchmarks in
+//! `benchmarking.rs` on reference hardware before a release.
+
+// Command to generate the weights:
+// ./target/release/green-aureus
+// benchmark
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=pallet_supply_chain
+// --extrinsic=*
+// --execution=wasm
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./pallets/supply-chain/src/weights.rs
+// --template=./.maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_supply_chain.
+pub trait WeightInfo {
+	fn authorize(n: u32, ) -> Weight;
+	fn deauthorize() -> Weight;
+	fn grant_role() -> Weight;
+	fn revoke_role() -> Weight;
+	fn set_authority_status() -> Weight;
+	fn revoke_key() -> Weight;
+	fn update_authority() -> Weight;
+	fn add_key() -> Weight;
+	fn rotate_key() -> Weight;
+	fn audit(d: u32, ) -> Weight;
+	fn audit_assembly(c: u32, d: u32, ) -> Weight;
+	fn revoke_audit() -> Weight;
+	fn amend_audit(d: u32, ) -> Weight;
+	fn record_quantity() -> Weight;
+	fn split_component(l: u32, ) -> Weight;
+	fn merge_components(c: u32, d: u32, ) -> Weight;
+	fn transform_component(d: u32, ) -> Weight;
+	fn transfer_custody() -> Weight;
+	fn accept_custody() -> Weight;
+	fn destroy_component() -> Weight;
+	fn recall_component(c: u32, ) -> Weight;
+	fn propagate_recall(c: u32, ) -> Weight;
+	fn attach_document() -> Weight;
+	fn register_schema(f: u32, ) -> Weight;
+}
+
+/// Weights for pallet_supply_chain using the Green Aureus node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn authorize(n: u32, ) -> Weight {
+		(15_338_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn deauthorize() -> Weight {
+		(25_077_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn grant_role() -> Weight {
+		(26_790_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn revoke_role() -> Weight {
+		(26_691_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn set_authority_status() -> Weight {
+		(27_846_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn revoke_key() -> Weight {
+		(27_570_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn update_authority() -> Weight {
+		(28_254_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn add_key() -> Weight {
+		(26_187_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn rotate_key() -> Weight {
+		(27_996_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: SupplyChain ComponentCustody (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn audit(d: u32, ) -> Weight {
+		(133_927_000 as Weight)
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn audit_assembly(c: u32, d: u32, ) -> Weight {
+		(133_927_000 as Weight)
+			.saturating_add((2_765_371_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain Audits (r:1 w:1)
+	fn revoke_audit() -> Weight {
+		(18_624_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain Audits (r:1 w:2)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	fn amend_audit(d: u32, ) -> Weight {
+		(140_527_000 as Weight)
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: SupplyChain Audits (r:1 w:0)
+	// Storage: SupplyChain Components (r:1 w:1)
+	fn record_quantity() -> Weight {
+		(2_817_117_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:2 w:2)
+	// Storage: SupplyChain ComponentCustody (r:2 w:2)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn split_component(l: u32, ) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((2_909_202_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads((10 as Weight).saturating_mul(l as Weight)))
+			.saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(l as Weight)))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn merge_components(c: u32, d: u32, ) -> Weight {
+		(136_051_000 as Weight)
+			.saturating_add((2_765_986_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:2 w:2)
+	// Storage: SupplyChain ComponentCustody (r:2 w:2)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn transform_component(d: u32, ) -> Weight {
+		(2_854_570_000 as Weight)
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn transfer_custody() -> Weight {
+		(29_478_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn accept_custody() -> Weight {
+		(25_912_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn destroy_component() -> Weight {
+		(24_760_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: SupplyChain Recalls (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	// Storage: SupplyChain PendingRecalls (r:1 w:1)
+	fn recall_component(c: u32, ) -> Weight {
+		(23_547_000 as Weight)
+			.saturating_add((2_245_119_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain PendingRecalls (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: SupplyChain Recalls (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn propagate_recall(c: u32, ) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((2_245_119_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain Audits (r:1 w:0)
+	// Storage: SupplyChain AuditDocuments (r:1 w:1)
+	fn attach_document() -> Weight {
+		(58_927_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain SchemaVersions (r:1 w:1)
+	// Storage: SupplyChain Schemas (r:0 w:1)
+	fn register_schema(f: u32, ) -> Weight {
+		(24_196_000 as Weight)
+			.saturating_add((1_204_000 as Weight).saturating_mul(f as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn authorize(n: u32, ) -> Weight {
+		(15_338_000 as Weight)
+			.saturating_add((5_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn deauthorize() -> Weight {
+		(25_077_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn grant_role() -> Weight {
+		(26_790_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn revoke_role() -> Weight {
+		(26_691_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn set_authority_status() -> Weight {
+		(27_846_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn revoke_key() -> Weight {
+		(27_570_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn update_authority() -> Weight {
+		(28_254_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn add_key() -> Weight {
+		(26_187_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:1)
+	fn rotate_key() -> Weight {
+		(27_996_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: SupplyChain ComponentCustody (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn audit(d: u32, ) -> Weight {
+		(133_927_000 as Weight)
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn audit_assembly(c: u32, d: u32, ) -> Weight {
+		(133_927_000 as Weight)
+			.saturating_add((2_765_371_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain Audits (r:1 w:1)
+	fn revoke_audit() -> Weight {
+		(18_624_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain Audits (r:1 w:2)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	fn amend_audit(d: u32, ) -> Weight {
+		(140_527_000 as Weight)
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: SupplyChain Audits (r:1 w:0)
+	// Storage: SupplyChain Components (r:1 w:1)
+	fn record_quantity() -> Weight {
+		(2_817_117_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:2 w:2)
+	// Storage: SupplyChain ComponentCustody (r:2 w:2)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn split_component(l: u32, ) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((2_909_202_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(RocksDbWeight::get().reads((10 as Weight).saturating_mul(l as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(l as Weight)))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn merge_components(c: u32, d: u32, ) -> Weight {
+		(136_051_000 as Weight)
+			.saturating_add((2_765_986_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain AuditCount (r:1 w:1)
+	// Storage: SupplyChain Components (r:2 w:2)
+	// Storage: SupplyChain ComponentCustody (r:2 w:2)
+	// Storage: SupplyChain Recalls (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: SupplyChain SchemaVersions (r:1 w:0)
+	// Storage: SupplyChain Schemas (r:1 w:0)
+	// Storage: SupplyChain AuditsById (r:0 w:1)
+	// Storage: SupplyChain Audits (r:0 w:1)
+	fn transform_component(d: u32, ) -> Weight {
+		(2_854_570_000 as Weight)
+			.saturating_add((17_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn transfer_custody() -> Weight {
+		(29_478_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn accept_custody() -> Weight {
+		(25_912_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn destroy_component() -> Weight {
+		(24_760_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain Authorities (r:1 w:0)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: SupplyChain Recalls (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	// Storage: SupplyChain PendingRecalls (r:1 w:1)
+	fn recall_component(c: u32, ) -> Weight {
+		(23_547_000 as Weight)
+			.saturating_add((2_245_119_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain PendingRecalls (r:1 w:1)
+	// Storage: SupplyChain Components (r:1 w:0)
+	// Storage: SupplyChain Recalls (r:1 w:1)
+	// Storage: SupplyChain ComponentCustody (r:1 w:1)
+	fn propagate_recall(c: u32, ) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((2_245_119_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Storage: SupplyChain Audits (r:1 w:0)
+	// Storage: SupplyChain AuditDocuments (r:1 w:1)
+	fn attach_document() -> Weight {
+		(58_927_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: SupplyChain SchemaVersions (r:1 w:1)
+	// Storage: SupplyChain Schemas (r:0 w:1)
+	fn register_schema(f: u32, ) -> Weight {
+		(24_196_000 as Weight)
+			.saturating_add((1_204_000 as Weight).saturating_mul(f as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,59 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+//! This module lets an origin other than root upgrade the runtime. `frame_system` only accepts
+//! new runtime code from the root origin, which would require a sudo key. Instead, the
+//! configured `UpgradeOrigin`, e.g. a majority of the council, sets the code and the module
+//! dispatches `frame_system`'s `set_code` as root on its behalf.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::{pallet_prelude::*, RawOrigin};
+    use sp_std::vec::Vec;
+
+    /// Configure the pallet by specifying the parameters and types on which it depends.
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The origin that may upgrade the runtime.
+        type UpgradeOrigin: EnsureOrigin<Self::Origin>;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(super) trait Store)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// The upgrade origin can replace the runtime code. The new code must be a runtime
+        /// with the same name and a higher spec version.
+        ///
+        /// # Parameters
+        ///
+        /// * `code`: The Wasm blob of the new runtime.
+        ///
+        /// `frame_system` weighs `set_code` as a full block, which a council proposal can't
+        /// dispatch, because closing the proposal adds its own weight. Checking the new code
+        /// is bounded by half a block instead.
+        #[pallet::weight((T::BlockWeights::get().max_block / 2, DispatchClass::Operational))]
+        pub fn set_code(origin: OriginFor<T>, code: Vec<u8>) -> DispatchResultWithPostInfo {
+            T::UpgradeOrigin::ensure_origin(origin)?;
+            <frame_system::Pallet<T>>::set_code(RawOrigin::Root.into(), code)
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+use crate as pallet_upgrade;
+use codec::Encode;
+use frame_support::{ord_parameter_types, parameter_types};
+use frame_system as system;
+use sp_core::{
+    traits::{Externalities, ReadRuntimeVersion, ReadRuntimeVersionExt},
+    H256,
+};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+use sp_version::RuntimeVersion;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Upgrade: pallet_upgrade::{Pallet, Call},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+    pub const Version: RuntimeVersion = RuntimeVersion {
+        spec_name: sp_version::create_runtime_str!("test"),
+        impl_name: sp_version::create_runtime_str!("test"),
+        authoring_version: 1,
+        spec_version: 1,
+        impl_version: 1,
+        apis: sp_version::create_apis_vec!([]),
+        transaction_version: 1,
+    };
+}
+
+impl system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = Version;
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+}
+
+ord_parameter_types! {
+    pub const Council: u64 = 1;
+}
+
+impl pallet_upgrade::Config for Test {
+    type UpgradeOrigin = frame_system::EnsureSignedBy<Council, u64>;
+}
+
+/// Reads the version of a runtime from the first byte of its code, which is its spec version.
+struct ReadVersion;
+
+impl ReadRuntimeVersion for ReadVersion {
+    fn read_runtime_version(
+        &self,
+        wasm_code: &[u8],
+        _ext: &mut dyn Externalities,
+    ) -> Result<Vec<u8>, String> {
+        let spec_version = *wasm_code.first().ok_or("empty code")? as u32;
+        Ok(RuntimeVersion { spec_version, ..Version::get() }.encode())
+    }
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.register_extension(ReadRuntimeVersionExt::new(ReadVersion));
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,39 @@
+// Copyright 2021-2022 Green Aureus GmbH
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy 
+// of this software and associated documentation files (the "Software"), to read 
+// the Software only. Permission is hereby NOT GRANTED to use, copy, modify, 
+// merge, publish, distribute, sublicense, and/or sell copies of the Software.
+
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use sp_core::storage::well_known_keys;
+use sp_runtime::DispatchError;
+
+#[test]
+fn upgrade_origin_can_set_code() {
+    new_test_ext().execute_with(|| {
+        let code = vec![2, 0, 0, 0];
+        assert_ok!(Upgrade::set_code(Origin::signed(1), code.clone()));
+        assert_eq!(sp_io::storage::get(well_known_keys::CODE), Some(code));
+        System::assert_last_event(frame_system::Event::CodeUpdated.into());
+    });
+}
+
+#[test]
+fn set_code_requires_upgrade_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Upgrade::set_code(Origin::signed(2), vec![2]), DispatchError::BadOrigin);
+        assert_noop!(Upgrade::set_code(Origin::root(), vec![2]), DispatchError::BadOrigin);
+    });
+}
+
+#[test]
+fn set_code_requires_newer_runtime() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Upgrade::set_code(Origin::signed(1), vec![1]),
+            frame_system::Error::<Test>::SpecVersionNeedsToIncrease
+        );
+    });
+}
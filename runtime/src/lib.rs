@@ -16,12 +16,22 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use pallet_grandpa::{
     fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
-use pallet_supply_chain::types::{
-    MaxAuditSize, MaxAuditorNameLength, MaxAudits, MaxComponentIdLength, MaxComponents,
+use pallet_supply_chain::{
+    types::{
+        Direction, DocumentRef, MaxAuditDocuments, MaxAuditSize, MaxAuditorNameLength, MaxAudits,
+        MaxAuthorityKeys, MaxCiphertextSize, MaxComponentIdLength, MaxComponents,
+        MaxDocumentRefLength, MaxKeyIdLength, MaxPayloadIdLength, MaxRecallPropagation,
+        MaxRecipients, MaxSchemaFields, MaxWebsiteLength, ProvenanceNode,
+    },
+    AuditOf, AuditPayloadOf, AuthorityInfoOf, ComponentIdOf, ComponentOf, ConfidentialDataOf,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{
+    crypto::KeyTypeId,
+    u32_trait::{_1, _2, _3},
+    OpaqueMetadata,
+};
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
@@ -36,7 +46,7 @@ use sp_version::RuntimeVersion;
 // A few exports that help ease life for downstream crates.
 pub use frame_support::{
     construct_runtime, parameter_types,
-    traits::{KeyOwnerProofSystem, Randomness, StorageInfo},
+    traits::{Contains, KeyOwnerProofSystem, Randomness, StorageInfo},
     weights::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
         IdentityFee, Weight,
@@ -44,6 +54,7 @@ pub use frame_support::{
     StorageValue,
 };
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_supply_chain::types::Role;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::CurrencyAdapter;
 #[cfg(any(feature = "std", test))]
@@ -103,10 +114,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("green-aureus"),
     impl_name: create_runtime_str!("green-aureus"),
     authoring_version: 1,
-    spec_version: 2,
+    spec_version: 4,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 2,
+    transaction_version: 5,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -148,11 +159,24 @@ parameter_types! {
     pub const SS58Prefix: u8 = 42;
 }
 
+/// Calls that may not be dispatched by signed extrinsics.
+///
+/// The council is the only way to manage the supply chain authorities and to upgrade the
+/// runtime. Its members can only be changed through `CouncilMembership`, which the council
+/// governs itself.
+pub struct BaseFilter;
+
+impl Contains<Call> for BaseFilter {
+    fn contains(call: &Call) -> bool {
+        !matches!(call, Call::Council(pallet_collective::Call::set_members { .. }))
+    }
+}
+
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Config for Runtime {
     /// The basic call filter to use in dispatchable.
-    type BaseCallFilter = frame_support::traits::Everything;
+    type BaseCallFilter = BaseFilter;
     /// Block & extrinsics weights: base values and limits.
     type BlockWeights = BlockWeights;
     /// The maximum length of a block (in bytes).
@@ -277,19 +301,79 @@ impl pallet_transaction_payment::Config for Runtime {
     type FeeMultiplierUpdate = ();
 }
 
-impl pallet_sudo::Config for Runtime {
+parameter_types! {
+    /// Proposals of the council expire if they are not closed within this period.
+    pub const CouncilMotionDuration: BlockNumber = 7 * DAYS;
+    pub const CouncilMaxProposals: u32 = 100;
+    pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
     type Event = Event;
-    type Call = Call;
+    type MotionDuration = CouncilMotionDuration;
+    type MaxProposals = CouncilMaxProposals;
+    type MaxMembers = CouncilMaxMembers;
+    type DefaultVote = pallet_collective::PrimeDefaultVote;
+    type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// More than half of the council has to approve.
+pub type EnsureCouncilMajority =
+    pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+
+/// At least two thirds of the council have to approve.
+pub type EnsureCouncilSupermajority =
+    pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+    type Event = Event;
+    type AddOrigin = EnsureCouncilSupermajority;
+    type RemoveOrigin = EnsureCouncilSupermajority;
+    type SwapOrigin = EnsureCouncilSupermajority;
+    type ResetOrigin = EnsureCouncilSupermajority;
+    type PrimeOrigin = EnsureCouncilSupermajority;
+    type MembershipInitialized = Council;
+    type MembershipChanged = Council;
+    type MaxMembers = CouncilMaxMembers;
+    type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_upgrade::Config for Runtime {
+    type UpgradeOrigin = EnsureCouncilMajority;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+    Call: From<C>,
+{
+    type Extrinsic = UncheckedExtrinsic;
+    type OverarchingCall = Call;
 }
 
 impl pallet_supply_chain::Config for Runtime {
     type Event = Event;
+    type AdminOrigin = EnsureCouncilMajority;
     type MaxAuditorNameLength = MaxAuditorNameLength;
     type MaxAudits = MaxAudits;
     type MaxAuditSize = MaxAuditSize;
+    type MaxAuthorityKeys = MaxAuthorityKeys;
     type MaxComponents = MaxComponents;
     type MaxComponentIdLength = MaxComponentIdLength;
+    type MaxKeyIdLength = MaxKeyIdLength;
+    type MaxPayloadIdLength = MaxPayloadIdLength;
+    type MaxRecallPropagation = MaxRecallPropagation;
+    type MaxSchemaFields = MaxSchemaFields;
+    type MaxAuditDocuments = MaxAuditDocuments;
+    type MaxDocumentRefLength = MaxDocumentRefLength;
+    type MaxRecipients = MaxRecipients;
+    type MaxCiphertextSize = MaxCiphertextSize;
+    type MaxWebsiteLength = MaxWebsiteLength;
     type Timestamp = Timestamp;
+    type WeightInfo = pallet_supply_chain::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -305,7 +389,11 @@ construct_runtime!(
         Timestamp: pallet_timestamp = 3,
         Aura: pallet_aura = 4,
         Grandpa: pallet_grandpa = 5,
-        Sudo: pallet_sudo = 6,
+
+        // Governance
+        Council: pallet_collective::<Instance1> = 10,
+        CouncilMembership: pallet_membership::<Instance1> = 11,
+        Upgrade: pallet_upgrade = 12,
 
         // Transactions
         Balances: pallet_balances = 30,
@@ -341,8 +429,20 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPallets,
+    RemoveSudoKey,
 >;
 
+/// Removes the key of the sudo pallet, which was replaced by runtime upgrades approved by the
+/// council.
+pub struct RemoveSudoKey;
+
+impl frame_support::traits::OnRuntimeUpgrade for RemoveSudoKey {
+    fn on_runtime_upgrade() -> Weight {
+        frame_support::storage::migration::remove_storage_prefix(b"Sudo", b"Key", &[]);
+        RocksDbWeight::get().writes(1)
+    }
+}
+
 impl_runtime_apis! {
     impl sp_api::Core<Block> for Runtime {
         fn version() -> RuntimeVersion {
@@ -474,6 +574,91 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_supply_chain_runtime_api::SupplyChainApi<
+        Block,
+        AccountId,
+        Hash,
+        Moment,
+        ComponentIdOf<Runtime>,
+        ComponentOf<Runtime>,
+        AuditOf<Runtime>,
+        AuditPayloadOf<Runtime>,
+        ConfidentialDataOf<Runtime>,
+        AuthorityInfoOf<Runtime>,
+    > for Runtime {
+        fn audit_by_id(
+            id: Hash,
+        ) -> Option<(ComponentIdOf<Runtime>, u32, AuditOf<Runtime>)> {
+            SupplyChain::audit_by_id(&id)
+        }
+
+        fn component(id: ComponentIdOf<Runtime>) -> Option<ComponentOf<Runtime>> {
+            SupplyChain::component(&id)
+        }
+
+        fn audits(id: ComponentIdOf<Runtime>, offset: u32, limit: u32) -> Vec<AuditOf<Runtime>> {
+            SupplyChain::audits_page(&id, offset, limit)
+        }
+
+        fn authority(account: AccountId) -> Option<AuthorityInfoOf<Runtime>> {
+            SupplyChain::authorities(&account)
+        }
+
+        fn parts_of(id: ComponentIdOf<Runtime>) -> Vec<ComponentIdOf<Runtime>> {
+            SupplyChain::parts_of(&id)
+        }
+
+        fn used_in(id: ComponentIdOf<Runtime>) -> Vec<ComponentIdOf<Runtime>> {
+            SupplyChain::used_in(&id)
+        }
+
+        fn exists(id: ComponentIdOf<Runtime>) -> bool {
+            SupplyChain::exists(&id)
+        }
+
+        fn provenance(
+            id: ComponentIdOf<Runtime>,
+            direction: Direction,
+            max_depth: u32,
+            offset: u32,
+            limit: u32,
+            audit_limit: u32,
+        ) -> Vec<ProvenanceNode<ComponentIdOf<Runtime>, AuditOf<Runtime>>> {
+            SupplyChain::provenance(&id, direction, max_depth, offset, limit, audit_limit)
+        }
+
+        fn audit_payload(
+            id: ComponentIdOf<Runtime>,
+            index: u32,
+        ) -> Option<AuditPayloadOf<Runtime>> {
+            SupplyChain::audit_payload(&id, index)
+        }
+
+        fn certified_components(
+            standard: Vec<u8>,
+            at: Moment,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ComponentIdOf<Runtime>, u32)> {
+            // No component is certified under a standard whose id can't be stored.
+            match standard.try_into() {
+                Ok(standard) => SupplyChain::certified_components(&standard, at, offset, limit),
+                Err(_) => Vec::new(),
+            }
+        }
+
+        fn audit_documents(id: ComponentIdOf<Runtime>, index: u32) -> Vec<DocumentRef<Vec<u8>>> {
+            SupplyChain::documents(&id, index)
+        }
+
+        fn confidential_data(
+            id: ComponentIdOf<Runtime>,
+            index: u32,
+        ) -> Option<ConfidentialDataOf<Runtime>> {
+            SupplyChain::confidential_data(&id, index)
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn benchmark_metadata(extra: bool) -> (
@@ -490,7 +675,8 @@ impl_runtime_apis! {
             list_benchmark!(list, extra, frame_benchmarking, BaselineBench::<Runtime>);
             list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
             list_benchmark!(list, extra, pallet_balances, Balances);
-            list_benchmark!(list, extra, pallet_supply_chain, Supplychain);
+            list_benchmark!(list, extra, pallet_collective, Council);
+            list_benchmark!(list, extra, pallet_supply_chain, SupplyChain);
             list_benchmark!(list, extra, pallet_timestamp, Timestamp);
 
             let storage_info = AllPalletsWithSystem::storage_info();
@@ -528,7 +714,8 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, frame_benchmarking, BaselineBench::<Runtime>);
             add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
             add_benchmark!(params, batches, pallet_balances, Balances);
-            add_benchmark!(params, batches, pallet_supply_chain, Supplychain);
+            add_benchmark!(params, batches, pallet_collective, Council);
+            add_benchmark!(params, batches, pallet_supply_chain, SupplyChain);
             add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 
             Ok(batches)